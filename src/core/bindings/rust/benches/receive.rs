@@ -0,0 +1,53 @@
+//! Receive path benchmarks
+//!
+//! Compares `receive()`, which allocates a buffer per message, with the
+//! allocation-free `receive_into()` and `receive_pooled()` over an
+//! in-memory connection pair.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use corebase_bindings::buffer_pool::BufferPool;
+use corebase_bindings::network::{NetworkConfig, NetworkConnection, NetworkManager, NetworkMessage};
+
+const PAYLOAD_SIZES: [usize; 3] = [64, 1024, 16 * 1024];
+
+fn pair(manager: &NetworkManager) -> (NetworkConnection, NetworkConnection) {
+    manager.create_pair(NetworkConfig::in_memory()).expect("in-memory pair")
+}
+
+fn bench_receive(c: &mut Criterion) {
+    let manager = NetworkManager::new().expect("network manager");
+    let (client, server) = pair(&manager);
+    let pool = BufferPool::default();
+    let mut buffer = vec![0u8; 64 * 1024];
+    
+    let mut group = c.benchmark_group("receive");
+    for size in PAYLOAD_SIZES {
+        // Identity encoding keeps compression out of the measurement
+        let message = NetworkMessage::new_binary(vec![0x5a; size]).with_header("content-encoding", "identity");
+        group.throughput(Throughput::Bytes(size as u64));
+        
+        group.bench_with_input(BenchmarkId::new("receive", size), &message, |b, message| {
+            b.iter(|| {
+                client.send(message).unwrap();
+                server.receive().unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("receive_into", size), &message, |b, message| {
+            b.iter(|| {
+                client.send(message).unwrap();
+                server.receive_into(&mut buffer).unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("receive_pooled", size), &message, |b, message| {
+            b.iter(|| {
+                client.send(message).unwrap();
+                server.receive_pooled(&pool).unwrap().len()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_receive);
+criterion_main!(benches);
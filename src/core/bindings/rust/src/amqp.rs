@@ -0,0 +1,904 @@
+//! AMQP client module for CoreBase Rust bindings
+//!
+//! This module provides queue-based messaging on top of the C++
+//! NetworkManager's AMQP protocol support. Queues, bindings, the prefetch
+//! limit and consumers are recorded so they can be restored when the
+//! connection is recovered after a failure.
+
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{to_c_string, CbaString};
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{NetworkConfig, NetworkMessage, NetworkProtocol};
+use crate::util::native_len;
+
+/// Initial payload buffer size for received messages
+const INITIAL_PAYLOAD_BUFFER: usize = 64 * 1024;
+/// Buffer size for queue names, consumer tags and routing keys
+const NAME_BUFFER_SIZE: usize = 256;
+/// How long the dispatcher waits for a delivery before checking for shutdown
+const POLL_TIMEOUT_MS: c_int = 100;
+
+/// How deliveries are acknowledged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AckMode {
+    /// The broker considers messages delivered as soon as they are sent
+    Auto,
+    /// Each delivery must be settled with `ack()` or `nack()`
+    #[default]
+    Manual,
+}
+
+/// AMQP connection options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmqpOptions {
+    pub vhost: String,
+    pub heartbeat: Duration,
+    /// Reconnect and restore the topology after the connection is lost
+    pub automatic_recovery: bool,
+    /// Delay between recovery attempts
+    pub recovery_interval: Duration,
+}
+
+impl Default for AmqpOptions {
+    fn default() -> Self {
+        AmqpOptions {
+            vhost: "/".to_string(),
+            heartbeat: Duration::from_secs(60),
+            automatic_recovery: true,
+            recovery_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl AmqpOptions {
+    /// Set virtual host
+    pub fn with_vhost(mut self, vhost: &str) -> Self {
+        self.vhost = vhost.to_string();
+        self
+    }
+    
+    /// Set heartbeat interval negotiated with the broker
+    pub fn with_heartbeat(mut self, heartbeat: Duration) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+    
+    /// Enable or disable automatic recovery
+    pub fn with_automatic_recovery(mut self, enabled: bool) -> Self {
+        self.automatic_recovery = enabled;
+        self
+    }
+    
+    /// Set delay between recovery attempts
+    pub fn with_recovery_interval(mut self, interval: Duration) -> Self {
+        self.recovery_interval = interval;
+        self
+    }
+}
+
+/// Queue declaration flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QueueOptions {
+    pub durable: bool,
+    pub exclusive: bool,
+    pub auto_delete: bool,
+}
+
+impl QueueOptions {
+    /// Keep the queue across broker restarts
+    pub fn durable(mut self) -> Self {
+        self.durable = true;
+        self
+    }
+    
+    /// Restrict the queue to this connection
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+    
+    /// Delete the queue when its last consumer goes away
+    pub fn auto_delete(mut self) -> Self {
+        self.auto_delete = true;
+        self
+    }
+}
+
+/// Message delivered to a consumer
+///
+/// The message topic is the routing key it was published with. With
+/// `AckMode::Manual` the delivery must be settled with `ack()` or `nack()`;
+/// unsettled deliveries count against the prefetch limit.
+#[derive(Debug)]
+pub struct AmqpDelivery {
+    pub message: NetworkMessage,
+    pub delivery_tag: u64,
+    pub redelivered: bool,
+    ack_mode: AckMode,
+    generation: u64,
+    shared: Weak<Shared>,
+}
+
+impl AmqpDelivery {
+    /// Acknowledge the delivery
+    pub fn ack(self) -> CoreBaseResult<()> {
+        let result = self.settle(|connection_id| unsafe {
+            crate::cba_amqp_ack(connection_id, self.delivery_tag, 0)
+        })?;
+        check(result, || format!("Failed to acknowledge delivery {}", self.delivery_tag))
+    }
+    
+    /// Reject the delivery, optionally returning it to the queue
+    pub fn nack(self, requeue: bool) -> CoreBaseResult<()> {
+        let result = self.settle(|connection_id| unsafe {
+            crate::cba_amqp_nack(connection_id, self.delivery_tag, 0, requeue as c_int)
+        })?;
+        check(result, || format!("Failed to reject delivery {}", self.delivery_tag))
+    }
+    
+    fn settle(&self, f: impl FnOnce(*const c_char) -> c_int) -> CoreBaseResult<c_int> {
+        if self.ack_mode == AckMode::Auto {
+            return Err(CoreBaseError::InvalidParameter(
+                "Delivery was acknowledged automatically".to_string(),
+                None,
+            ));
+        }
+        
+        let shared = self.shared.upgrade().ok_or_else(|| {
+            CoreBaseError::OperationFailed("AMQP client is closed".to_string(), None)
+        })?;
+        
+        // Delivery tags are only valid on the connection that delivered them
+        if shared.generation.load(Ordering::SeqCst) != self.generation {
+            return Err(CoreBaseError::OperationFailed(
+                "Delivery belongs to a lost connection; the broker will redeliver it".to_string(),
+                None,
+            ));
+        }
+        
+        let c_connection_id = to_c_string(&shared.connection_id())?;
+        Ok(f(c_connection_id.as_ptr()))
+    }
+}
+
+/// Receiver for deliveries from one queue
+///
+/// The consumer is cancelled when it is dropped.
+#[derive(Debug)]
+pub struct AmqpConsumer {
+    id: u64,
+    queue: String,
+    receiver: Receiver<AmqpDelivery>,
+    shared: Weak<Shared>,
+}
+
+impl AmqpConsumer {
+    /// Get the queue this consumer reads from
+    pub fn queue(&self) -> &str {
+        &self.queue
+    }
+    
+    /// Block until the next delivery arrives
+    pub fn recv(&self) -> CoreBaseResult<AmqpDelivery> {
+        self.receiver.recv().map_err(|_| CoreBaseError::NetworkError(
+            "AMQP consumer closed".to_string(),
+            None,
+        ))
+    }
+    
+    /// Wait up to `timeout` for the next delivery
+    pub fn recv_timeout(&self, timeout: Duration) -> CoreBaseResult<AmqpDelivery> {
+        self.receiver.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => CoreBaseError::Timeout(
+                format!("No delivery from '{}' within {:?}", self.queue, timeout),
+                None,
+            ),
+            RecvTimeoutError::Disconnected => CoreBaseError::NetworkError(
+                "AMQP consumer closed".to_string(),
+                None,
+            ),
+        })
+    }
+    
+    /// Get the next delivery if one is already waiting
+    pub fn try_recv(&self) -> Option<AmqpDelivery> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for AmqpConsumer {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.remove_consumer(self.id);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QueueDeclaration {
+    /// Name passed to `declare_queue`, empty for server-named queues
+    requested: String,
+    /// Name assigned by the broker
+    name: String,
+    options: QueueOptions,
+}
+
+#[derive(Debug)]
+struct Binding {
+    queue: String,
+    exchange: String,
+    routing_key: String,
+}
+
+/// Everything restored on recovery, in declaration order
+#[derive(Debug, Default)]
+struct Topology {
+    queues: Vec<QueueDeclaration>,
+    bindings: Vec<Binding>,
+    prefetch: Option<u16>,
+}
+
+#[derive(Debug)]
+struct Consumer {
+    id: u64,
+    queue: String,
+    ack_mode: AckMode,
+    tag: String,
+    sender: Sender<AmqpDelivery>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    config: NetworkConfig,
+    options: AmqpOptions,
+    connection_id: Mutex<String>,
+    /// Incremented on every recovery
+    generation: AtomicU64,
+    /// Lock order: `topology` before `consumers`
+    topology: Mutex<Topology>,
+    consumers: Mutex<Vec<Consumer>>,
+    next_consumer_id: AtomicU64,
+    running: AtomicBool,
+    connected: AtomicBool,
+}
+
+impl Shared {
+    fn connection_id(&self) -> String {
+        self.connection_id.lock().map(|id| id.clone()).unwrap_or_default()
+    }
+    
+    fn topology(&self) -> CoreBaseResult<MutexGuard<'_, Topology>> {
+        self.topology.lock().map_err(|_| lock_error())
+    }
+    
+    fn consumers(&self) -> CoreBaseResult<MutexGuard<'_, Vec<Consumer>>> {
+        self.consumers.lock().map_err(|_| lock_error())
+    }
+    
+    fn remove_consumer(&self, id: u64) {
+        let removed = match self.consumers.lock() {
+            Ok(mut consumers) => consumers
+                .iter()
+                .position(|consumer| consumer.id == id)
+                .map(|index| consumers.remove(index)),
+            Err(_) => None,
+        };
+        if let Some(consumer) = removed {
+            let _ = cancel(&self.connection_id(), &consumer.tag);
+        }
+    }
+}
+
+/// AMQP client wrapping an AMQP connection of the C++ NetworkManager
+///
+/// Deliveries are dispatched on a background thread to the consumer they
+/// were sent to. When the connection is lost it is re-established every
+/// `recovery_interval`, and the recorded queues, bindings, prefetch limit
+/// and consumers are restored.
+#[derive(Debug)]
+pub struct AmqpClient {
+    shared: Arc<Shared>,
+    dispatcher: Option<JoinHandle<()>>,
+}
+
+impl AmqpClient {
+    /// Connect to an AMQP broker
+    pub fn connect(config: &NetworkConfig, options: AmqpOptions) -> CoreBaseResult<Self> {
+        if config.protocol != NetworkProtocol::AMQP {
+            return Err(CoreBaseError::InvalidParameter(
+                format!("AmqpClient requires the AMQP protocol, got {:?}", config.protocol),
+                None,
+            ));
+        }
+        
+        let connection_id = open(config, &options)?;
+        let shared = Arc::new(Shared {
+            config: config.clone(),
+            options,
+            connection_id: Mutex::new(connection_id),
+            generation: AtomicU64::new(0),
+            topology: Mutex::new(Topology::default()),
+            consumers: Mutex::new(Vec::new()),
+            next_consumer_id: AtomicU64::new(1),
+            running: AtomicBool::new(true),
+            connected: AtomicBool::new(true),
+        });
+        
+        let dispatcher_shared = Arc::clone(&shared);
+        let dispatcher = std::thread::Builder::new()
+            .name("cba-amqp-dispatch".to_string())
+            .spawn(move || dispatch_loop(&dispatcher_shared))
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to start AMQP dispatcher: {}", e), None))?;
+        
+        Ok(AmqpClient {
+            shared,
+            dispatcher: Some(dispatcher),
+        })
+    }
+    
+    /// Get the ID of the current underlying connection
+    ///
+    /// The ID changes when the connection is recovered.
+    pub fn connection_id(&self) -> String {
+        self.shared.connection_id()
+    }
+    
+    /// Check whether the connection is up, false while recovering
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::SeqCst)
+    }
+    
+    /// Declare a queue, returning its name
+    ///
+    /// An empty name asks the broker to generate one. Server-named queues
+    /// get a new name on recovery; bindings and consumers follow it.
+    pub fn declare_queue(&self, name: &str, options: QueueOptions) -> CoreBaseResult<String> {
+        let mut topology = self.shared.topology()?;
+        let declared = declare_queue(&self.shared.connection_id(), name, options)?;
+        
+        topology.queues.retain(|queue| queue.name != declared);
+        topology.queues.push(QueueDeclaration {
+            requested: name.to_string(),
+            name: declared.clone(),
+            options,
+        });
+        Ok(declared)
+    }
+    
+    /// Bind a queue to an exchange with a routing key
+    pub fn bind_queue(&self, queue: &str, exchange: &str, routing_key: &str) -> CoreBaseResult<()> {
+        let mut topology = self.shared.topology()?;
+        bind_queue(&self.shared.connection_id(), queue, exchange, routing_key)?;
+        
+        topology.bindings.push(Binding {
+            queue: queue.to_string(),
+            exchange: exchange.to_string(),
+            routing_key: routing_key.to_string(),
+        });
+        Ok(())
+    }
+    
+    /// Limit the number of unacknowledged deliveries per consumer
+    pub fn set_prefetch(&self, count: u16) -> CoreBaseResult<()> {
+        let mut topology = self.shared.topology()?;
+        set_prefetch(&self.shared.connection_id(), count)?;
+        topology.prefetch = Some(count);
+        Ok(())
+    }
+    
+    /// Publish a message to an exchange (`""` is the default exchange)
+    pub fn publish(&self, exchange: &str, routing_key: &str, payload: &[u8]) -> CoreBaseResult<()> {
+        self.publish_message(exchange, routing_key, payload, false)
+    }
+    
+    /// Publish a message the broker writes to disk in durable queues
+    pub fn publish_persistent(&self, exchange: &str, routing_key: &str, payload: &[u8]) -> CoreBaseResult<()> {
+        self.publish_message(exchange, routing_key, payload, true)
+    }
+    
+    fn publish_message(&self, exchange: &str, routing_key: &str, payload: &[u8], persistent: bool) -> CoreBaseResult<()> {
+        let payload_len = c_int::try_from(payload.len())
+            .map_err(|_| CoreBaseError::InvalidParameter("Message too large".to_string(), None))?;
+        let c_connection_id = to_c_string(&self.shared.connection_id())?;
+        let c_exchange = to_c_string(exchange)?;
+        let c_routing_key = to_c_string(routing_key)?;
+        
+        unsafe {
+            let result = crate::cba_amqp_publish(
+                c_connection_id.as_ptr(),
+                c_exchange.as_ptr(),
+                c_routing_key.as_ptr(),
+                payload.as_ptr(),
+                payload_len,
+                persistent as c_int,
+            );
+            check(result, || format!("Failed to publish to '{}' with key '{}'", exchange, routing_key))
+        }
+    }
+    
+    /// Start consuming from a queue
+    pub fn consume(&self, queue: &str, ack_mode: AckMode) -> CoreBaseResult<AmqpConsumer> {
+        let (sender, receiver) = mpsc::channel();
+        let id = self.shared.next_consumer_id.fetch_add(1, Ordering::Relaxed);
+        
+        // Register under the consumers lock so no delivery for the tag is missed
+        let mut consumers = self.shared.consumers()?;
+        let tag = consume(&self.shared.connection_id(), queue, ack_mode)?;
+        consumers.push(Consumer {
+            id,
+            queue: queue.to_string(),
+            ack_mode,
+            tag,
+            sender,
+        });
+        
+        Ok(AmqpConsumer {
+            id,
+            queue: queue.to_string(),
+            receiver,
+            shared: Arc::downgrade(&self.shared),
+        })
+    }
+    
+    /// Close the connection
+    ///
+    /// Unacknowledged deliveries are returned to their queues by the broker.
+    pub fn close(mut self) -> CoreBaseResult<()> {
+        self.shutdown()
+    }
+    
+    fn shutdown(&mut self) -> CoreBaseResult<()> {
+        let Some(dispatcher) = self.dispatcher.take() else {
+            return Ok(());
+        };
+        
+        self.shared.running.store(false, Ordering::SeqCst);
+        let _ = dispatcher.join();
+        self.shared.connected.store(false, Ordering::SeqCst);
+        
+        // Dropping the senders ends every consumer
+        if let Ok(mut consumers) = self.shared.consumers.lock() {
+            consumers.clear();
+        }
+        close(&self.shared.connection_id())
+    }
+}
+
+impl Drop for AmqpClient {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Receive deliveries from the broker and route them to consumers
+fn dispatch_loop(shared: &Arc<Shared>) {
+    let mut payload_buffer = vec![0u8; INITIAL_PAYLOAD_BUFFER];
+    let mut tag_buffer = vec![0u8; NAME_BUFFER_SIZE];
+    let mut routing_key_buffer = vec![0u8; NAME_BUFFER_SIZE];
+    
+    while shared.running.load(Ordering::SeqCst) {
+        let Ok(c_connection_id) = to_c_string(&shared.connection_id()) else {
+            return;
+        };
+        let mut payload_len: c_int = 0;
+        let mut delivery_tag: u64 = 0;
+        let mut redelivered: c_int = 0;
+        
+        let result = unsafe {
+            crate::cba_amqp_poll_delivery(
+                c_connection_id.as_ptr(),
+                tag_buffer.as_mut_ptr() as *mut c_char,
+                tag_buffer.len() as c_int,
+                routing_key_buffer.as_mut_ptr() as *mut c_char,
+                routing_key_buffer.len() as c_int,
+                payload_buffer.as_mut_ptr(),
+                payload_buffer.len() as c_int,
+                &mut payload_len,
+                &mut delivery_tag,
+                &mut redelivered,
+                POLL_TIMEOUT_MS,
+            )
+        };
+        
+        // A negative length means the C++ side is in a bad state; it is
+        // handled like a failed poll
+        match (result, native_len(payload_len)) {
+            (0, Ok(payload_len)) => {
+                let payload_len = payload_len.min(payload_buffer.len());
+                let routing_key = buffer_string(&routing_key_buffer);
+                let message = NetworkMessage::new_binary(payload_buffer[..payload_len].to_vec())
+                    .with_topic(&routing_key);
+                dispatch(shared, &buffer_string(&tag_buffer), message, delivery_tag, redelivered != 0);
+            }
+            // Payload larger than the buffer; the delivery stays queued
+            (2, Ok(payload_len)) if payload_len > payload_buffer.len() => {
+                payload_buffer.resize(payload_len, 0);
+            }
+            (1, _) => {}
+            _ => {
+                shared.connected.store(false, Ordering::SeqCst);
+                if !shared.options.automatic_recovery {
+                    // Dropping the senders ends every consumer
+                    if let Ok(mut consumers) = shared.consumers.lock() {
+                        consumers.clear();
+                    }
+                    return;
+                }
+                recover_until_connected(shared);
+            }
+        }
+    }
+}
+
+fn dispatch(shared: &Arc<Shared>, consumer_tag: &str, message: NetworkMessage, delivery_tag: u64, redelivered: bool) {
+    let Ok(mut consumers) = shared.consumers.lock() else {
+        return;
+    };
+    let connection_id = shared.connection_id();
+    
+    let Some(index) = consumers.iter().position(|consumer| consumer.tag == consumer_tag) else {
+        // Cancelled consumer; let another one have the message
+        let _ = reject(&connection_id, delivery_tag);
+        return;
+    };
+    
+    let consumer = &consumers[index];
+    let delivery = AmqpDelivery {
+        message,
+        delivery_tag,
+        redelivered,
+        ack_mode: consumer.ack_mode,
+        generation: shared.generation.load(Ordering::SeqCst),
+        shared: Arc::downgrade(shared),
+    };
+    
+    if consumer.sender.send(delivery).is_err() {
+        // The receiver went away without cancelling
+        let consumer = consumers.remove(index);
+        if consumer.ack_mode == AckMode::Manual {
+            let _ = reject(&connection_id, delivery_tag);
+        }
+        let _ = cancel(&connection_id, &consumer.tag);
+    }
+}
+
+/// Retry `recover` every `recovery_interval` until it succeeds or the client closes
+fn recover_until_connected(shared: &Shared) {
+    loop {
+        let retry_at = Instant::now() + shared.options.recovery_interval;
+        while Instant::now() < retry_at {
+            if !shared.running.load(Ordering::SeqCst) {
+                return;
+            }
+            std::thread::sleep(retry_at.saturating_duration_since(Instant::now()).min(Duration::from_millis(POLL_TIMEOUT_MS as u64)));
+        }
+        
+        if recover(shared).is_ok() {
+            return;
+        }
+    }
+}
+
+/// Open a new connection and restore the recorded topology on it
+fn recover(shared: &Shared) -> CoreBaseResult<()> {
+    let mut topology = shared.topology()?;
+    let mut consumers = shared.consumers()?;
+    let connection_id = open(&shared.config, &shared.options)?;
+    
+    if let Err(e) = restore(connection_id.as_str(), &mut topology, &mut consumers) {
+        let _ = close(&connection_id);
+        return Err(e);
+    }
+    
+    let previous = std::mem::replace(&mut *shared.connection_id.lock().map_err(|_| lock_error())?, connection_id);
+    let _ = close(&previous);
+    shared.generation.fetch_add(1, Ordering::SeqCst);
+    shared.connected.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Broker operations needed to restore a topology
+///
+/// Implemented for connection IDs of the C++ side.
+trait Channel {
+    fn set_prefetch(&self, count: u16) -> CoreBaseResult<()>;
+    fn declare_queue(&self, name: &str, options: QueueOptions) -> CoreBaseResult<String>;
+    fn bind_queue(&self, queue: &str, exchange: &str, routing_key: &str) -> CoreBaseResult<()>;
+    fn consume(&self, queue: &str, ack_mode: AckMode) -> CoreBaseResult<String>;
+}
+
+impl Channel for str {
+    fn set_prefetch(&self, count: u16) -> CoreBaseResult<()> {
+        set_prefetch(self, count)
+    }
+    
+    fn declare_queue(&self, name: &str, options: QueueOptions) -> CoreBaseResult<String> {
+        declare_queue(self, name, options)
+    }
+    
+    fn bind_queue(&self, queue: &str, exchange: &str, routing_key: &str) -> CoreBaseResult<()> {
+        bind_queue(self, queue, exchange, routing_key)
+    }
+    
+    fn consume(&self, queue: &str, ack_mode: AckMode) -> CoreBaseResult<String> {
+        consume(self, queue, ack_mode)
+    }
+}
+
+fn restore(channel: &(impl Channel + ?Sized), topology: &mut Topology, consumers: &mut [Consumer]) -> CoreBaseResult<()> {
+    if let Some(prefetch) = topology.prefetch {
+        channel.set_prefetch(prefetch)?;
+    }
+    
+    for queue in &mut topology.queues {
+        let name = channel.declare_queue(&queue.requested, queue.options)?;
+        if name != queue.name {
+            for binding in topology.bindings.iter_mut().filter(|binding| binding.queue == queue.name) {
+                binding.queue = name.clone();
+            }
+            for consumer in consumers.iter_mut().filter(|consumer| consumer.queue == queue.name) {
+                consumer.queue = name.clone();
+            }
+            queue.name = name;
+        }
+    }
+    
+    for binding in &topology.bindings {
+        channel.bind_queue(&binding.queue, &binding.exchange, &binding.routing_key)?;
+    }
+    
+    for consumer in consumers.iter_mut() {
+        consumer.tag = channel.consume(&consumer.queue, consumer.ack_mode)?;
+    }
+    Ok(())
+}
+
+fn open(config: &NetworkConfig, options: &AmqpOptions) -> CoreBaseResult<String> {
+    let c_host = to_c_string(&config.host)?;
+    let c_username = config.username.as_deref().map(to_c_string).transpose()?;
+    let c_password = config.password.as_deref().map(to_c_string).transpose()?;
+    let c_vhost = to_c_string(&options.vhost)?;
+    
+    unsafe {
+        let connection_id_ptr = crate::cba_amqp_connect(
+            c_host.as_ptr(),
+            config.port as c_int,
+            c_username.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            c_password.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            c_vhost.as_ptr(),
+            options.heartbeat.as_secs().min(u16::MAX as u64) as c_int,
+        );
+        
+        CbaString::from_raw(connection_id_ptr)
+            .ok_or_else(|| CoreBaseError::NetworkError(
+                format!("Failed to connect to AMQP broker {}:{}", config.host, config.port),
+                None,
+            ))?
+            .into_string()
+    }
+}
+
+fn declare_queue(connection_id: &str, name: &str, options: QueueOptions) -> CoreBaseResult<String> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let c_name = to_c_string(name)?;
+    let mut name_buffer = vec![0u8; NAME_BUFFER_SIZE];
+    
+    let result = unsafe {
+        crate::cba_amqp_declare_queue(
+            c_connection_id.as_ptr(),
+            c_name.as_ptr(),
+            options.durable as c_int,
+            options.exclusive as c_int,
+            options.auto_delete as c_int,
+            name_buffer.as_mut_ptr() as *mut c_char,
+            name_buffer.len() as c_int,
+        )
+    };
+    check(result, || format!("Failed to declare queue '{}'", name))?;
+    Ok(buffer_string(&name_buffer))
+}
+
+fn bind_queue(connection_id: &str, queue: &str, exchange: &str, routing_key: &str) -> CoreBaseResult<()> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let c_queue = to_c_string(queue)?;
+    let c_exchange = to_c_string(exchange)?;
+    let c_routing_key = to_c_string(routing_key)?;
+    
+    let result = unsafe {
+        crate::cba_amqp_bind_queue(c_connection_id.as_ptr(), c_queue.as_ptr(), c_exchange.as_ptr(), c_routing_key.as_ptr())
+    };
+    check(result, || format!("Failed to bind queue '{}' to exchange '{}'", queue, exchange))
+}
+
+fn set_prefetch(connection_id: &str, count: u16) -> CoreBaseResult<()> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let result = unsafe { crate::cba_amqp_set_prefetch(c_connection_id.as_ptr(), count as c_int) };
+    check(result, || "Failed to set prefetch count".to_string())
+}
+
+fn consume(connection_id: &str, queue: &str, ack_mode: AckMode) -> CoreBaseResult<String> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let c_queue = to_c_string(queue)?;
+    let mut tag_buffer = vec![0u8; NAME_BUFFER_SIZE];
+    
+    let result = unsafe {
+        crate::cba_amqp_consume(
+            c_connection_id.as_ptr(),
+            c_queue.as_ptr(),
+            (ack_mode == AckMode::Auto) as c_int,
+            tag_buffer.as_mut_ptr() as *mut c_char,
+            tag_buffer.len() as c_int,
+        )
+    };
+    check(result, || format!("Failed to consume from queue '{}'", queue))?;
+    Ok(buffer_string(&tag_buffer))
+}
+
+fn cancel(connection_id: &str, consumer_tag: &str) -> CoreBaseResult<()> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let c_tag = to_c_string(consumer_tag)?;
+    let result = unsafe { crate::cba_amqp_cancel(c_connection_id.as_ptr(), c_tag.as_ptr()) };
+    check(result, || format!("Failed to cancel consumer '{}'", consumer_tag))
+}
+
+/// Return a delivery to its queue
+fn reject(connection_id: &str, delivery_tag: u64) -> CoreBaseResult<()> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let result = unsafe { crate::cba_amqp_nack(c_connection_id.as_ptr(), delivery_tag, 0, 1) };
+    check(result, || format!("Failed to reject delivery {}", delivery_tag))
+}
+
+fn close(connection_id: &str) -> CoreBaseResult<()> {
+    let c_connection_id = to_c_string(connection_id)?;
+    let result = unsafe { crate::cba_amqp_close(c_connection_id.as_ptr()) };
+    check(result, || "Failed to close AMQP connection".to_string())
+}
+
+fn check(result: c_int, message: impl FnOnce() -> String) -> CoreBaseResult<()> {
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(CoreBaseError::NetworkError(message(), None).with_last_error())
+    }
+}
+
+fn buffer_string(buffer: &[u8]) -> String {
+    let len = buffer.iter().position(|&x| x == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into_owned()
+}
+
+fn lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access AMQP client state".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn amqp_config() -> NetworkConfig {
+        NetworkConfig {
+            protocol: NetworkProtocol::AMQP,
+            ..NetworkConfig::tcp("localhost", 5672)
+        }
+    }
+    
+    #[test]
+    fn test_options_builders() {
+        let options = AmqpOptions::default()
+            .with_vhost("/jobs")
+            .with_heartbeat(Duration::from_secs(30))
+            .with_recovery_interval(Duration::from_secs(1));
+        assert_eq!(options.vhost, "/jobs");
+        assert!(options.automatic_recovery);
+        assert!(!options.with_automatic_recovery(false).automatic_recovery);
+        
+        let queue = QueueOptions::default().durable().auto_delete();
+        assert!(queue.durable && queue.auto_delete && !queue.exclusive);
+        assert!(AmqpClient::connect(&NetworkConfig::tcp("localhost", 5672), AmqpOptions::default()).is_err());
+    }
+    
+    /// Broker that names every server-named queue "amq.gen-new" and
+    /// records the calls made on it
+    #[derive(Default)]
+    struct RecordingChannel(Mutex<Vec<String>>);
+    
+    impl Channel for RecordingChannel {
+        fn set_prefetch(&self, count: u16) -> CoreBaseResult<()> {
+            self.0.lock().unwrap().push(format!("prefetch {}", count));
+            Ok(())
+        }
+        
+        fn declare_queue(&self, name: &str, _options: QueueOptions) -> CoreBaseResult<String> {
+            self.0.lock().unwrap().push(format!("declare '{}'", name));
+            Ok(if name.is_empty() { "amq.gen-new".to_string() } else { name.to_string() })
+        }
+        
+        fn bind_queue(&self, queue: &str, exchange: &str, routing_key: &str) -> CoreBaseResult<()> {
+            self.0.lock().unwrap().push(format!("bind {} {} {}", queue, exchange, routing_key));
+            Ok(())
+        }
+        
+        fn consume(&self, queue: &str, _ack_mode: AckMode) -> CoreBaseResult<String> {
+            self.0.lock().unwrap().push(format!("consume {}", queue));
+            Ok(format!("ctag-{}", queue))
+        }
+    }
+    
+    #[test]
+    #[ignore = "needs an AMQP broker at localhost:5672"]
+    fn test_publish_consume_and_ack() {
+        let client = AmqpClient::connect(&amqp_config(), AmqpOptions::default()).unwrap();
+        assert!(client.is_connected());
+        assert_eq!(client.declare_queue("jobs", QueueOptions::default().durable()).unwrap(), "jobs");
+        assert_eq!(client.declare_queue("", QueueOptions::default().exclusive()).unwrap(), "amq.gen-1");
+        client.bind_queue("jobs", "work", "jobs.#").unwrap();
+        client.set_prefetch(10).unwrap();
+        
+        let consumer = client.consume("jobs", AckMode::Manual).unwrap();
+        assert_eq!(consumer.queue(), "jobs");
+        client.publish_persistent("", "jobs", b"resize image").unwrap();
+        let delivery = consumer.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(delivery.message.topic.as_deref(), Some("jobs"));
+        assert_eq!(delivery.message.as_bytes(), b"resize image");
+        assert!(!delivery.redelivered);
+        delivery.ack().unwrap();
+        
+        // Deliveries from before a recovery can no longer be settled
+        client.publish("", "jobs", b"thumbnail").unwrap();
+        let delivery = consumer.recv_timeout(Duration::from_secs(5)).unwrap();
+        client.shared.generation.fetch_add(1, Ordering::SeqCst);
+        assert!(delivery.nack(true).is_err());
+        
+        let auto = client.consume("jobs", AckMode::Auto).unwrap();
+        client.publish("", "jobs", b"cleanup").unwrap();
+        assert!(auto.recv_timeout(Duration::from_secs(5)).unwrap().ack().is_err());
+        
+        client.close().unwrap();
+        assert!(consumer.recv_timeout(Duration::from_millis(10)).is_err());
+    }
+    
+    #[test]
+    fn test_restore_renames_server_named_queues() {
+        let mut topology = Topology {
+            queues: vec![QueueDeclaration {
+                requested: String::new(),
+                name: "amq.gen-old".to_string(),
+                options: QueueOptions::default().exclusive(),
+            }],
+            bindings: vec![Binding {
+                queue: "amq.gen-old".to_string(),
+                exchange: "events".to_string(),
+                routing_key: "#".to_string(),
+            }],
+            prefetch: Some(5),
+        };
+        let (sender, _receiver) = mpsc::channel();
+        let mut consumers = vec![Consumer {
+            id: 1,
+            queue: "amq.gen-old".to_string(),
+            ack_mode: AckMode::Manual,
+            tag: "ctag-old".to_string(),
+            sender,
+        }];
+        
+        let channel = RecordingChannel::default();
+        restore(&channel, &mut topology, &mut consumers).unwrap();
+        assert_eq!(topology.queues[0].name, "amq.gen-new");
+        assert_eq!(topology.bindings[0].queue, "amq.gen-new");
+        assert_eq!(consumers[0].queue, "amq.gen-new");
+        assert_eq!(consumers[0].tag, "ctag-amq.gen-new");
+        assert_eq!(*channel.0.lock().unwrap(), [
+            "prefetch 5",
+            "declare ''",
+            "bind amq.gen-new events #",
+            "consume amq.gen-new",
+        ]);
+    }
+}
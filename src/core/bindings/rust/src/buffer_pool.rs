@@ -0,0 +1,184 @@
+//! Receive buffer pooling for CoreBase Rust bindings
+//!
+//! This module provides `BufferPool`, which hands out reusable buffers to
+//! `NetworkConnection::receive_pooled` so hot receive paths do not allocate
+//! a fresh buffer for every message.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+/// Default size of new pool buffers
+pub const DEFAULT_BUFFER_SIZE: usize = 4096;
+
+/// Snapshot of buffer pool statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// Buffers allocated because the pool was empty
+    pub allocated: u64,
+    /// Buffers taken from the pool instead of being allocated
+    pub reused: u64,
+    /// Buffers currently waiting in the pool
+    pub idle: usize,
+}
+
+#[derive(Debug)]
+struct PoolShared {
+    buffer_size: usize,
+    max_idle: usize,
+    idle: Mutex<Vec<Vec<u8>>>,
+    stats: Mutex<BufferPoolStats>,
+}
+
+/// Pool of reusable receive buffers
+///
+/// Clones share the same buffers. Buffers grow to fit large messages and
+/// keep their size when they return to the pool.
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    shared: Arc<PoolShared>,
+}
+
+impl BufferPool {
+    /// Create a pool of `buffer_size`-byte buffers, keeping at most
+    /// `max_idle` of them between uses
+    pub fn new(buffer_size: usize, max_idle: usize) -> Self {
+        BufferPool {
+            shared: Arc::new(PoolShared {
+                buffer_size: buffer_size.max(1),
+                max_idle,
+                idle: Mutex::new(Vec::new()),
+                stats: Mutex::new(BufferPoolStats::default()),
+            }),
+        }
+    }
+    
+    /// Get the size of new buffers
+    pub fn buffer_size(&self) -> usize {
+        self.shared.buffer_size
+    }
+    
+    /// Take an empty buffer, allocating one if the pool is empty
+    pub fn take(&self) -> PooledBuffer {
+        let reused = self.shared.idle.lock().ok().and_then(|mut idle| idle.pop());
+        if let Ok(mut stats) = self.shared.stats.lock() {
+            match reused {
+                Some(_) => stats.reused += 1,
+                None => stats.allocated += 1,
+            }
+        }
+        
+        PooledBuffer {
+            data: reused.unwrap_or_else(|| vec![0u8; self.shared.buffer_size]),
+            len: 0,
+            pool: Arc::clone(&self.shared),
+        }
+    }
+    
+    /// Get current pool statistics
+    pub fn stats(&self) -> BufferPoolStats {
+        let idle = self.shared.idle.lock().map(|idle| idle.len()).unwrap_or_default();
+        self.shared.stats.lock()
+            .map(|stats| BufferPoolStats { idle, ..*stats })
+            .unwrap_or_default()
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUFFER_SIZE, 64)
+    }
+}
+
+/// A buffer taken from a `BufferPool`
+///
+/// Dereferences to the received bytes and returns to the pool on drop.
+#[derive(Debug)]
+pub struct PooledBuffer {
+    /// Allocated storage, always fully initialized
+    data: Vec<u8>,
+    len: usize,
+    pool: Arc<PoolShared>,
+}
+
+impl PooledBuffer {
+    /// Copy the contents into a new `Vec`
+    pub fn to_vec(&self) -> Vec<u8> {
+        self[..].to_vec()
+    }
+    
+    /// Get the unfilled part of the buffer, growing it if it is full
+    pub(crate) fn unfilled(&mut self) -> &mut [u8] {
+        if self.len == self.data.len() {
+            let grown = (self.data.len() * 2).max(self.pool.buffer_size);
+            self.data.resize(grown, 0);
+        }
+        &mut self.data[self.len..]
+    }
+    
+    /// Mark `count` more bytes of `unfilled()` as received
+    pub(crate) fn advance(&mut self, count: usize) {
+        self.len = (self.len + count).min(self.data.len());
+    }
+    
+    /// Replace the contents with a copy of `bytes`
+    pub(crate) fn fill_from(&mut self, bytes: &[u8]) {
+        if self.data.len() < bytes.len() {
+            self.data.resize(bytes.len(), 0);
+        }
+        self.data[..bytes.len()].copy_from_slice(bytes);
+        self.len = bytes.len();
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+    
+    fn deref(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.data[..self.len]
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Ok(mut idle) = self.pool.idle.lock() {
+            if idle.len() < self.pool.max_idle {
+                idle.push(std::mem::take(&mut self.data));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_buffer_reuse() {
+        let pool = BufferPool::new(4, 1);
+        
+        let mut buffer = pool.take();
+        assert!(buffer.is_empty());
+        buffer.unfilled()[..4].copy_from_slice(b"abcd");
+        buffer.advance(4);
+        buffer.unfilled()[..2].copy_from_slice(b"ef");
+        buffer.advance(2);
+        assert_eq!(&buffer[..], b"abcdef");
+        
+        let other = pool.take();
+        drop(buffer);
+        drop(other);
+        assert_eq!(pool.stats(), BufferPoolStats { allocated: 2, reused: 0, idle: 1 });
+        
+        let mut reused = pool.take();
+        assert!(reused.is_empty());
+        reused.fill_from(b"0123456789");
+        assert_eq!(reused.to_vec(), b"0123456789");
+        assert_eq!(pool.stats().reused, 1);
+    }
+}
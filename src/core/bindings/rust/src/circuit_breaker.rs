@@ -0,0 +1,392 @@
+//! Circuit breaking for CoreBase Rust bindings
+//!
+//! This module provides `CircuitBreaker`, which tracks the outcome of calls
+//! to one endpoint and makes further calls fail fast while the endpoint keeps
+//! failing. `NetworkManager::set_circuit_breaker` keeps one breaker per
+//! endpoint around `create_connection`, `reconnect` and `send`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+
+/// State of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through and their outcomes are tracked
+    Closed,
+    /// Calls fail fast until `open_duration` has passed
+    Open,
+    /// A limited number of trial calls decide whether to close again
+    HalfOpen,
+}
+
+/// Circuit breaker options
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakerOptions {
+    /// Number of recent outcomes the failure rate is computed over
+    pub window: usize,
+    /// Outcomes needed in the window before the breaker may open
+    pub minimum_calls: usize,
+    /// Failure rate, from 0.0 to 1.0, at which the breaker opens
+    pub failure_rate: f64,
+    /// How long the breaker stays open before allowing trial calls
+    pub open_duration: Duration,
+    /// Trial calls allowed while half-open; all of them must succeed to close
+    pub half_open_calls: usize,
+}
+
+impl Default for BreakerOptions {
+    fn default() -> Self {
+        BreakerOptions {
+            window: 20,
+            minimum_calls: 5,
+            failure_rate: 0.5,
+            open_duration: Duration::from_secs(30),
+            half_open_calls: 1,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    /// Recent outcomes, `true` for failures
+    outcomes: VecDeque<bool>,
+    opened_at: Instant,
+    /// Trial calls started since the breaker became half-open
+    trials: usize,
+    /// Trial calls that succeeded since the breaker became half-open
+    trial_successes: usize,
+    rejected: u64,
+}
+
+/// Circuit breaker guarding one endpoint
+///
+/// While closed, the breaker opens once at least `minimum_calls` of the last
+/// `window` outcomes were recorded and `failure_rate` of them failed. While
+/// open, `acquire()` fails with `CoreBaseError::OperationFailed` without
+/// touching the endpoint. After `open_duration` the breaker lets
+/// `half_open_calls` trial calls through: a failing trial opens it again,
+/// and once all of them succeed it closes.
+///
+/// Only `NetworkError` and `Timeout` count as failures; other errors mean
+/// the endpoint answered and count as successes.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    endpoint: String,
+    options: BreakerOptions,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker for an endpoint
+    pub fn new(endpoint: &str, options: BreakerOptions) -> Self {
+        CircuitBreaker {
+            endpoint: endpoint.to_string(),
+            options,
+            state: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: Instant::now(),
+                trials: 0,
+                trial_successes: 0,
+                rejected: 0,
+            }),
+        }
+    }
+    
+    /// Get the endpoint this breaker guards
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+    
+    /// Get the breaker options
+    pub fn options(&self) -> &BreakerOptions {
+        &self.options
+    }
+    
+    /// Get the current state
+    ///
+    /// An open breaker reports `HalfOpen` once `open_duration` has passed.
+    pub fn state(&self) -> CircuitState {
+        match self.state.lock() {
+            Ok(mut state) => {
+                self.expire_open(&mut state);
+                state.state
+            }
+            Err(_) => CircuitState::Closed,
+        }
+    }
+    
+    /// Get the failure rate over the current window
+    pub fn failure_rate(&self) -> f64 {
+        self.state.lock()
+            .map(|state| failure_rate(&state.outcomes))
+            .unwrap_or(0.0)
+    }
+    
+    /// Get the number of calls rejected while the breaker was open
+    pub fn rejected(&self) -> u64 {
+        self.state.lock().map(|state| state.rejected).unwrap_or(0)
+    }
+    
+    /// Ask to make a call
+    ///
+    /// Every successful `acquire()` must be followed by `record()`.
+    pub fn acquire(&self) -> CoreBaseResult<()> {
+        let mut state = self.state.lock().map_err(|_| breaker_lock_error())?;
+        self.expire_open(&mut state);
+        
+        match state.state {
+            CircuitState::Closed => return Ok(()),
+            CircuitState::HalfOpen if state.trials < self.options.half_open_calls.max(1) => {
+                state.trials += 1;
+                return Ok(());
+            }
+            CircuitState::HalfOpen | CircuitState::Open => state.rejected += 1,
+        }
+        
+        let retry_in = (state.opened_at + self.options.open_duration).saturating_duration_since(Instant::now());
+        Err(CoreBaseError::OperationFailed(
+            format!("Circuit breaker for {} is open, retry in {:?}", self.endpoint, retry_in),
+            None,
+        ))
+    }
+    
+    /// Record the outcome of a call allowed by `acquire()`
+    pub fn record<T>(&self, result: &CoreBaseResult<T>) {
+        let failed = matches!(result, Err(CoreBaseError::NetworkError(..)) | Err(CoreBaseError::Timeout(..)));
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        
+        match state.state {
+            CircuitState::Closed => {
+                state.outcomes.push_back(failed);
+                while state.outcomes.len() > self.options.window.max(1) {
+                    state.outcomes.pop_front();
+                }
+                if state.outcomes.len() >= self.options.minimum_calls
+                    && failure_rate(&state.outcomes) >= self.options.failure_rate
+                {
+                    open(&mut state);
+                }
+            }
+            CircuitState::HalfOpen if failed => open(&mut state),
+            CircuitState::HalfOpen => {
+                state.trial_successes += 1;
+                if state.trial_successes >= self.options.half_open_calls.max(1) {
+                    state.state = CircuitState::Closed;
+                    state.outcomes.clear();
+                }
+            }
+            // Calls started before the breaker opened
+            CircuitState::Open => {}
+        }
+    }
+    
+    /// Run `call` if the breaker allows it and record its outcome
+    pub fn call<T, F>(&self, call: F) -> CoreBaseResult<T>
+    where
+        F: FnOnce() -> CoreBaseResult<T>,
+    {
+        self.acquire()?;
+        let result = call();
+        self.record(&result);
+        result
+    }
+    
+    /// Close the breaker and forget recorded outcomes
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.state = CircuitState::Closed;
+            state.outcomes.clear();
+        }
+    }
+    
+    fn expire_open(&self, state: &mut BreakerState) {
+        if state.state == CircuitState::Open && state.opened_at.elapsed() >= self.options.open_duration {
+            state.state = CircuitState::HalfOpen;
+            state.trials = 0;
+            state.trial_successes = 0;
+        }
+    }
+}
+
+/// Breakers of a `NetworkManager`, one per endpoint
+#[derive(Debug)]
+pub(crate) struct BreakerRegistry {
+    options: BreakerOptions,
+    breakers: Mutex<HashMap<String, Arc<CircuitBreaker>>>,
+}
+
+impl BreakerRegistry {
+    pub(crate) fn new(options: BreakerOptions) -> Self {
+        BreakerRegistry {
+            options,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Get the breaker of an endpoint, creating it on first use
+    pub(crate) fn breaker(&self, endpoint: &str) -> Option<Arc<CircuitBreaker>> {
+        let mut breakers = self.breakers.lock().ok()?;
+        let breaker = breakers
+            .entry(endpoint.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(endpoint, self.options.clone())));
+        Some(Arc::clone(breaker))
+    }
+    
+    /// Get the breaker of an endpoint if it was used
+    pub(crate) fn get(&self, endpoint: &str) -> Option<Arc<CircuitBreaker>> {
+        self.breakers.lock().ok()?.get(endpoint).cloned()
+    }
+}
+
+fn open(state: &mut BreakerState) {
+    state.state = CircuitState::Open;
+    state.opened_at = Instant::now();
+    state.outcomes.clear();
+}
+
+fn failure_rate(outcomes: &VecDeque<bool>) -> f64 {
+    if outcomes.is_empty() {
+        return 0.0;
+    }
+    outcomes.iter().filter(|failed| **failed).count() as f64 / outcomes.len() as f64
+}
+
+fn breaker_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access circuit breaker".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    
+    use crate::network::{NetworkConfig, NetworkManager, NetworkMessage};
+    use crate::transport::Transport;
+    
+    /// Transport that fails every call while `down` is set
+    #[derive(Debug, Default)]
+    struct FlakyTransport {
+        down: Arc<AtomicBool>,
+        calls: Arc<AtomicUsize>,
+    }
+    
+    impl FlakyTransport {
+        fn attempt(&self) -> CoreBaseResult<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                return Err(CoreBaseError::NetworkError("unreachable".to_string(), None));
+            }
+            Ok(())
+        }
+    }
+    
+    impl Transport for FlakyTransport {
+        fn connect(&self, _config: &NetworkConfig) -> CoreBaseResult<String> {
+            self.attempt().map(|_| self.calls.load(Ordering::SeqCst).to_string())
+        }
+        
+        fn send(&self, _connection_id: &str, _data: &[u8]) -> CoreBaseResult<()> {
+            self.attempt()
+        }
+        
+        fn receive(&self, _connection_id: &str, _timeout: Option<Duration>) -> CoreBaseResult<Option<Vec<u8>>> {
+            Ok(None)
+        }
+        
+        fn close(&self, _connection_id: &str) -> CoreBaseResult<()> {
+            Ok(())
+        }
+    }
+    
+    fn failure() -> CoreBaseResult<()> {
+        Err(CoreBaseError::NetworkError("refused".to_string(), None))
+    }
+    
+    #[test]
+    fn test_breaker_states() {
+        let breaker = CircuitBreaker::new("db:5432", BreakerOptions {
+            window: 4,
+            minimum_calls: 4,
+            failure_rate: 0.5,
+            open_duration: Duration::from_millis(30),
+            half_open_calls: 2,
+        });
+        
+        assert!(breaker.call(|| Ok(())).is_ok());
+        assert!(breaker.call(|| Err::<(), _>(CoreBaseError::InvalidParameter("bad".to_string(), None))).is_err());
+        assert!(breaker.call(|| Err::<(), _>(CoreBaseError::InvalidData("Message too large".to_string(), None))).is_err());
+        assert!(breaker.call(failure).is_err());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.call(failure).is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        
+        let mut called = false;
+        let rejected = breaker.call(|| {
+            called = true;
+            Ok(())
+        });
+        assert!(matches!(rejected, Err(CoreBaseError::OperationFailed(..))));
+        assert!(!called);
+        assert_eq!(breaker.rejected(), 1);
+        
+        // A failed trial opens the breaker again
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.call(failure).is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        
+        std::thread::sleep(Duration::from_millis(40));
+        breaker.acquire().unwrap();
+        breaker.acquire().unwrap();
+        assert!(breaker.acquire().is_err());
+        breaker.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.failure_rate(), 0.0);
+    }
+    
+    #[test]
+    fn test_manager_breakers() {
+        let transport = FlakyTransport::default();
+        let (down, calls) = (Arc::clone(&transport.down), Arc::clone(&transport.calls));
+        let manager = NetworkManager::new().unwrap();
+        manager.register_transport("flaky", transport).unwrap();
+        manager.set_circuit_breaker(BreakerOptions {
+            minimum_calls: 3,
+            ..BreakerOptions::default()
+        }).unwrap();
+        
+        let config = NetworkConfig::custom("flaky", "api", 443);
+        let connection = manager.create_connection(config.clone()).unwrap();
+        let breaker = manager.circuit_breaker("api", 443).unwrap();
+        assert!(manager.circuit_breaker("other", 443).is_none());
+        
+        down.store(true, Ordering::SeqCst);
+        let message = NetworkMessage::new_text("hello");
+        assert!(matches!(connection.send(&message), Err(CoreBaseError::NetworkError(..))));
+        assert!(matches!(connection.send(&message), Err(CoreBaseError::NetworkError(..))));
+        assert_eq!(breaker.state(), CircuitState::Open);
+        
+        // Open breakers fail fast without reaching the transport
+        let before = calls.load(Ordering::SeqCst);
+        assert!(matches!(connection.send(&message), Err(CoreBaseError::OperationFailed(..))));
+        assert!(matches!(manager.create_connection(config.clone()), Err(CoreBaseError::OperationFailed(..))));
+        assert!(manager.reconnect(&connection.id).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), before);
+        assert_eq!(breaker.rejected(), 3);
+        
+        breaker.reset();
+        down.store(false, Ordering::SeqCst);
+        assert!(manager.create_connection(config.clone()).is_ok());
+        assert!(manager.clear_circuit_breaker());
+        assert!(manager.circuit_breaker("api", 443).is_none());
+    }
+}
@@ -0,0 +1,254 @@
+//! Payload codecs for CoreBase Rust bindings
+//!
+//! This module provides the `Codec` trait used to serialize typed values
+//! into `NetworkMessage` payloads. JSON is always available; MessagePack,
+//! bincode and CBOR are enabled by the "msgpack", "bincode" and "cbor"
+//! features.
+
+use std::fmt;
+use std::str::FromStr;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+
+/// Header carrying the content type of an encoded payload
+pub const CONTENT_TYPE_HEADER: &str = "content-type";
+
+/// Serializes values to and from message payloads
+pub trait Codec: fmt::Debug + Send + Sync {
+    /// MIME type written to the content-type header
+    fn content_type(&self) -> &'static str;
+    
+    /// Serialize a value
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> CoreBaseResult<Vec<u8>>;
+    
+    /// Deserialize a value
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> CoreBaseResult<T>;
+}
+
+/// JSON codec
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+    
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> CoreBaseResult<Vec<u8>> {
+        serde_json::to_vec(value).map_err(|e| encode_error("JSON", e))
+    }
+    
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> CoreBaseResult<T> {
+        serde_json::from_slice(data).map_err(|e| decode_error("JSON", e))
+    }
+}
+
+/// MessagePack codec (requires "msgpack" feature)
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessagePackCodec;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MessagePackCodec {
+    fn content_type(&self) -> &'static str {
+        "application/msgpack"
+    }
+    
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> CoreBaseResult<Vec<u8>> {
+        // Named fields keep payloads readable by non-Rust peers
+        rmp_serde::to_vec_named(value).map_err(|e| encode_error("MessagePack", e))
+    }
+    
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> CoreBaseResult<T> {
+        rmp_serde::from_slice(data).map_err(|e| decode_error("MessagePack", e))
+    }
+}
+
+/// bincode codec (requires "bincode" feature)
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BincodeCodec;
+
+#[cfg(feature = "bincode")]
+impl Codec for BincodeCodec {
+    fn content_type(&self) -> &'static str {
+        "application/x-bincode"
+    }
+    
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> CoreBaseResult<Vec<u8>> {
+        bincode::serialize(value).map_err(|e| encode_error("bincode", e))
+    }
+    
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> CoreBaseResult<T> {
+        bincode::deserialize(data).map_err(|e| decode_error("bincode", e))
+    }
+}
+
+/// CBOR codec (requires "cbor" feature)
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CborCodec;
+
+#[cfg(feature = "cbor")]
+impl Codec for CborCodec {
+    fn content_type(&self) -> &'static str {
+        "application/cbor"
+    }
+    
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> CoreBaseResult<Vec<u8>> {
+        let mut data = Vec::new();
+        ciborium::into_writer(value, &mut data).map_err(|e| encode_error("CBOR", e))?;
+        Ok(data)
+    }
+    
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> CoreBaseResult<T> {
+        ciborium::from_reader(data).map_err(|e| decode_error("CBOR", e))
+    }
+}
+
+/// Built-in codec selection, stored in `NetworkConfig`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PayloadCodec {
+    #[default]
+    Json,
+    /// MessagePack (requires "msgpack" feature)
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    /// bincode (requires "bincode" feature)
+    #[cfg(feature = "bincode")]
+    Bincode,
+    /// CBOR (requires "cbor" feature)
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl PayloadCodec {
+    /// Find the built-in codec for a content type
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        [
+            PayloadCodec::Json,
+            #[cfg(feature = "msgpack")]
+            PayloadCodec::MessagePack,
+            #[cfg(feature = "bincode")]
+            PayloadCodec::Bincode,
+            #[cfg(feature = "cbor")]
+            PayloadCodec::Cbor,
+        ]
+        .into_iter()
+        .find(|codec| codec.content_type().eq_ignore_ascii_case(mime))
+    }
+}
+
+impl FromStr for PayloadCodec {
+    type Err = CoreBaseError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(PayloadCodec::Json),
+            #[cfg(feature = "msgpack")]
+            "msgpack" | "messagepack" => Ok(PayloadCodec::MessagePack),
+            #[cfg(feature = "bincode")]
+            "bincode" => Ok(PayloadCodec::Bincode),
+            #[cfg(feature = "cbor")]
+            "cbor" => Ok(PayloadCodec::Cbor),
+            _ => Err(CoreBaseError::InvalidParameter(format!("Unknown or disabled codec: {}", s), None)),
+        }
+    }
+}
+
+impl Codec for PayloadCodec {
+    fn content_type(&self) -> &'static str {
+        match self {
+            PayloadCodec::Json => JsonCodec.content_type(),
+            #[cfg(feature = "msgpack")]
+            PayloadCodec::MessagePack => MessagePackCodec.content_type(),
+            #[cfg(feature = "bincode")]
+            PayloadCodec::Bincode => BincodeCodec.content_type(),
+            #[cfg(feature = "cbor")]
+            PayloadCodec::Cbor => CborCodec.content_type(),
+        }
+    }
+    
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> CoreBaseResult<Vec<u8>> {
+        match self {
+            PayloadCodec::Json => JsonCodec.encode(value),
+            #[cfg(feature = "msgpack")]
+            PayloadCodec::MessagePack => MessagePackCodec.encode(value),
+            #[cfg(feature = "bincode")]
+            PayloadCodec::Bincode => BincodeCodec.encode(value),
+            #[cfg(feature = "cbor")]
+            PayloadCodec::Cbor => CborCodec.encode(value),
+        }
+    }
+    
+    fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> CoreBaseResult<T> {
+        match self {
+            PayloadCodec::Json => JsonCodec.decode(data),
+            #[cfg(feature = "msgpack")]
+            PayloadCodec::MessagePack => MessagePackCodec.decode(data),
+            #[cfg(feature = "bincode")]
+            PayloadCodec::Bincode => BincodeCodec.decode(data),
+            #[cfg(feature = "cbor")]
+            PayloadCodec::Cbor => CborCodec.decode(data),
+        }
+    }
+}
+
+fn encode_error(format: &str, e: impl fmt::Display) -> CoreBaseError {
+    CoreBaseError::InvalidParameter(format!("{} serialization error: {}", format, e), None)
+}
+
+fn decode_error(format: &str, e: impl fmt::Display) -> CoreBaseError {
+    CoreBaseError::InvalidData(format!("{} deserialization error: {}", format, e), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        sensor: String,
+        value: f64,
+        tags: HashMap<String, String>,
+    }
+    
+    fn reading() -> Reading {
+        Reading {
+            sensor: "kitchen".to_string(),
+            value: 21.5,
+            tags: HashMap::from([("unit".to_string(), "celsius".to_string())]),
+        }
+    }
+    
+    fn round_trip<C: Codec>(codec: C) {
+        let encoded = codec.encode(&reading()).unwrap();
+        assert_eq!(codec.decode::<Reading>(&encoded).unwrap(), reading());
+        assert!(codec.decode::<Reading>(&[0xff, 0x00]).is_err());
+        assert!(PayloadCodec::from_content_type(codec.content_type()).is_some());
+    }
+    
+    #[test]
+    fn test_codec_round_trips() {
+        round_trip(JsonCodec);
+        round_trip(PayloadCodec::default());
+        #[cfg(feature = "msgpack")]
+        round_trip(MessagePackCodec);
+        #[cfg(feature = "bincode")]
+        round_trip(BincodeCodec);
+        #[cfg(feature = "cbor")]
+        round_trip(CborCodec);
+    }
+    
+    #[test]
+    fn test_codec_lookup() {
+        assert_eq!(PayloadCodec::from_content_type("application/json; charset=utf-8"), Some(PayloadCodec::Json));
+        assert_eq!(PayloadCodec::from_content_type("text/plain"), None);
+        assert_eq!("JSON".parse::<PayloadCodec>().unwrap(), PayloadCodec::Json);
+        assert!("yaml".parse::<PayloadCodec>().is_err());
+    }
+}
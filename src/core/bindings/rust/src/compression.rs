@@ -0,0 +1,175 @@
+//! Payload compression for CoreBase Rust bindings
+//!
+//! Connections can compress outgoing payloads and transparently decompress
+//! incoming ones. Connections with compression enabled put a one-byte flag
+//! naming the encoding in front of every payload, so both peers must enable
+//! it. gzip is enabled by the "gzip" feature and zstd by the "zstd" feature.
+
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+
+/// Header selecting (on send) or reporting (on receive) payload compression
+pub const CONTENT_ENCODING_HEADER: &str = "content-encoding";
+
+/// Largest payload `Compression::decompress()` produces, in bytes
+pub const MAX_DECOMPRESSED_LEN: usize = 64 * 1024 * 1024;
+
+/// Compression algorithms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Compression {
+    #[default]
+    None,
+    /// gzip (requires "gzip" feature)
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// zstd (requires "zstd" feature)
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Name used in the content-encoding header
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compression::None => "identity",
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => "gzip",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "zstd",
+        }
+    }
+    
+    /// Compress a payload
+    pub fn compress(&self, data: &[u8]) -> CoreBaseResult<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                use std::io::Write;
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).map_err(compression_error)?;
+                encoder.finish().map_err(compression_error)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(data, 0).map_err(compression_error),
+        }
+    }
+    
+    /// Decompress a payload of at most `MAX_DECOMPRESSED_LEN` bytes
+    pub fn decompress(&self, data: &[u8]) -> CoreBaseResult<Vec<u8>> {
+        self.decompress_limited(data, MAX_DECOMPRESSED_LEN)
+    }
+    
+    /// Decompress a payload, failing with `InvalidData` once the output
+    /// exceeds `limit` bytes
+    pub fn decompress_limited(&self, data: &[u8], limit: usize) -> CoreBaseResult<Vec<u8>> {
+        let decoded = match self {
+            Compression::None => data.to_vec(),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => read_limited(flate2::read::GzDecoder::new(data), limit)?,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => read_limited(zstd::stream::read::Decoder::with_buffer(data).map_err(decompression_error)?, limit)?,
+        };
+        if decoded.len() > limit {
+            return Err(CoreBaseError::InvalidData(
+                format!("Decompressed payload exceeds {} bytes", limit),
+                None,
+            ));
+        }
+        Ok(decoded)
+    }
+    
+    /// Flag byte marking payloads encoded with this algorithm
+    pub fn flag(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => 1,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => 2,
+        }
+    }
+    
+    /// Algorithm named by a flag byte
+    pub fn from_flag(flag: u8) -> CoreBaseResult<Compression> {
+        match flag {
+            0 => Ok(Compression::None),
+            #[cfg(feature = "gzip")]
+            1 => Ok(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            2 => Ok(Compression::Zstd),
+            _ => Err(CoreBaseError::InvalidData(format!("Unknown or disabled compression flag: {}", flag), None)),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = CoreBaseError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" | "identity" => Ok(Compression::None),
+            #[cfg(feature = "gzip")]
+            "gzip" => Ok(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(CoreBaseError::InvalidParameter(format!("Unknown or disabled compression: {}", s), None)),
+        }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn compression_error(e: std::io::Error) -> CoreBaseError {
+    CoreBaseError::NetworkError(format!("Compression failed: {}", e), None)
+}
+
+/// Read at most one byte past `limit`, enough to tell that it was exceeded
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn read_limited<R: std::io::Read>(reader: R, limit: usize) -> CoreBaseResult<Vec<u8>> {
+    use std::io::Read;
+    let mut decoded = Vec::new();
+    reader.take(limit as u64 + 1).read_to_end(&mut decoded).map_err(decompression_error)?;
+    Ok(decoded)
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn decompression_error(e: std::io::Error) -> CoreBaseError {
+    CoreBaseError::InvalidData(format!("Decompression failed: {}", e), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_compression_round_trips() {
+        let data = "sensor reading 21.5C; ".repeat(100).into_bytes();
+        let algorithms = [
+            Compression::None,
+            #[cfg(feature = "gzip")]
+            Compression::Gzip,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd,
+        ];
+        
+        for algorithm in algorithms {
+            let compressed = algorithm.compress(&data).unwrap();
+            assert_eq!(Compression::from_flag(algorithm.flag()).unwrap(), algorithm);
+            assert_eq!(algorithm.decompress(&compressed).unwrap(), data);
+            assert_eq!(algorithm.decompress_limited(&compressed, data.len()).unwrap(), data);
+            assert!(matches!(
+                algorithm.decompress_limited(&compressed, data.len() - 1),
+                Err(CoreBaseError::InvalidData(..))
+            ));
+            assert_eq!(algorithm.name().parse::<Compression>().unwrap(), algorithm);
+            if algorithm != Compression::None {
+                assert!(compressed.len() < data.len());
+            }
+        }
+        
+        assert!("brotli".parse::<Compression>().is_err());
+        assert!(matches!(Compression::from_flag(0xff), Err(CoreBaseError::InvalidData(..))));
+    }
+}
@@ -5,13 +5,22 @@
 
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use serde_json;
 
-use crate::{to_c_string, from_c_string};
-use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::{LogLevel, to_c_string};
+use crate::config_format::{env_var_to_key, parse_dotenv, parse_ini, write_ini, ConfigFormat};
+use crate::config_store::{ConfigChange, ConfigStore, Store};
+use crate::error::{CoreBaseError, CoreBaseResult, ErrorHandler};
+use crate::util::unique_path;
 
 /// Configuration value types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -23,9 +32,17 @@ pub enum ConfigValue {
     Boolean(bool),
     Array(Vec<ConfigValue>),
     Object(HashMap<String, ConfigValue>),
+    /// Binary data, stored as a base64 string in the C++ store
+    #[serde(serialize_with = "serialize_bytes", skip_deserializing)]
+    Bytes(Vec<u8>),
     Null,
 }
 
+/// Serialize binary config values as base64 strings
+fn serialize_bytes<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&BASE64.encode(bytes))
+}
+
 impl ConfigValue {
     /// Convert to string representation
     pub fn as_string(&self) -> Option<String> {
@@ -76,6 +93,15 @@ impl ConfigValue {
         }
     }
     
+    /// Convert to binary data, decoding base64 strings
+    pub fn as_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            ConfigValue::Bytes(b) => Some(b.clone()),
+            ConfigValue::String(s) => BASE64.decode(s).ok(),
+            _ => None,
+        }
+    }
+    
     /// Convert to array
     pub fn as_array(&self) -> Option<&Vec<ConfigValue>> {
         match self {
@@ -134,17 +160,128 @@ impl From<f32> for ConfigValue {
     }
 }
 
+impl From<Vec<u8>> for ConfigValue {
+    fn from(b: Vec<u8>) -> Self {
+        ConfigValue::Bytes(b)
+    }
+}
+
+impl From<&[u8]> for ConfigValue {
+    fn from(b: &[u8]) -> Self {
+        ConfigValue::Bytes(b.to_vec())
+    }
+}
+
 impl From<bool> for ConfigValue {
     fn from(b: bool) -> Self {
         ConfigValue::Boolean(b)
     }
 }
 
+/// Replacement text for secret values in audit events
+const REDACTED: &str = "[REDACTED]";
+
+/// Key fragments treated as secret by default
+const DEFAULT_SECRET_PATTERNS: &[&str] = &["password", "secret", "token", "api_key", "private_key", "credential"];
+
+/// Config operation recorded by the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigOperation {
+    Get,
+    Set,
+}
+
+/// Audit record for a single config access
+#[derive(Debug, Clone)]
+pub struct ConfigAuditEvent {
+    pub operation: ConfigOperation,
+    pub key: String,
+    pub caller: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub success: bool,
+    pub timestamp: u64,
+}
+
+impl fmt::Display for ConfigAuditEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operation = match self.operation {
+            ConfigOperation::Get => "get",
+            ConfigOperation::Set => "set",
+        };
+        write!(f, "config {} '{}' at {}", operation, self.key, self.caller)?;
+        
+        match (&self.old_value, &self.new_value) {
+            (Some(old), Some(new)) => write!(f, ": {} -> {}", old, new)?,
+            (None, Some(new)) => write!(f, ": {}", new)?,
+            _ => {}
+        }
+        
+        if !self.success {
+            write!(f, " (failed)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Destination for config audit events
+pub enum AuditSink {
+    /// Log events through the C++ ErrorHandler at the given level
+    ErrorHandler(LogLevel),
+    /// Deliver events to a custom callback
+    Custom(Box<dyn Fn(&ConfigAuditEvent) + Send + Sync>),
+}
+
+impl fmt::Debug for AuditSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditSink::ErrorHandler(level) => f.debug_tuple("ErrorHandler").field(level).finish(),
+            AuditSink::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Validation callback run against a value before it is written
+pub type ConfigValidator = Box<dyn Fn(&ConfigValue) -> Result<(), String> + Send + Sync>;
+
+/// Callback fired when a watched key changes
+pub type KeyWatchCallback = Box<dyn Fn(Option<&ConfigValue>) + Send + Sync>;
+
+/// Callback registered through `ConfigManager::watch_key`
+struct KeyWatcher {
+    callback: KeyWatchCallback,
+    last: Option<ConfigValue>,
+}
+
 /// Configuration manager wrapper for the C++ ConfigManager class
-#[derive(Debug)]
 pub struct ConfigManager {
     initialized: bool,
     cache: HashMap<String, ConfigValue>,
+    validators: HashMap<String, Vec<ConfigValidator>>,
+    audit: Option<AuditSink>,
+    secret_patterns: Vec<String>,
+    known_keys: HashSet<String>,
+    watchers: HashMap<String, Vec<KeyWatcher>>,
+    defaults: HashMap<String, ConfigValue>,
+    backend: Option<Box<dyn ConfigStore>>,
+    expirations: HashMap<String, Instant>,
+    env_overrides: HashMap<String, ConfigValue>,
+}
+
+impl fmt::Debug for ConfigManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigManager")
+            .field("initialized", &self.initialized)
+            .field("cache", &self.cache)
+            .field("validators", &self.validators.keys().collect::<Vec<_>>())
+            .field("audit", &self.audit)
+            .field("watchers", &self.watchers.keys().collect::<Vec<_>>())
+            .field("defaults", &self.defaults)
+            .field("backend", &self.backend)
+            .field("expirations", &self.expirations)
+            .field("env_overrides", &self.env_overrides.keys().collect::<Vec<_>>())
+            .finish()
+    }
 }
 
 impl ConfigManager {
@@ -153,45 +290,296 @@ impl ConfigManager {
         Ok(ConfigManager {
             initialized: true,
             cache: HashMap::new(),
+            validators: HashMap::new(),
+            audit: None,
+            secret_patterns: DEFAULT_SECRET_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            known_keys: HashSet::new(),
+            watchers: HashMap::new(),
+            defaults: HashMap::new(),
+            backend: None,
+            expirations: HashMap::new(),
+            env_overrides: HashMap::new(),
         })
     }
     
+    /// Create a ConfigManager from a JSON document without touching the filesystem
+    ///
+    /// Nested objects are flattened into dotted keys, matching the layout
+    /// produced by `load()`. The values live in a `MemoryStore`, so reads,
+    /// writes, `load()` and `save()` never reach the C++ store.
+    pub fn from_json_str(json: &str) -> CoreBaseResult<Self> {
+        let json_value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|e| CoreBaseError::ConfigError(format!("JSON parse error: {}", e), None))?;
+        
+        let root = json_to_config_value(json_value);
+        if root.as_object().is_none() {
+            return Err(CoreBaseError::ConfigError(
+                "JSON configuration must be an object".to_string(),
+                None,
+            ));
+        }
+        
+        let mut manager = Self::with_store(Store::Memory)?;
+        flatten_config_value("", &root, &mut manager.cache);
+        if let Some(backend) = manager.backend.as_mut() {
+            backend.set_many(&manager.cache)?;
+        }
+        Ok(manager)
+    }
+    
+    /// Export the Rust-side configuration tree as a JSON string
+    ///
+    /// Dotted keys are expanded back into nested objects. Managers with a
+    /// backend export every stored value, others the cached ones.
+    pub fn to_json_string(&self) -> CoreBaseResult<String> {
+        match self.backend.as_ref() {
+            Some(backend) => config_value_to_json_string(&unflatten_config_values(&backend.load_all()?)),
+            None => config_value_to_json_string(&unflatten_config_values(&self.cache)),
+        }
+    }
+    
+    /// Create a ConfigManager persisting values in the given store
+    ///
+    /// With a non-native store, `get`/`set`/`remove` go to the backend and
+    /// `load`/`save` import and export JSON files on the Rust side instead
+    /// of using the C++ file handling.
+    pub fn with_store(store: Store) -> CoreBaseResult<Self> {
+        let mut manager = Self::new()?;
+        manager.backend = store.open()?;
+        Ok(manager)
+    }
+    
+    /// Get the recorded changes for a key, oldest first
+    ///
+    /// Only stores with history support (such as SQLite) record changes.
+    pub fn history(&self, key: &str) -> CoreBaseResult<Vec<ConfigChange>> {
+        match &self.backend {
+            Some(backend) => backend.history(key),
+            None => Ok(Vec::new()),
+        }
+    }
+    
     /// Load configuration from a file
     pub fn load<P: AsRef<Path>>(&mut self, filename: P) -> CoreBaseResult<()> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ConfigManager not initialized".to_string()
+                "ConfigManager not initialized".to_string(),
+                None,
             ));
         }
         
-        let filename_str = filename.as_ref().to_string_lossy();
+        let path = filename.as_ref();
+        if ConfigFormat::from_path(path) == ConfigFormat::Ini {
+            return self.load_ini(path);
+        }
+        
+        if let Some(backend) = self.backend.as_mut() {
+            // Import the file (and its includes) into the backend in one batch
+            let values = collect_included_values(path, &mut Vec::new())?;
+            backend.set_many(&values)?;
+            
+            self.cache.clear();
+            self.known_keys = values.into_keys().collect();
+            self.notify_all_watchers();
+            return Ok(());
+        }
+        
+        self.native_load(path)?;
+        
+        // Clear cache after loading new config
+        self.cache.clear();
+        self.known_keys.clear();
+        self.apply_includes(path)?;
+        self.notify_all_watchers();
+        Ok(())
+    }
+    
+    /// Load an INI file, mapping sections to nested keys
+    fn load_ini(&mut self, path: &Path) -> CoreBaseResult<()> {
+        let contents = fs::read_to_string(path).map_err(|e| CoreBaseError::ConfigError(
+            format!("Failed to read config file {}: {}", path.display(), e),
+            None,
+        ))?;
+        let values = parse_ini(&contents).map_err(|e| CoreBaseError::ConfigError(
+            format!("Failed to parse INI file {}: {}", path.display(), e),
+            None,
+        ))?;
+        
+        if let Some(backend) = self.backend.as_mut() {
+            backend.set_many(&values)?;
+        } else {
+            // The C++ loader only reads JSON, so hand it an equivalent document
+            let transfer = TransferFile::new();
+            write_config_file(transfer.path(), &config_value_to_json_string(&unflatten_config_values(&values))?)?;
+            self.native_load(transfer.path())?;
+        }
+        
+        self.cache.clear();
+        self.known_keys = values.into_keys().collect();
+        self.notify_all_watchers();
+        Ok(())
+    }
+    
+    /// Load a JSON file into the C++ store
+    fn native_load(&self, path: &Path) -> CoreBaseResult<()> {
+        let filename_str = path.to_string_lossy();
         let c_filename = to_c_string(&filename_str)?;
         
         unsafe {
             let result = crate::cba_config_load(c_filename.as_ptr());
             if result == 0 {
-                // Clear cache after loading new config
-                self.cache.clear();
                 Ok(())
             } else {
                 Err(CoreBaseError::ConfigError(
-                    format!("Failed to load config file: {}", filename_str)
-                ))
+                    format!("Failed to load config file: {}", filename_str),
+                    None,
+                ).with_last_error())
+            }
+        }
+    }
+    
+    /// Save the C++ store to a JSON file
+    fn native_save(&self, path: &Path) -> CoreBaseResult<()> {
+        let filename_str = path.to_string_lossy();
+        let c_filename = to_c_string(&filename_str)?;
+        
+        unsafe {
+            let result = crate::cba_config_save(c_filename.as_ptr());
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(CoreBaseError::ConfigError(
+                    format!("Failed to save config file: {}", filename_str),
+                    None,
+                ).with_last_error())
+            }
+        }
+    }
+    
+    /// Load a dotenv (`.env`) file into the environment override layer
+    ///
+    /// Variable names map to config keys through `env_var_to_key`
+    /// (`SERVER__PORT` becomes `server.port`). Override values take
+    /// precedence over stored values until cleared.
+    pub fn load_dotenv<P: AsRef<Path>>(&mut self, path: P) -> CoreBaseResult<()> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| CoreBaseError::ConfigError(
+            format!("Failed to read dotenv file {}: {}", path.display(), e),
+            None,
+        ))?;
+        let entries = parse_dotenv(&contents).map_err(|e| CoreBaseError::ConfigError(
+            format!("Failed to parse dotenv file {}: {}", path.display(), e),
+            None,
+        ))?;
+        
+        for (name, value) in entries {
+            self.set_env_override(&env_var_to_key(&name), ConfigValue::String(value));
+        }
+        Ok(())
+    }
+    
+    /// Set a value in the environment override layer
+    pub fn set_env_override(&mut self, key: &str, value: ConfigValue) {
+        self.env_overrides.insert(key.to_string(), value);
+        self.notify_watchers(key);
+    }
+    
+    /// Get all values in the environment override layer
+    pub fn env_overrides(&self) -> &HashMap<String, ConfigValue> {
+        &self.env_overrides
+    }
+    
+    /// Clear the environment override layer
+    pub fn clear_env_overrides(&mut self) {
+        let keys: Vec<String> = self.env_overrides.drain().map(|(key, _)| key).collect();
+        for key in keys {
+            self.notify_watchers(&key);
+        }
+    }
+    
+    /// Process the `include` directive of a loaded config file
+    ///
+    /// Included files are resolved relative to the including file and merged
+    /// in order, so later includes override earlier ones. Keys defined in the
+    /// including file itself always take precedence over included values.
+    /// Fails if the file is not valid JSON, since its includes could not
+    /// be found.
+    fn apply_includes(&mut self, path: &Path) -> CoreBaseResult<()> {
+        let root = read_config_file(path)?;
+        
+        let mut own = HashMap::new();
+        flatten_config_value("", &root, &mut own);
+        self.known_keys = own.keys().cloned().collect();
+        
+        let includes = include_paths(path, &root)?;
+        if includes.is_empty() {
+            return Ok(());
+        }
+        
+        let mut stack = vec![canonical_path(path)?];
+        let mut included = HashMap::new();
+        for include in includes {
+            included.extend(collect_included_values(&include, &mut stack)?);
+        }
+        
+        for (key, value) in included {
+            if !own.contains_key(&key) {
+                self.set(&key, value)?;
             }
         }
+        
+        Ok(())
     }
     
     /// Get a configuration value by key
+    #[track_caller]
     pub fn get(&mut self, key: &str) -> CoreBaseResult<ConfigValue> {
+        let caller = Location::caller();
+        let result = self.fetch(key);
+        
+        if self.audit.is_some() {
+            self.record_audit(ConfigOperation::Get, key, caller, None, result.as_ref().ok(), result.is_ok());
+        }
+        
+        result
+    }
+    
+    /// Read a value from the cache or the C++ store
+    fn fetch(&mut self, key: &str) -> CoreBaseResult<ConfigValue> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ConfigManager not initialized".to_string()
+                "ConfigManager not initialized".to_string(),
+                None,
             ));
         }
         
+        self.expire_if_due(key);
+        
+        // Environment overrides take precedence over stored values
+        if let Some(value) = self.env_overrides.get(key) {
+            return Ok(value.clone());
+        }
+        
+        match self.read_stored(key)? {
+            Some(value) => Ok(value),
+            None => self.missing_value(key),
+        }
+    }
+    
+    /// Read the stored value of a key from the cache, the backend or the
+    /// C++ store, caching it
+    fn read_stored(&mut self, key: &str) -> CoreBaseResult<Option<ConfigValue>> {
         // Check cache first
         if let Some(value) = self.cache.get(key) {
-            return Ok(value.clone());
+            return Ok(Some(value.clone()));
+        }
+        
+        if let Some(backend) = self.backend.as_ref() {
+            let value = backend.get(key)?;
+            if let Some(value) = &value {
+                self.cache.insert(key.to_string(), value.clone());
+            }
+            return Ok(value);
         }
         
         let c_key = to_c_string(key)?;
@@ -218,23 +606,72 @@ impl ConfigManager {
                 
                 // Cache the value
                 self.cache.insert(key.to_string(), config_value.clone());
-                Ok(config_value)
+                Ok(Some(config_value))
             } else {
-                Err(CoreBaseError::ConfigError(
-                    format!("Failed to get config value for key: {}", key)
-                ))
+                Ok(None)
             }
         }
     }
     
+    /// Resolve a key the store has no value for
+    ///
+    /// Falls back to the registered default, otherwise fails with a
+    /// `ConfigError` suggesting the closest known keys.
+    fn missing_value(&self, key: &str) -> CoreBaseResult<ConfigValue> {
+        if let Some(value) = self.defaults.get(key) {
+            return Ok(value.clone());
+        }
+        
+        let suggestions = suggest_keys(key, &self.keys());
+        let message = if suggestions.is_empty() {
+            format!("Failed to get config value for key: {}", key)
+        } else {
+            format!(
+                "Failed to get config value for key: {} (did you mean {}?)",
+                key,
+                suggestions.iter().map(|k| format!("'{}'", k)).collect::<Vec<_>>().join(", ")
+            )
+        };
+        Err(CoreBaseError::ConfigError(message, None))
+    }
+    
     /// Set a configuration value by key
+    #[track_caller]
     pub fn set(&mut self, key: &str, value: ConfigValue) -> CoreBaseResult<()> {
+        let caller = Location::caller();
+        if self.audit.is_none() {
+            return self.store(key, value);
+        }
+        
+        // Read the previous value straight from the store, which is not
+        // audited as a read
+        let old_value = if self.initialized { self.read_stored(key).ok().flatten() } else { None };
+        let new_value = value.clone();
+        let result = self.store(key, value);
+        self.record_audit(ConfigOperation::Set, key, caller, old_value.as_ref(), Some(&new_value), result.is_ok());
+        
+        result
+    }
+    
+    /// Validate a value and write it to the C++ store
+    fn store(&mut self, key: &str, value: ConfigValue) -> CoreBaseResult<()> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ConfigManager not initialized".to_string()
+                "ConfigManager not initialized".to_string(),
+                None,
             ));
         }
         
+        self.validate(key, &value)?;
+        self.expirations.remove(key);
+        
+        if let Some(backend) = self.backend.as_mut() {
+            backend.set(key, &value)?;
+            self.cache.insert(key.to_string(), value);
+            self.notify_watchers(key);
+            return Ok(());
+        }
+        
         let c_key = to_c_string(key)?;
         let value_str = config_value_to_json_string(&value)?;
         let c_value = to_c_string(&value_str)?;
@@ -244,39 +681,301 @@ impl ConfigManager {
             if result == 0 {
                 // Update cache
                 self.cache.insert(key.to_string(), value);
+                self.notify_watchers(key);
                 Ok(())
             } else {
                 Err(CoreBaseError::ConfigError(
-                    format!("Failed to set config value for key: {}", key)
-                ))
+                    format!("Failed to set config value for key: {}", key),
+                    None,
+                ).with_last_error())
             }
         }
     }
     
-    /// Save configuration to a file
-    pub fn save<P: AsRef<Path>>(&self, filename: P) -> CoreBaseResult<()> {
+    /// Set a configuration value that expires after `ttl`
+    ///
+    /// Once expired the value is removed from the store and `get()` falls
+    /// back to the registered default, or fails as for a missing key.
+    /// A later plain `set()` of the key clears the expiry.
+    #[track_caller]
+    pub fn set_with_ttl(&mut self, key: &str, value: ConfigValue, ttl: Duration) -> CoreBaseResult<()> {
+        self.set(key, value)?;
+        self.expirations.insert(key.to_string(), Instant::now() + ttl);
+        Ok(())
+    }
+    
+    /// Get the time left before a key expires, if it was set with a TTL
+    pub fn ttl_remaining(&self, key: &str) -> Option<Duration> {
+        self.expirations
+            .get(key)
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+    
+    /// Remove every value whose TTL has elapsed
+    pub fn purge_expired(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<String> = self.expirations
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        
+        for key in expired {
+            self.expire_if_due(&key);
+        }
+    }
+    
+    /// Remove a key if its TTL has elapsed, returning whether it expired
+    fn expire_if_due(&mut self, key: &str) -> bool {
+        match self.expirations.get(key) {
+            Some(deadline) if *deadline <= Instant::now() => {
+                self.expirations.remove(key);
+                if self.remove(key).is_err() {
+                    // Keep the stale value out of the cache even if the
+                    // store could not delete it
+                    self.cache.remove(key);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Remove a configuration value by key
+    pub fn remove(&mut self, key: &str) -> CoreBaseResult<()> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ConfigManager not initialized".to_string()
+                "ConfigManager not initialized".to_string(),
+                None,
             ));
         }
         
-        let filename_str = filename.as_ref().to_string_lossy();
-        let c_filename = to_c_string(&filename_str)?;
+        if let Some(backend) = self.backend.as_mut() {
+            backend.remove(key)?;
+            self.cache.remove(key);
+            self.known_keys.remove(key);
+            self.notify_watchers(key);
+            return Ok(());
+        }
+        
+        let c_key = to_c_string(key)?;
         
         unsafe {
-            let result = crate::cba_config_save(c_filename.as_ptr());
+            let result = crate::cba_config_remove_value(c_key.as_ptr());
             if result == 0 {
+                self.cache.remove(key);
+                self.known_keys.remove(key);
+                self.notify_watchers(key);
                 Ok(())
             } else {
                 Err(CoreBaseError::ConfigError(
-                    format!("Failed to save config file: {}", filename_str)
-                ))
+                    format!("Failed to remove config value for key: {}", key),
+                    None,
+                ).with_last_error())
+            }
+        }
+    }
+    
+    /// Register a validation callback for a key
+    ///
+    /// The callback runs on every `set()` of the key; if it returns an error
+    /// the write is rejected with a `ConfigError` describing the failure.
+    /// Multiple callbacks may be registered for the same key.
+    pub fn on_set<F, T, E>(&mut self, key: &str, validator: F)
+    where
+        F: Fn(&ConfigValue) -> Result<T, E> + Send + Sync + 'static,
+        E: fmt::Display,
+    {
+        self.validators
+            .entry(key.to_string())
+            .or_default()
+            .push(Box::new(move |value| {
+                validator(value).map(|_| ()).map_err(|e| e.to_string())
+            }));
+    }
+    
+    /// Remove all validation callbacks registered for a key
+    pub fn clear_validators(&mut self, key: &str) {
+        self.validators.remove(key);
+    }
+    
+    /// Run the validation callbacks registered for a key
+    fn validate(&self, key: &str, value: &ConfigValue) -> CoreBaseResult<()> {
+        if let Some(validators) = self.validators.get(key) {
+            for validator in validators {
+                validator(value).map_err(|e| CoreBaseError::ConfigError(
+                    format!("Invalid value for config key '{}': {}", key, e),
+                    None,
+                ))?;
             }
         }
+        Ok(())
+    }
+    
+    /// Watch a single key for changes to its effective value
+    ///
+    /// The callback receives the new value (`None` if the key was removed)
+    /// and fires only when the value actually differs from the last one
+    /// observed, whether the change came from `set()`, `remove()`, a reload
+    /// or the environment override layer.
+    pub fn watch_key<F>(&mut self, key: &str, callback: F)
+    where
+        F: Fn(Option<&ConfigValue>) + Send + Sync + 'static,
+    {
+        let last = self.fetch(key).ok();
+        self.watchers
+            .entry(key.to_string())
+            .or_default()
+            .push(KeyWatcher {
+                callback: Box::new(callback),
+                last,
+            });
+    }
+    
+    /// Remove all watchers registered for a key
+    pub fn unwatch_key(&mut self, key: &str) {
+        self.watchers.remove(key);
+    }
+    
+    /// Re-read a watched key and fire its watchers if the value changed
+    fn notify_watchers(&mut self, key: &str) {
+        if !self.watchers.contains_key(key) {
+            return;
+        }
+        
+        let current = self.fetch(key).ok();
+        if let Some(watchers) = self.watchers.get_mut(key) {
+            for watcher in watchers.iter_mut() {
+                if watcher.last != current {
+                    watcher.last = current.clone();
+                    (watcher.callback)(current.as_ref());
+                }
+            }
+        }
+    }
+    
+    /// Re-check every watched key, e.g. after a reload
+    fn notify_all_watchers(&mut self) {
+        let keys: Vec<String> = self.watchers.keys().cloned().collect();
+        for key in keys {
+            self.notify_watchers(&key);
+        }
+    }
+    
+    /// Enable audit mode, reporting every `get`/`set` to the given sink
+    pub fn enable_audit(&mut self, sink: AuditSink) {
+        self.audit = Some(sink);
+    }
+    
+    /// Disable audit mode
+    pub fn disable_audit(&mut self) {
+        self.audit = None;
+    }
+    
+    /// Check if audit mode is enabled
+    pub fn is_audit_enabled(&self) -> bool {
+        self.audit.is_some()
+    }
+    
+    /// Mark keys containing `pattern` (case-insensitive) as secret
+    ///
+    /// Values of secret keys are redacted in audit events.
+    pub fn add_secret_pattern(&mut self, pattern: &str) {
+        self.secret_patterns.push(pattern.to_lowercase());
+    }
+    
+    /// Check if a key holds a secret value
+    pub fn is_secret_key(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        self.secret_patterns.iter().any(|pattern| key.contains(pattern.as_str()))
+    }
+    
+    /// Build an audit event and deliver it to the configured sink
+    fn record_audit(
+        &self,
+        operation: ConfigOperation,
+        key: &str,
+        caller: &Location<'_>,
+        old_value: Option<&ConfigValue>,
+        new_value: Option<&ConfigValue>,
+        success: bool,
+    ) {
+        let sink = match &self.audit {
+            Some(sink) => sink,
+            None => return,
+        };
+        
+        let secret = self.is_secret_key(key);
+        let render = |value: &ConfigValue| {
+            if secret {
+                REDACTED.to_string()
+            } else {
+                config_value_to_json_string(value).unwrap_or_else(|_| format!("{:?}", value))
+            }
+        };
+        
+        let event = ConfigAuditEvent {
+            operation,
+            key: key.to_string(),
+            caller: caller.to_string(),
+            old_value: old_value.map(render),
+            new_value: new_value.map(render),
+            success,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        
+        match sink {
+            AuditSink::ErrorHandler(level) => {
+                if let Ok(handler) = ErrorHandler::new() {
+                    let _ = handler.log(*level, &event.to_string());
+                }
+            }
+            AuditSink::Custom(callback) => callback(&event),
+        }
+    }
+    
+    /// Save configuration to a file
+    pub fn save<P: AsRef<Path>>(&self, filename: P) -> CoreBaseResult<()> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "ConfigManager not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let path = filename.as_ref();
+        if ConfigFormat::from_path(path) == ConfigFormat::Ini {
+            let values = match self.backend.as_ref() {
+                Some(backend) => backend.load_all()?,
+                None => {
+                    // The C++ store can only be dumped as JSON; convert the dump
+                    let transfer = TransferFile::new();
+                    self.native_save(transfer.path())?;
+                    let dump = read_config_file(transfer.path())?;
+                    
+                    let mut values = HashMap::new();
+                    flatten_config_value("", &dump, &mut values);
+                    values
+                }
+            };
+            return write_config_file(path, &write_ini(&values));
+        }
+        
+        if let Some(backend) = self.backend.as_ref() {
+            let tree = unflatten_config_values(&backend.load_all()?);
+            let contents = serde_json::to_string_pretty(&config_value_to_json(&tree))
+                .map_err(|e| CoreBaseError::ConfigError(format!("JSON serialization error: {}", e), None))?;
+            return write_config_file(path, &contents);
+        }
+        
+        self.native_save(path)
     }
     
     /// Get a string value with default
+    #[track_caller]
     pub fn get_string(&mut self, key: &str, default: &str) -> String {
         self.get(key)
             .ok()
@@ -285,6 +984,7 @@ impl ConfigManager {
     }
     
     /// Get an integer value with default
+    #[track_caller]
     pub fn get_integer(&mut self, key: &str, default: i64) -> i64 {
         self.get(key)
             .ok()
@@ -293,6 +993,7 @@ impl ConfigManager {
     }
     
     /// Get a float value with default
+    #[track_caller]
     pub fn get_float(&mut self, key: &str, default: f64) -> f64 {
         self.get(key)
             .ok()
@@ -300,7 +1001,27 @@ impl ConfigManager {
             .unwrap_or(default)
     }
     
+    /// Get binary data stored under a key
+    ///
+    /// Values read back from the C++ store are base64 strings and are
+    /// decoded transparently.
+    #[track_caller]
+    pub fn get_bytes(&mut self, key: &str) -> CoreBaseResult<Vec<u8>> {
+        match self.get(key)? {
+            ConfigValue::Bytes(b) => Ok(b),
+            ConfigValue::String(s) => BASE64.decode(s.as_bytes()).map_err(|e| CoreBaseError::ConfigError(
+                format!("Invalid base64 data for config key '{}': {}", key, e),
+                None,
+            )),
+            _ => Err(CoreBaseError::ConfigError(
+                format!("Config value for key '{}' is not binary data", key),
+                None,
+            )),
+        }
+    }
+    
     /// Get a boolean value with default
+    #[track_caller]
     pub fn get_boolean(&mut self, key: &str, default: bool) -> bool {
         self.get(key)
             .ok()
@@ -309,6 +1030,7 @@ impl ConfigManager {
     }
     
     /// Check if a key exists in the configuration
+    #[track_caller]
     pub fn has_key(&mut self, key: &str) -> bool {
         self.get(key).is_ok()
     }
@@ -322,6 +1044,52 @@ impl ConfigManager {
     pub fn get_cached_keys(&self) -> Vec<String> {
         self.cache.keys().cloned().collect()
     }
+    
+    /// Register default values consulted when the C++ store has no value
+    ///
+    /// Later registrations override earlier defaults for the same key.
+    pub fn register_defaults(&mut self, defaults: HashMap<String, ConfigValue>) {
+        let keys: Vec<String> = defaults.keys().cloned().collect();
+        self.defaults.extend(defaults);
+        
+        for key in keys {
+            self.notify_watchers(&key);
+        }
+    }
+    
+    /// Get the registered default for a key
+    pub fn get_default(&self, key: &str) -> Option<&ConfigValue> {
+        self.defaults.get(key)
+    }
+    
+    /// Get all registered defaults
+    pub fn defaults(&self) -> &HashMap<String, ConfigValue> {
+        &self.defaults
+    }
+    
+    /// Get all keys known on the Rust side, sorted
+    ///
+    /// This covers the keys of the last loaded file (including its includes),
+    /// every key read or written through this manager and all registered
+    /// defaults.
+    pub fn keys(&self) -> Vec<String> {
+        let stored = self.backend
+            .as_ref()
+            .and_then(|backend| backend.keys().ok())
+            .unwrap_or_default();
+        
+        let mut keys: Vec<String> = self.known_keys
+            .iter()
+            .chain(self.cache.keys())
+            .chain(self.defaults.keys())
+            .chain(self.env_overrides.keys())
+            .chain(stored.iter())
+            .cloned()
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
 }
 
 impl Default for ConfigManager {
@@ -329,12 +1097,275 @@ impl Default for ConfigManager {
         Self::new().unwrap_or(ConfigManager {
             initialized: false,
             cache: HashMap::new(),
+            validators: HashMap::new(),
+            audit: None,
+            secret_patterns: DEFAULT_SECRET_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            known_keys: HashSet::new(),
+            watchers: HashMap::new(),
+            defaults: HashMap::new(),
+            backend: None,
+            expirations: HashMap::new(),
+            env_overrides: HashMap::new(),
         })
     }
 }
 
+/// Scoped override of a configuration value, intended for unit tests
+///
+/// The override is written to the shared C++ store when the guard is created
+/// and the previous value is restored (or the key removed, if it did not
+/// exist) when the guard is dropped. Other `ConfigManager` instances that
+/// already cached the key should call `clear_cache()` to observe the override.
+#[must_use = "the override is reverted as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ConfigOverrideGuard {
+    manager: ConfigManager,
+    key: String,
+    previous: Option<ConfigValue>,
+}
+
+impl ConfigOverrideGuard {
+    /// Override `key` with `value` until the returned guard is dropped
+    pub fn set<V: Into<ConfigValue>>(key: &str, value: V) -> CoreBaseResult<Self> {
+        let mut manager = ConfigManager::new()?;
+        let previous = manager.get(key).ok();
+        manager.set(key, value.into())?;
+        
+        Ok(ConfigOverrideGuard {
+            manager,
+            key: key.to_string(),
+            previous,
+        })
+    }
+    
+    /// Get the overridden key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    
+    /// Get the value that will be restored on drop
+    pub fn previous(&self) -> Option<&ConfigValue> {
+        self.previous.as_ref()
+    }
+}
+
+impl Drop for ConfigOverrideGuard {
+    fn drop(&mut self) {
+        let _ = match self.previous.take() {
+            Some(value) => self.manager.set(&self.key, value),
+            None => self.manager.remove(&self.key),
+        };
+    }
+}
+
+/// Maximum number of suggestions offered for a missing key
+const MAX_KEY_SUGGESTIONS: usize = 3;
+
+/// Find the known keys closest to a missing key by edit distance
+fn suggest_keys(key: &str, known_keys: &[String]) -> Vec<String> {
+    let max_distance = (key.chars().count() / 3).max(2);
+    
+    let mut candidates: Vec<(usize, &String)> = known_keys
+        .iter()
+        .filter(|k| k.as_str() != key)
+        .map(|k| (levenshtein_distance(key, k), k))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort();
+    
+    candidates
+        .into_iter()
+        .take(MAX_KEY_SUGGESTIONS)
+        .map(|(_, k)| k.clone())
+        .collect()
+}
+
+/// Compute the Levenshtein edit distance between two strings
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+    
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    
+    previous[b_chars.len()]
+}
+
+/// Top-level key listing files to include into a config file
+const INCLUDE_KEY: &str = "include";
+
+/// Read and parse a JSON config file on the Rust side
+fn read_config_file(path: &Path) -> CoreBaseResult<ConfigValue> {
+    let contents = fs::read_to_string(path).map_err(|e| CoreBaseError::ConfigError(
+        format!("Failed to read config file {}: {}", path.display(), e),
+        None,
+    ))?;
+    let json_value = serde_json::from_str::<serde_json::Value>(&contents).map_err(|e| {
+        CoreBaseError::ConfigError(format!("Failed to parse config file {}: {}", path.display(), e), None)
+    })?;
+    Ok(json_to_config_value(json_value))
+}
+
+/// Write a config file from the Rust side
+fn write_config_file(path: &Path, contents: &str) -> CoreBaseResult<()> {
+    fs::write(path, contents).map_err(|e| CoreBaseError::ConfigError(
+        format!("Failed to save config file {}: {}", path.display(), e),
+        None,
+    ))
+}
+
+/// Temporary JSON file used to exchange non-JSON formats with the C++ store
+///
+/// Lives in the system temp directory under a unique name, so config files
+/// in read-only directories still load and concurrent transfers never share
+/// a file. Removed when dropped.
+struct TransferFile(PathBuf);
+
+impl TransferFile {
+    fn new() -> Self {
+        TransferFile(unique_path(&std::env::temp_dir(), "cba-transfer", ".json"))
+    }
+    
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TransferFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Canonicalize a config file path for cycle detection
+fn canonical_path(path: &Path) -> CoreBaseResult<PathBuf> {
+    fs::canonicalize(path).map_err(|e| CoreBaseError::ConfigError(
+        format!("Failed to resolve config file {}: {}", path.display(), e),
+        None,
+    ))
+}
+
+/// Get the files listed in the `include` directive, resolved relative to `path`
+fn include_paths(path: &Path, root: &ConfigValue) -> CoreBaseResult<Vec<PathBuf>> {
+    let entries = match root.as_object().and_then(|obj| obj.get(INCLUDE_KEY)) {
+        None => return Ok(Vec::new()),
+        Some(ConfigValue::String(s)) => vec![s.clone()],
+        Some(ConfigValue::Array(arr)) => arr
+            .iter()
+            .map(|v| match v {
+                ConfigValue::String(s) => Ok(s.clone()),
+                _ => Err(CoreBaseError::ConfigError(
+                    format!("Invalid include entry in {}: expected a file name", path.display()),
+                    None,
+                )),
+            })
+            .collect::<CoreBaseResult<Vec<_>>>()?,
+        Some(_) => {
+            return Err(CoreBaseError::ConfigError(
+                format!("Invalid include directive in {}: expected a string or array", path.display()),
+                None,
+            ));
+        }
+    };
+    
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(entries.into_iter().map(|entry| base_dir.join(entry)).collect())
+}
+
+/// Recursively collect the flattened values of an included file
+///
+/// `stack` holds the canonical paths of the files currently being included
+/// and is used to reject include cycles.
+fn collect_included_values(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> CoreBaseResult<HashMap<String, ConfigValue>> {
+    let canonical = canonical_path(path)?;
+    if stack.contains(&canonical) {
+        let chain: Vec<String> = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(CoreBaseError::ConfigError(
+            format!("Include cycle detected: {}", chain.join(" -> ")),
+            None,
+        ));
+    }
+    
+    let root = read_config_file(path)?;
+    stack.push(canonical);
+    
+    let mut values = HashMap::new();
+    for include in include_paths(path, &root)? {
+        values.extend(collect_included_values(&include, stack)?);
+    }
+    flatten_config_value("", &root, &mut values);
+    
+    stack.pop();
+    Ok(values)
+}
+
+/// Flatten nested objects into dotted keys, matching the C++ loader
+fn flatten_config_value(prefix: &str, value: &ConfigValue, out: &mut HashMap<String, ConfigValue>) {
+    if let ConfigValue::Object(obj) = value {
+        for (k, v) in obj {
+            if prefix.is_empty() && k == INCLUDE_KEY {
+                continue;
+            }
+            let key = if prefix.is_empty() { k.clone() } else { format!("{}.{}", prefix, k) };
+            match v {
+                ConfigValue::Object(_) => flatten_config_value(&key, v, out),
+                _ => {
+                    out.insert(key, v.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Expand dotted keys into nested objects, the inverse of `flatten_config_value`
+fn unflatten_config_values(values: &HashMap<String, ConfigValue>) -> ConfigValue {
+    let mut root = HashMap::new();
+    
+    let mut keys: Vec<&String> = values.keys().collect();
+    keys.sort();
+    
+    for key in keys {
+        let mut parts: Vec<&str> = key.split('.').collect();
+        let last = parts.pop().unwrap_or_default();
+        
+        let mut node = &mut root;
+        for part in parts {
+            let entry = node
+                .entry(part.to_string())
+                .or_insert_with(|| ConfigValue::Object(HashMap::new()));
+            if !matches!(entry, ConfigValue::Object(_)) {
+                *entry = ConfigValue::Object(HashMap::new());
+            }
+            node = match entry {
+                ConfigValue::Object(obj) => obj,
+                _ => unreachable!(),
+            };
+        }
+        
+        node.insert(last.to_string(), values[key].clone());
+    }
+    
+    ConfigValue::Object(root)
+}
+
 /// Convert serde_json::Value to ConfigValue
-fn json_to_config_value(json: serde_json::Value) -> ConfigValue {
+pub(crate) fn json_to_config_value(json: serde_json::Value) -> ConfigValue {
     match json {
         serde_json::Value::Null => ConfigValue::Null,
         serde_json::Value::Bool(b) => ConfigValue::Boolean(b),
@@ -362,10 +1393,10 @@ fn json_to_config_value(json: serde_json::Value) -> ConfigValue {
 }
 
 /// Convert ConfigValue to JSON string
-fn config_value_to_json_string(value: &ConfigValue) -> CoreBaseResult<String> {
+pub(crate) fn config_value_to_json_string(value: &ConfigValue) -> CoreBaseResult<String> {
     let json_value = config_value_to_json(value);
     serde_json::to_string(&json_value)
-        .map_err(|e| CoreBaseError::ConfigError(format!("JSON serialization error: {}", e)))
+        .map_err(|e| CoreBaseError::ConfigError(format!("JSON serialization error: {}", e), None))
 }
 
 /// Convert ConfigValue to serde_json::Value
@@ -378,6 +1409,7 @@ fn config_value_to_json(value: &ConfigValue) -> serde_json::Value {
             serde_json::Value::Number(serde_json::Number::from_f64(*f).unwrap_or_else(|| 0.into()))
         },
         ConfigValue::String(s) => serde_json::Value::String(s.clone()),
+        ConfigValue::Bytes(b) => serde_json::Value::String(BASE64.encode(b)),
         ConfigValue::Array(arr) => {
             serde_json::Value::Array(arr.iter().map(config_value_to_json).collect())
         },
@@ -391,6 +1423,38 @@ fn config_value_to_json(value: &ConfigValue) -> serde_json::Value {
     }
 }
 
+/// Read an optional key for a `from_config` constructor
+pub(crate) fn config_entry(config: &mut ConfigManager, key: &str) -> Option<ConfigValue> {
+    config.get(key).ok()
+}
+
+/// Require a config value to be a string
+pub(crate) fn config_string(key: &str, value: &ConfigValue) -> CoreBaseResult<String> {
+    value.as_string().ok_or_else(|| CoreBaseError::ConfigError(
+        format!("Config key '{}' must be a string", key),
+        None,
+    ))
+}
+
+/// Require a config value to be a boolean
+pub(crate) fn config_bool(key: &str, value: &ConfigValue) -> CoreBaseResult<bool> {
+    value.as_boolean().ok_or_else(|| CoreBaseError::ConfigError(
+        format!("Config key '{}' must be a boolean", key),
+        None,
+    ))
+}
+
+/// Require a config value to be an integer that fits in `T`
+pub(crate) fn config_number<T: TryFrom<i64>>(key: &str, value: &ConfigValue) -> CoreBaseResult<T> {
+    value
+        .as_integer()
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| CoreBaseError::ConfigError(
+            format!("Config key '{}' must be a non-negative integer in range", key),
+            None,
+        ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,6 +1494,25 @@ mod tests {
         assert_eq!(ConfigValue::from(true), ConfigValue::Boolean(true));
     }
     
+    #[test]
+    fn test_bytes_values() {
+        let bytes = ConfigValue::from(vec![0u8, 1, 2, 255]);
+        assert_eq!(config_value_to_json_string(&bytes).unwrap(), "\"AAEC/w==\"");
+        assert_eq!(serde_json::to_string(&bytes).unwrap(), "\"AAEC/w==\"");
+        
+        // Values read back through the FFI arrive as base64 strings
+        let from_ffi = ConfigValue::String("AAEC/w==".to_string());
+        assert_eq!(from_ffi.as_bytes(), Some(vec![0u8, 1, 2, 255]));
+        assert_eq!(bytes.as_bytes(), Some(vec![0u8, 1, 2, 255]));
+        assert_eq!(ConfigValue::Integer(1).as_bytes(), None);
+        
+        let mut manager = ConfigManager::new().unwrap();
+        manager.cache.insert("tls.cert".to_string(), from_ffi);
+        manager.cache.insert("tls.host".to_string(), ConfigValue::from("not base64!"));
+        assert_eq!(manager.get_bytes("tls.cert").unwrap(), vec![0u8, 1, 2, 255]);
+        assert!(manager.get_bytes("tls.host").is_err());
+    }
+    
     #[test]
     fn test_json_conversion() {
         let config_val = ConfigValue::Object({
@@ -453,6 +1536,380 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_set_validation() {
+        let mut manager = ConfigManager::new().unwrap();
+        manager.on_set("server.port", |v| {
+            v.as_integer()
+                .filter(|p| *p > 0 && *p < 65536)
+                .ok_or("port must be between 1 and 65535")
+        });
+        
+        match manager.set("server.port", ConfigValue::Integer(70000)) {
+            Err(CoreBaseError::ConfigError(msg, _)) => {
+                assert!(msg.contains("server.port"));
+                assert!(msg.contains("port must be between 1 and 65535"));
+            }
+            other => panic!("Expected validation error, got {:?}", other),
+        }
+        assert!(manager.get_cached_keys().is_empty());
+        
+        assert!(manager.validate("server.port", &ConfigValue::Integer(8080)).is_ok());
+        assert!(manager.validate("server.host", &ConfigValue::Integer(70000)).is_ok());
+        
+        manager.clear_validators("server.port");
+        assert!(manager.validate("server.port", &ConfigValue::Integer(70000)).is_ok());
+    }
+    
+    #[test]
+    fn test_config_audit() {
+        use std::sync::{Arc, Mutex};
+        
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = Arc::clone(&events);
+        
+        let mut manager = ConfigManager::new().unwrap();
+        manager.enable_audit(AuditSink::Custom(Box::new(move |event| {
+            sink_events.lock().unwrap().push(event.clone());
+        })));
+        assert!(manager.is_audit_enabled());
+        
+        let caller = Location::caller();
+        manager.record_audit(
+            ConfigOperation::Set,
+            "server.port",
+            caller,
+            Some(&ConfigValue::Integer(80)),
+            Some(&ConfigValue::Integer(443)),
+            true,
+        );
+        manager.record_audit(
+            ConfigOperation::Get,
+            "database.password",
+            caller,
+            None,
+            Some(&ConfigValue::from("hunter2")),
+            true,
+        );
+        
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].old_value.as_deref(), Some("80"));
+        assert_eq!(events[0].new_value.as_deref(), Some("443"));
+        assert!(events[0].caller.contains("config.rs"));
+        assert!(events[0].to_string().contains("80 -> 443"));
+        assert_eq!(events[1].new_value.as_deref(), Some(REDACTED));
+        assert!(!events[1].to_string().contains("hunter2"));
+    }
+    
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_audit_set_reads_stored_value() {
+        use std::sync::{Arc, Mutex};
+        
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ConfigManager::with_store(Store::Sqlite(dir.path().join("config.db"))).unwrap();
+        manager.set("server.port", ConfigValue::Integer(80)).unwrap();
+        manager.cache.clear();
+        
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = Arc::clone(&events);
+        manager.enable_audit(AuditSink::Custom(Box::new(move |event| {
+            sink_events.lock().unwrap().push(event.clone());
+        })));
+        manager.set("server.port", ConfigValue::Integer(443)).unwrap();
+        
+        // One set event carrying the stored value, and no read event
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].operation, ConfigOperation::Set);
+        assert_eq!(events[0].old_value.as_deref(), Some("80"));
+        assert_eq!(events[0].new_value.as_deref(), Some("443"));
+    }
+    
+    #[test]
+    fn test_secret_patterns() {
+        let mut manager = ConfigManager::new().unwrap();
+        assert!(manager.is_secret_key("auth.API_KEY"));
+        assert!(!manager.is_secret_key("server.host"));
+        
+        manager.add_secret_pattern("Host");
+        assert!(manager.is_secret_key("server.host"));
+    }
+    
+    #[test]
+    fn test_json_string_round_trip() {
+        let mut manager = ConfigManager::from_json_str(
+            r#"{"server": {"host": "localhost", "port": 8080}, "debug": true}"#
+        ).unwrap();
+        
+        let mut keys = manager.get_cached_keys();
+        keys.sort();
+        assert_eq!(keys, vec!["debug", "server.host", "server.port"]);
+        
+        let exported: serde_json::Value = serde_json::from_str(&manager.to_json_string().unwrap()).unwrap();
+        assert_eq!(exported, serde_json::json!({
+            "server": {"host": "localhost", "port": 8080},
+            "debug": true
+        }));
+        
+        // Values live in the memory store, not the C++ store
+        manager.cache.clear();
+        assert_eq!(manager.get("server.port").unwrap(), ConfigValue::Integer(8080));
+        assert!(matches!(manager.get("server.missing"), Err(CoreBaseError::ConfigError(..))));
+        manager.set("server.port", ConfigValue::Integer(9090)).unwrap();
+        manager.remove("debug").unwrap();
+        let exported: serde_json::Value = serde_json::from_str(&manager.to_json_string().unwrap()).unwrap();
+        assert_eq!(exported, serde_json::json!({"server": {"host": "localhost", "port": 9090}}));
+        
+        assert!(ConfigManager::from_json_str("[1, 2, 3]").is_err());
+        assert!(ConfigManager::from_json_str("{not json").is_err());
+    }
+    
+    #[test]
+    fn test_key_suggestions() {
+        assert_eq!(levenshtein_distance("server.port", "server.port"), 0);
+        assert_eq!(levenshtein_distance("server.prot", "server.port"), 2);
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+        
+        let known = vec![
+            "server.port".to_string(),
+            "server.host".to_string(),
+            "monitor.update_interval".to_string(),
+        ];
+        assert_eq!(suggest_keys("server.prot", &known)[0], "server.port");
+        assert_eq!(suggest_keys("monitor.update_intreval", &known), vec!["monitor.update_interval"]);
+        assert!(suggest_keys("database.url", &known).is_empty());
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_config_override_guard() {
+        let guard = ConfigOverrideGuard::set("test.override", 42).unwrap();
+        assert_eq!(guard.key(), "test.override");
+        assert!(guard.previous().is_none());
+        assert_eq!(ConfigManager::new().unwrap().get("test.override").unwrap(), ConfigValue::Integer(42));
+        drop(guard);
+        assert!(ConfigManager::new().unwrap().get("test.override").is_err());
+        
+        ConfigManager::new().unwrap().set("test.override.restored", ConfigValue::Integer(1)).unwrap();
+        let guard = ConfigOverrideGuard::set("test.override.restored", 2).unwrap();
+        assert_eq!(guard.previous(), Some(&ConfigValue::Integer(1)));
+        assert_eq!(ConfigManager::new().unwrap().get("test.override.restored").unwrap(), ConfigValue::Integer(2));
+        drop(guard);
+        assert_eq!(ConfigManager::new().unwrap().get("test.override.restored").unwrap(), ConfigValue::Integer(1));
+    }
+    
+    #[test]
+    fn test_watch_key() {
+        use std::sync::{Arc, Mutex};
+        
+        let mut manager = ConfigManager::from_json_str(r#"{"monitor": {"update_interval": 1000}}"#).unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let watcher_seen = Arc::clone(&seen);
+        manager.watch_key("monitor.update_interval", move |value| {
+            watcher_seen.lock().unwrap().push(value.cloned());
+        });
+        
+        // Unchanged value does not fire
+        manager.notify_watchers("monitor.update_interval");
+        assert!(seen.lock().unwrap().is_empty());
+        
+        manager.cache.insert("monitor.update_interval".to_string(), ConfigValue::Integer(500));
+        manager.notify_watchers("monitor.update_interval");
+        manager.notify_all_watchers();
+        assert_eq!(*seen.lock().unwrap(), vec![Some(ConfigValue::Integer(500))]);
+        
+        manager.unwatch_key("monitor.update_interval");
+        manager.cache.insert("monitor.update_interval".to_string(), ConfigValue::Integer(250));
+        manager.notify_all_watchers();
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_register_defaults() {
+        let mut manager = ConfigManager::from_json_str(r#"{"server": {"port": 9090}}"#).unwrap();
+        
+        let mut defaults = HashMap::new();
+        defaults.insert("server.port".to_string(), ConfigValue::Integer(8080));
+        defaults.insert("server.host".to_string(), ConfigValue::from("localhost"));
+        manager.register_defaults(defaults);
+        
+        assert_eq!(manager.get_default("server.host"), Some(&ConfigValue::from("localhost")));
+        assert_eq!(manager.get_integer("server.port", 0), 9090);
+        assert_eq!(manager.keys(), vec!["server.host", "server.port"]);
+        assert_eq!(manager.defaults().len(), 2);
+    }
+    
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_backed_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("config.db");
+        let file = dir.path().join("config.json");
+        fs::write(&file, r#"{"server": {"host": "localhost", "port": 8080}}"#).unwrap();
+        
+        let mut manager = ConfigManager::with_store(Store::Sqlite(db.clone())).unwrap();
+        manager.load(&file).unwrap();
+        manager.set("server.port", ConfigValue::Integer(9090)).unwrap();
+        assert_eq!(manager.history("server.port").unwrap().len(), 2);
+        
+        // Values persist across managers
+        let mut reopened = ConfigManager::with_store(Store::Sqlite(db)).unwrap();
+        assert_eq!(reopened.get_integer("server.port", 0), 9090);
+        assert_eq!(reopened.keys(), vec!["server.host", "server.port"]);
+        
+        reopened.remove("server.host").unwrap();
+        assert!(reopened.get("server.host").is_err());
+        
+        let exported = dir.path().join("exported.json");
+        reopened.save(&exported).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&exported).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({"server": {"port": 9090}}));
+    }
+    
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_ini_load_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let ini = dir.path().join("legacy.ini");
+        fs::write(&ini, "[server]\nhost = localhost\nport = 8080\n").unwrap();
+        
+        let mut manager = ConfigManager::with_store(Store::Sqlite(dir.path().join("config.db"))).unwrap();
+        manager.load(&ini).unwrap();
+        assert_eq!(manager.get_integer("server.port", 0), 8080);
+        
+        manager.set("server.port", ConfigValue::Integer(9090)).unwrap();
+        let saved = dir.path().join("saved.ini");
+        manager.save(&saved).unwrap();
+        assert_eq!(fs::read_to_string(&saved).unwrap(), "[server]\nhost = localhost\nport = 9090\n");
+    }
+    
+    #[test]
+    fn test_ini_transfer_file() {
+        let values = parse_ini("[server]\nhost = localhost\nport = 8080\n").unwrap();
+        let json = config_value_to_json_string(&unflatten_config_values(&values)).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(document, serde_json::json!({"server": {"host": "localhost", "port": 8080}}));
+        
+        // Concurrent transfers never share a file, and dropping removes it
+        let first = TransferFile::new();
+        let second = TransferFile::new();
+        assert_ne!(first.path(), second.path());
+        assert_eq!(first.path().parent(), Some(std::env::temp_dir().as_path()));
+        write_config_file(first.path(), &json).unwrap();
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_ini_native_transfer() {
+        let dir = tempfile::tempdir().unwrap();
+        let ini = dir.path().join("legacy.ini");
+        fs::write(&ini, "[server]\nhost = localhost\nport = 8080\n").unwrap();
+        
+        let mut manager = ConfigManager::new().unwrap();
+        manager.load(&ini).unwrap();
+        assert!(manager.known_keys.contains("server.port"));
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(files, ["legacy.ini"]);
+    }
+    
+    #[test]
+    fn test_ttl_expiry() {
+        let mut manager = ConfigManager::from_json_str(r#"{"feature": {"beta": true}}"#).unwrap();
+        manager.expirations.insert("feature.beta".to_string(), Instant::now() + Duration::from_secs(60));
+        assert!(manager.ttl_remaining("feature.beta").unwrap() > Duration::from_secs(50));
+        assert!(!manager.expire_if_due("feature.beta"));
+        assert_eq!(manager.cache.get("feature.beta"), Some(&ConfigValue::Boolean(true)));
+        
+        manager.expirations.insert("feature.beta".to_string(), Instant::now());
+        manager.purge_expired();
+        assert!(manager.ttl_remaining("feature.beta").is_none());
+        assert!(!manager.cache.contains_key("feature.beta"));
+    }
+    
+    #[test]
+    fn test_load_dotenv() {
+        use std::sync::{Arc, Mutex};
+        
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join(".env");
+        fs::write(&env_file, "SERVER__PORT=9090\nexport API_TOKEN='abc'\n").unwrap();
+        
+        let mut manager = ConfigManager::from_json_str(r#"{"server": {"port": 8080}}"#).unwrap();
+        let fired = Arc::new(Mutex::new(0));
+        let watcher_fired = Arc::clone(&fired);
+        manager.watch_key("server.port", move |_| *watcher_fired.lock().unwrap() += 1);
+        
+        manager.load_dotenv(&env_file).unwrap();
+        assert_eq!(manager.get_integer("server.port", 0), 9090);
+        assert_eq!(manager.get_string("api_token", ""), "abc");
+        assert_eq!(*fired.lock().unwrap(), 1);
+        
+        manager.clear_env_overrides();
+        assert!(manager.env_overrides().is_empty());
+        assert_eq!(manager.get_integer("server.port", 0), 8080);
+        assert_eq!(*fired.lock().unwrap(), 2);
+    }
+    
+    #[test]
+    fn test_include_merging() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("conf.d")).unwrap();
+        fs::write(
+            dir.path().join("conf.d").join("common.json"),
+            r#"{"server": {"host": "0.0.0.0", "port": 80}, "log": "info"}"#,
+        ).unwrap();
+        fs::write(
+            dir.path().join("conf.d").join("secrets.json"),
+            r#"{"server": {"port": 443}, "api_key": "secret"}"#,
+        ).unwrap();
+        let main = dir.path().join("main.json");
+        fs::write(
+            &main,
+            r#"{"include": ["conf.d/common.json", "conf.d/secrets.json"], "server": {"port": 8443}}"#,
+        ).unwrap();
+        
+        // The native loader already stored the main file's own keys, so
+        // only included values are written
+        let mut manager = ConfigManager::with_store(Store::Memory).unwrap();
+        manager.apply_includes(&main).unwrap();
+        assert_eq!(manager.get("server.host").unwrap(), ConfigValue::String("0.0.0.0".to_string()));
+        assert_eq!(manager.get("api_key").unwrap(), ConfigValue::String("secret".to_string()));
+        assert_eq!(manager.get("log").unwrap(), ConfigValue::String("info".to_string()));
+        assert!(manager.get("server.port").is_err());
+        assert!(manager.known_keys.contains("server.port"));
+        assert!(manager.get(INCLUDE_KEY).is_err());
+        
+        let mut manager = ConfigManager::with_store(Store::Memory).unwrap();
+        manager.load(&main).unwrap();
+        assert_eq!(manager.get("server.port").unwrap(), ConfigValue::Integer(8443));
+        assert_eq!(manager.get("server.host").unwrap(), ConfigValue::String("0.0.0.0".to_string()));
+        
+        let broken = dir.path().join("broken.json");
+        fs::write(&broken, "{not json").unwrap();
+        assert!(matches!(manager.apply_includes(&broken), Err(CoreBaseError::ConfigError(..))));
+    }
+    
+    #[test]
+    fn test_include_cycle_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.json");
+        let b = dir.path().join("b.json");
+        fs::write(&a, r#"{"include": "b.json", "a": 1}"#).unwrap();
+        fs::write(&b, r#"{"include": ["a.json"], "b": 2}"#).unwrap();
+        
+        let mut stack = Vec::new();
+        let result = collect_included_values(&a, &mut stack);
+        match result {
+            Err(CoreBaseError::ConfigError(msg, _)) => assert!(msg.contains("Include cycle detected")),
+            other => panic!("Expected include cycle error, got {:?}", other),
+        }
+    }
+    
     #[test]
     fn test_default_config_manager() {
         let manager = ConfigManager::default();
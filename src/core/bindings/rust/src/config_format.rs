@@ -0,0 +1,411 @@
+//! Configuration file formats for CoreBase Rust bindings
+//!
+//! The C++ ConfigManager only reads and writes JSON. This module provides
+//! the Rust-side parsers and writers for the other supported formats.
+//! Values are exchanged as flattened dotted keys, matching the C++ store.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::config::{config_value_to_json_string, ConfigValue};
+
+/// On-disk configuration file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Ini,
+}
+
+impl ConfigFormat {
+    /// Detect the format from a file extension, defaulting to JSON
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("ini") => ConfigFormat::Ini,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+/// Parse an INI document into flattened dotted keys
+///
+/// Sections map to key prefixes (`[server]` + `port = 80` becomes
+/// `server.port`), so nested sections can be written as `[server.tls]`.
+/// Unquoted scalars are typed as booleans, integers or floats when possible.
+pub(crate) fn parse_ini(contents: &str) -> Result<HashMap<String, ConfigValue>, String> {
+    let mut values = HashMap::new();
+    let mut section = String::new();
+    
+    for (index, raw_line) in contents.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        
+        if let Some(rest) = line.strip_prefix('[') {
+            let name = rest
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {}: unterminated section header", index + 1))?
+                .trim();
+            if name.is_empty() {
+                return Err(format!("line {}: empty section name", index + 1));
+            }
+            section = name.to_string();
+            continue;
+        }
+        
+        let separator = line
+            .find(['=', ':'])
+            .ok_or_else(|| format!("line {}: expected 'key = value'", index + 1))?;
+        let key = line[..separator].trim();
+        if key.is_empty() {
+            return Err(format!("line {}: empty key", index + 1));
+        }
+        
+        let value = parse_ini_value(line[separator + 1..].trim())
+            .map_err(|e| format!("line {}: {}", index + 1, e))?;
+        let full_key = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+        values.insert(full_key, value);
+    }
+    
+    Ok(values)
+}
+
+/// Parse a single INI value
+fn parse_ini_value(raw: &str) -> Result<ConfigValue, String> {
+    if let Some(quote) = raw.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let mut value = String::new();
+        let mut chars = raw[1..].chars();
+        loop {
+            match chars.next() {
+                None => return Err("unterminated quoted value".to_string()),
+                Some('\\') if quote == '"' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c) => value.push(c),
+                    None => return Err("unterminated quoted value".to_string()),
+                },
+                Some(c) if c == quote => break,
+                Some(c) => value.push(c),
+            }
+        }
+        return Ok(ConfigValue::String(value));
+    }
+    
+    // Strip inline comments from unquoted values
+    let value = [" ;", " #"]
+        .iter()
+        .filter_map(|marker| raw.find(marker))
+        .min()
+        .map_or(raw, |pos| &raw[..pos])
+        .trim();
+    
+    if value.starts_with('[') {
+        if let Ok(serde_json::Value::Array(arr)) = serde_json::from_str(value) {
+            return Ok(crate::config::json_to_config_value(serde_json::Value::Array(arr)));
+        }
+    }
+    
+    Ok(match value {
+        "true" | "TRUE" | "True" => ConfigValue::Boolean(true),
+        "false" | "FALSE" | "False" => ConfigValue::Boolean(false),
+        _ => {
+            if let Ok(i) = value.parse::<i64>() {
+                ConfigValue::Integer(i)
+            } else if let Ok(f) = value.parse::<f64>() {
+                ConfigValue::Float(f)
+            } else {
+                ConfigValue::String(value.to_string())
+            }
+        }
+    })
+}
+
+/// Serialize flattened dotted keys as an INI document
+///
+/// The last key segment becomes the INI key and the rest the section name.
+/// Keys without a dot are written before the first section.
+pub(crate) fn write_ini(values: &HashMap<String, ConfigValue>) -> String {
+    let mut sections: BTreeMap<&str, BTreeMap<&str, &ConfigValue>> = BTreeMap::new();
+    for (key, value) in values {
+        let (section, name) = key.rsplit_once('.').unwrap_or(("", key.as_str()));
+        sections.entry(section).or_default().insert(name, value);
+    }
+    
+    let mut output = String::new();
+    for (section, entries) in sections {
+        if !section.is_empty() {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&format!("[{}]\n", section));
+        }
+        for (name, value) in entries {
+            output.push_str(&format!("{} = {}\n", name, format_ini_value(value)));
+        }
+    }
+    output
+}
+
+/// Format a value so that `parse_ini_value` reads it back unchanged
+fn format_ini_value(value: &ConfigValue) -> String {
+    match value {
+        ConfigValue::String(s) => {
+            let reparsed = parse_ini_value(s).ok();
+            if reparsed.as_ref() == Some(value) && !s.is_empty() {
+                s.clone()
+            } else {
+                quote_ini_string(s)
+            }
+        }
+        ConfigValue::Integer(i) => i.to_string(),
+        ConfigValue::Float(f) => {
+            // Keep a decimal point so the value reads back as a float
+            if f.fract() == 0.0 && f.is_finite() { format!("{:.1}", f) } else { f.to_string() }
+        }
+        ConfigValue::Boolean(b) => b.to_string(),
+        ConfigValue::Null => "\"\"".to_string(),
+        ConfigValue::Array(_) | ConfigValue::Object(_) | ConfigValue::Bytes(_) => {
+            let json = config_value_to_json_string(value).unwrap_or_default();
+            match value {
+                ConfigValue::Array(_) => json,
+                _ => quote_ini_string(json.trim_matches('"')),
+            }
+        }
+    }
+}
+
+/// Parse a dotenv (`.env`) document into ordered `(name, value)` pairs
+///
+/// Supports `export` prefixes, `#` comments, single-quoted literals,
+/// double-quoted values with escapes (`\n`, `\t`, `\"`, `\\`, `\$`) and
+/// multi-line quoted values. `${VAR}` and `$VAR` references in unquoted and
+/// double-quoted values expand to variables defined earlier in the file,
+/// then to the process environment.
+pub(crate) fn parse_dotenv(contents: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut lines = contents.lines().enumerate();
+    
+    while let Some((index, raw_line)) = lines.next() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let (name, rest) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected 'NAME=value'", index + 1))?;
+        let name = name.trim();
+        if !is_valid_env_name(name) {
+            return Err(format!("line {}: invalid variable name '{}'", index + 1, name));
+        }
+        
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('\'' | '"')) => {
+                // Quoted values may continue over several lines
+                let mut raw = rest[1..].to_string();
+                let raw = loop {
+                    if let Some(end) = find_closing_quote(&raw, quote) {
+                        raw.truncate(end);
+                        break raw;
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            raw.push('\n');
+                            raw.push_str(next);
+                        }
+                        None => return Err(format!("line {}: unterminated quoted value", index + 1)),
+                    }
+                };
+                
+                if quote == '\'' {
+                    raw
+                } else {
+                    expand_dotenv_value(&raw, &entries, true)
+                }
+            }
+            _ => {
+                let unquoted = rest.find(" #").map_or(rest, |pos| &rest[..pos]).trim_end();
+                expand_dotenv_value(unquoted, &entries, false)
+            }
+        };
+        
+        entries.retain(|(existing, _)| existing != name);
+        entries.push((name.to_string(), value));
+    }
+    
+    Ok(entries)
+}
+
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Find the closing quote, skipping backslash-escaped characters in double quotes
+fn find_closing_quote(raw: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (pos, c) in raw.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(pos);
+        }
+    }
+    None
+}
+
+/// Process escapes (for double-quoted values) and variable references
+fn expand_dotenv_value(raw: &str, defined: &[(String, String)], escapes: bool) -> String {
+    let lookup = |name: &str| {
+        defined
+            .iter()
+            .rev()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value.clone())
+            .or_else(|| std::env::var(name).ok())
+            .unwrap_or_default()
+    };
+    
+    let mut output = String::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if escapes => match chars.next() {
+                Some('n') => output.push('\n'),
+                Some('r') => output.push('\r'),
+                Some('t') => output.push('\t'),
+                Some(other) => output.push(other),
+                None => output.push('\\'),
+            },
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                output.push_str(&lookup(&name));
+            }
+            '$' if chars.peek().is_some_and(|c| c.is_ascii_alphabetic() || *c == '_') => {
+                let mut name = String::new();
+                while let Some(c) = chars.peek().copied().filter(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                    chars.next();
+                }
+                output.push_str(&lookup(&name));
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Map an environment variable name to a config key
+///
+/// Names are lowercased and `__` separates nesting levels, so
+/// `SERVER__PORT` maps to `server.port`.
+pub fn env_var_to_key(name: &str) -> String {
+    name.to_lowercase().replace("__", ".")
+}
+
+fn quote_ini_string(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(ConfigFormat::from_path("legacy/app.INI"), ConfigFormat::Ini);
+        assert_eq!(ConfigFormat::from_path("config.json"), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path("config"), ConfigFormat::Json);
+    }
+    
+    #[test]
+    fn test_parse_ini() {
+        let values = parse_ini(
+            "; legacy config\n\
+             name = demo\n\
+             \n\
+             [server]\n\
+             host = 0.0.0.0   ; bind address\n\
+             port = 8080\n\
+             ratio: 0.5\n\
+             \n\
+             [server.tls]\n\
+             enabled = true\n\
+             motd = \"Hello; \\\"world\\\"\"\n\
+             ports = [443, 8443]\n",
+        ).unwrap();
+        
+        assert_eq!(values.get("name"), Some(&ConfigValue::from("demo")));
+        assert_eq!(values.get("server.host"), Some(&ConfigValue::from("0.0.0.0")));
+        assert_eq!(values.get("server.port"), Some(&ConfigValue::Integer(8080)));
+        assert_eq!(values.get("server.ratio"), Some(&ConfigValue::Float(0.5)));
+        assert_eq!(values.get("server.tls.enabled"), Some(&ConfigValue::Boolean(true)));
+        assert_eq!(values.get("server.tls.motd"), Some(&ConfigValue::from("Hello; \"world\"")));
+        assert_eq!(
+            values.get("server.tls.ports"),
+            Some(&ConfigValue::Array(vec![ConfigValue::Integer(443), ConfigValue::Integer(8443)]))
+        );
+        
+        assert!(parse_ini("[server\nport = 1").is_err());
+        assert!(parse_ini("just a line").is_err());
+    }
+    
+    #[test]
+    fn test_parse_dotenv() {
+        let entries = parse_dotenv(
+            "# local development\n\
+             export SERVER__HOST=localhost\n\
+             SERVER__PORT = 8080 # inline comment\n\
+             GREETING=\"Hello\\n\\\"${SERVER__HOST}\\\"\"\n\
+             LITERAL='${SERVER__HOST}\\n'\n\
+             URL=http://$SERVER__HOST:${SERVER__PORT}/api\n\
+             CERT=\"-----BEGIN-----\n\
+             abc\n\
+             -----END-----\"\n\
+             SERVER__PORT=9090\n",
+        ).unwrap();
+        
+        let get = |name: &str| entries.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+        assert_eq!(get("SERVER__HOST"), Some("localhost"));
+        assert_eq!(get("GREETING"), Some("Hello\n\"localhost\""));
+        assert_eq!(get("LITERAL"), Some("${SERVER__HOST}\\n"));
+        assert_eq!(get("URL"), Some("http://localhost:8080/api"));
+        assert_eq!(get("CERT"), Some("-----BEGIN-----\nabc\n-----END-----"));
+        assert_eq!(get("SERVER__PORT"), Some("9090"));
+        assert_eq!(entries.last().unwrap().0, "SERVER__PORT");
+        
+        assert!(parse_dotenv("NOT VALID").is_err());
+        assert!(parse_dotenv("1BAD=x").is_err());
+        assert!(parse_dotenv("OPEN=\"never closed").is_err());
+        
+        assert_eq!(env_var_to_key("SERVER__PORT"), "server.port");
+        assert_eq!(env_var_to_key("LOG_LEVEL"), "log_level");
+    }
+    
+    #[test]
+    fn test_write_ini_round_trip() {
+        let mut values = HashMap::new();
+        values.insert("name".to_string(), ConfigValue::from("demo"));
+        values.insert("server.port".to_string(), ConfigValue::Integer(8080));
+        values.insert("server.timeout".to_string(), ConfigValue::Float(2.0));
+        values.insert("server.banner".to_string(), ConfigValue::from(" padded; text "));
+        values.insert("server.version".to_string(), ConfigValue::from("42"));
+        values.insert("server.tls.enabled".to_string(), ConfigValue::Boolean(false));
+        
+        let ini = write_ini(&values);
+        assert!(ini.starts_with("name = demo\n"));
+        assert!(ini.contains("[server.tls]\nenabled = false\n"));
+        assert_eq!(parse_ini(&ini).unwrap(), values);
+    }
+}
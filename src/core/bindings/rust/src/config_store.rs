@@ -0,0 +1,358 @@
+//! Configuration storage backends for CoreBase Rust bindings
+//!
+//! By default configuration values live in the C++ ConfigManager store.
+//! This module defines the `ConfigStore` trait for alternative Rust-side
+//! backends, such as the in-memory `MemoryStore` and the SQLite store
+//! enabled by the `sqlite` feature.
+
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "sqlite")]
+use std::path::PathBuf;
+
+use crate::config::ConfigValue;
+use crate::error::CoreBaseResult;
+
+/// A recorded change to a stored configuration value
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    pub key: String,
+    pub old_value: Option<ConfigValue>,
+    pub new_value: Option<ConfigValue>,
+    pub timestamp: u64,
+}
+
+/// Storage backend for configuration values
+///
+/// Keys are flattened dotted keys, the same layout used by the C++ store.
+pub trait ConfigStore: fmt::Debug + Send + Sync {
+    /// Get the stored value for a key
+    fn get(&self, key: &str) -> CoreBaseResult<Option<ConfigValue>>;
+    
+    /// Store a value for a key
+    fn set(&mut self, key: &str, value: &ConfigValue) -> CoreBaseResult<()>;
+    
+    /// Store several values at once
+    ///
+    /// Backends that support transactions should apply all values or none.
+    fn set_many(&mut self, values: &HashMap<String, ConfigValue>) -> CoreBaseResult<()> {
+        for (key, value) in values {
+            self.set(key, value)?;
+        }
+        Ok(())
+    }
+    
+    /// Remove the value stored for a key
+    fn remove(&mut self, key: &str) -> CoreBaseResult<()>;
+    
+    /// Get all stored values
+    fn load_all(&self) -> CoreBaseResult<HashMap<String, ConfigValue>>;
+    
+    /// Get all stored keys
+    fn keys(&self) -> CoreBaseResult<Vec<String>> {
+        Ok(self.load_all()?.into_keys().collect())
+    }
+    
+    /// Get the recorded changes for a key, oldest first
+    ///
+    /// Backends without history support return an empty list.
+    fn history(&self, _key: &str) -> CoreBaseResult<Vec<ConfigChange>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Selects where a ConfigManager persists its values
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Store {
+    /// The C++ ConfigManager store
+    #[default]
+    Native,
+    /// A `MemoryStore`, dropped with the manager
+    Memory,
+    /// A SQLite database at the given path (requires "sqlite" feature)
+    #[cfg(feature = "sqlite")]
+    Sqlite(PathBuf),
+}
+
+impl Store {
+    /// Open the backend, returning `None` for the native C++ store
+    pub(crate) fn open(self) -> CoreBaseResult<Option<Box<dyn ConfigStore>>> {
+        match self {
+            Store::Native => Ok(None),
+            Store::Memory => Ok(Some(Box::new(MemoryStore::default()))),
+            #[cfg(feature = "sqlite")]
+            Store::Sqlite(path) => Ok(Some(Box::new(sqlite::SqliteStore::open(path)?))),
+        }
+    }
+}
+
+/// Configuration store keeping values in a map, never touching the C++ side
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    values: HashMap<String, ConfigValue>,
+}
+
+impl ConfigStore for MemoryStore {
+    fn get(&self, key: &str) -> CoreBaseResult<Option<ConfigValue>> {
+        Ok(self.values.get(key).cloned())
+    }
+    
+    fn set(&mut self, key: &str, value: &ConfigValue) -> CoreBaseResult<()> {
+        self.values.insert(key.to_string(), value.clone());
+        Ok(())
+    }
+    
+    fn remove(&mut self, key: &str) -> CoreBaseResult<()> {
+        self.values.remove(key);
+        Ok(())
+    }
+    
+    fn load_all(&self) -> CoreBaseResult<HashMap<String, ConfigValue>> {
+        Ok(self.values.clone())
+    }
+}
+
+/// SQLite configuration store (requires "sqlite" feature)
+#[cfg(feature = "sqlite")]
+pub mod sqlite {
+    use super::*;
+    use std::path::Path;
+    use std::sync::{Mutex, MutexGuard};
+    use rusqlite::{params, Connection, OptionalExtension, Transaction};
+    
+    use crate::config::{config_value_to_json_string, json_to_config_value};
+    use crate::error::CoreBaseError;
+    
+    /// Configuration store backed by a local SQLite database
+    ///
+    /// Every write runs in a transaction and is recorded in a history table.
+    #[derive(Debug)]
+    pub struct SqliteStore {
+        conn: Mutex<Connection>,
+    }
+    
+    impl SqliteStore {
+        /// Open (or create) a SQLite config database
+        pub fn open<P: AsRef<Path>>(path: P) -> CoreBaseResult<Self> {
+            let conn = Connection::open(path.as_ref()).map_err(sql_error)?;
+            Self::from_connection(conn)
+        }
+        
+        /// Open a temporary in-memory database
+        pub fn open_in_memory() -> CoreBaseResult<Self> {
+            let conn = Connection::open_in_memory().map_err(sql_error)?;
+            Self::from_connection(conn)
+        }
+        
+        fn from_connection(conn: Connection) -> CoreBaseResult<Self> {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS config (
+                     key TEXT PRIMARY KEY,
+                     value TEXT NOT NULL,
+                     updated_at INTEGER NOT NULL
+                 );
+                 CREATE TABLE IF NOT EXISTS config_history (
+                     id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     key TEXT NOT NULL,
+                     old_value TEXT,
+                     new_value TEXT,
+                     changed_at INTEGER NOT NULL
+                 );
+                 CREATE INDEX IF NOT EXISTS config_history_key ON config_history (key);",
+            ).map_err(sql_error)?;
+            
+            Ok(SqliteStore {
+                conn: Mutex::new(conn),
+            })
+        }
+        
+        fn connection(&self) -> CoreBaseResult<MutexGuard<'_, Connection>> {
+            self.conn.lock().map_err(|_| CoreBaseError::OperationFailed(
+                "Failed to access config database".to_string(),
+                None,
+            ))
+        }
+    }
+    
+    impl ConfigStore for SqliteStore {
+        fn get(&self, key: &str) -> CoreBaseResult<Option<ConfigValue>> {
+            let conn = self.connection()?;
+            let value: Option<String> = conn
+                .query_row("SELECT value FROM config WHERE key = ?1", params![key], |row| row.get(0))
+                .optional()
+                .map_err(sql_error)?;
+            Ok(value.map(|v| decode_value(&v)))
+        }
+        
+        fn set(&mut self, key: &str, value: &ConfigValue) -> CoreBaseResult<()> {
+            let encoded = config_value_to_json_string(value)?;
+            let mut conn = self.connection()?;
+            let tx = conn.transaction().map_err(sql_error)?;
+            write_value(&tx, key, Some(&encoded))?;
+            tx.commit().map_err(sql_error)
+        }
+        
+        fn set_many(&mut self, values: &HashMap<String, ConfigValue>) -> CoreBaseResult<()> {
+            let mut conn = self.connection()?;
+            let tx = conn.transaction().map_err(sql_error)?;
+            for (key, value) in values {
+                let encoded = config_value_to_json_string(value)?;
+                write_value(&tx, key, Some(&encoded))?;
+            }
+            tx.commit().map_err(sql_error)
+        }
+        
+        fn remove(&mut self, key: &str) -> CoreBaseResult<()> {
+            let mut conn = self.connection()?;
+            let tx = conn.transaction().map_err(sql_error)?;
+            write_value(&tx, key, None)?;
+            tx.commit().map_err(sql_error)
+        }
+        
+        fn load_all(&self) -> CoreBaseResult<HashMap<String, ConfigValue>> {
+            let conn = self.connection()?;
+            let mut stmt = conn.prepare("SELECT key, value FROM config").map_err(sql_error)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .map_err(sql_error)?;
+            
+            let mut values = HashMap::new();
+            for row in rows {
+                let (key, value) = row.map_err(sql_error)?;
+                values.insert(key, decode_value(&value));
+            }
+            Ok(values)
+        }
+        
+        fn history(&self, key: &str) -> CoreBaseResult<Vec<ConfigChange>> {
+            let conn = self.connection()?;
+            let mut stmt = conn
+                .prepare("SELECT old_value, new_value, changed_at FROM config_history WHERE key = ?1 ORDER BY id")
+                .map_err(sql_error)?;
+            let rows = stmt
+                .query_map(params![key], |row| {
+                    Ok((
+                        row.get::<_, Option<String>>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                })
+                .map_err(sql_error)?;
+            
+            let mut changes = Vec::new();
+            for row in rows {
+                let (old_value, new_value, changed_at) = row.map_err(sql_error)?;
+                changes.push(ConfigChange {
+                    key: key.to_string(),
+                    old_value: old_value.as_deref().map(decode_value),
+                    new_value: new_value.as_deref().map(decode_value),
+                    timestamp: changed_at.max(0) as u64,
+                });
+            }
+            Ok(changes)
+        }
+    }
+    
+    /// Write (or delete, for `None`) a value and record the change in history
+    fn write_value(tx: &Transaction<'_>, key: &str, value: Option<&str>) -> CoreBaseResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        
+        let old_value: Option<String> = tx
+            .query_row("SELECT value FROM config WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()
+            .map_err(sql_error)?;
+        
+        if old_value.as_deref() == value {
+            return Ok(());
+        }
+        
+        match value {
+            Some(value) => tx.execute(
+                "INSERT INTO config (key, value, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+                params![key, value, now],
+            ),
+            None => tx.execute("DELETE FROM config WHERE key = ?1", params![key]),
+        }.map_err(sql_error)?;
+        
+        tx.execute(
+            "INSERT INTO config_history (key, old_value, new_value, changed_at) VALUES (?1, ?2, ?3, ?4)",
+            params![key, old_value, value, now],
+        ).map_err(sql_error)?;
+        
+        Ok(())
+    }
+    
+    /// Decode a stored JSON value, falling back to a plain string
+    fn decode_value(value: &str) -> ConfigValue {
+        serde_json::from_str::<serde_json::Value>(value)
+            .map(json_to_config_value)
+            .unwrap_or_else(|_| ConfigValue::String(value.to_string()))
+    }
+    
+    fn sql_error(e: rusqlite::Error) -> CoreBaseError {
+        CoreBaseError::ConfigError(format!("SQLite error: {}", e), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_native_store_has_no_backend() {
+        assert_eq!(Store::default(), Store::Native);
+        assert!(Store::Native.open().unwrap().is_none());
+    }
+    
+    #[test]
+    fn test_memory_store() {
+        let mut store = Store::Memory.open().unwrap().unwrap();
+        assert_eq!(store.get("server.port").unwrap(), None);
+        
+        store.set("server.port", &ConfigValue::Integer(80)).unwrap();
+        let mut batch = HashMap::new();
+        batch.insert("server.host".to_string(), ConfigValue::from("localhost"));
+        store.set_many(&batch).unwrap();
+        assert_eq!(store.get("server.port").unwrap(), Some(ConfigValue::Integer(80)));
+        assert_eq!(store.load_all().unwrap().len(), 2);
+        
+        store.remove("server.port").unwrap();
+        assert_eq!(store.keys().unwrap(), vec!["server.host"]);
+        assert!(store.history("server.host").unwrap().is_empty());
+    }
+    
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_store() {
+        let mut store = sqlite::SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.get("server.port").unwrap(), None);
+        
+        store.set("server.port", &ConfigValue::Integer(80)).unwrap();
+        store.set("server.port", &ConfigValue::Integer(443)).unwrap();
+        store.set("server.port", &ConfigValue::Integer(443)).unwrap();
+        assert_eq!(store.get("server.port").unwrap(), Some(ConfigValue::Integer(443)));
+        
+        let mut batch = HashMap::new();
+        batch.insert("server.host".to_string(), ConfigValue::from("localhost"));
+        batch.insert("debug".to_string(), ConfigValue::Boolean(true));
+        store.set_many(&batch).unwrap();
+        
+        let mut keys = store.keys().unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["debug", "server.host", "server.port"]);
+        
+        store.remove("server.port").unwrap();
+        assert_eq!(store.get("server.port").unwrap(), None);
+        
+        let history = store.history("server.port").unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].old_value, None);
+        assert_eq!(history[1].old_value, Some(ConfigValue::Integer(80)));
+        assert_eq!(history[1].new_value, Some(ConfigValue::Integer(443)));
+        assert_eq!(history[2].new_value, None);
+    }
+}
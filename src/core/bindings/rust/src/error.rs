@@ -3,87 +3,323 @@
 //! This module provides error types and error handling functionality
 //! that wraps the C++ ErrorHandler class.
 
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
 use std::os::raw::{c_char, c_int};
 use std::fmt;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 
-use crate::{LogLevel, to_c_string, from_c_string};
+use crate::{LogLevel, to_c_string};
+use crate::log_sink::{CppSink, LogRecord, LogSink};
 
 /// CoreBase error types
+///
+/// The second field is the cause when one is known, such as the code the
+/// C++ side reported for a failed call, and `None` otherwise.
 #[derive(Error, Debug, Clone)]
 pub enum CoreBaseError {
     #[error("Initialization failed: {0}")]
-    InitializationFailed(String),
+    InitializationFailed(String, Option<ErrorCode>),
     
     #[error("Shutdown failed: {0}")]
-    ShutdownFailed(String),
+    ShutdownFailed(String, Option<ErrorCode>),
     
     #[error("Invalid string: {0}")]
-    InvalidString(String),
+    InvalidString(String, Option<ErrorCode>),
     
     #[error("Configuration error: {0}")]
-    ConfigError(String),
+    ConfigError(String, Option<ErrorCode>),
     
     #[error("Network error: {0}")]
-    NetworkError(String),
+    NetworkError(String, Option<ErrorCode>),
     
     #[error("System monitor error: {0}")]
-    MonitorError(String),
+    MonitorError(String, Option<ErrorCode>),
     
     #[error("Operation failed: {0}")]
-    OperationFailed(String),
+    OperationFailed(String, Option<ErrorCode>),
     
     #[error("Invalid parameter: {0}")]
-    InvalidParameter(String),
+    InvalidParameter(String, Option<ErrorCode>),
+    
+    /// Malformed or oversized data, which fails the same way if retried
+    #[error("Invalid data: {0}")]
+    InvalidData(String, Option<ErrorCode>),
     
     #[error("Resource not found: {0}")]
-    ResourceNotFound(String),
+    ResourceNotFound(String, Option<ErrorCode>),
     
     #[error("Permission denied: {0}")]
-    PermissionDenied(String),
+    PermissionDenied(String, Option<ErrorCode>),
     
     #[error("Timeout occurred: {0}")]
-    Timeout(String),
+    Timeout(String, Option<ErrorCode>),
     
     #[error("Unknown error: {0}")]
-    Unknown(String),
+    Unknown(String, Option<ErrorCode>),
+}
+
+/// Cause of an error, as reported by `cba_get_last_error` on the C++ side
+///
+/// `CoreBaseError::code()` maps errors raised in Rust to the closest code,
+/// so callers can match on causes whichever side failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorCode {
+    Unknown,
+    InvalidArgument,
+    NotInitialized,
+    NotFound,
+    PermissionDenied,
+    Timeout,
+    Network,
+    Config,
+    Monitor,
+    Io,
+    OutOfMemory,
+    Unsupported,
+    /// Code without a variant, from a newer C++ library
+    Other(i32),
+}
+
+impl From<c_int> for ErrorCode {
+    fn from(code: c_int) -> Self {
+        match code {
+            1 => ErrorCode::Unknown,
+            2 => ErrorCode::InvalidArgument,
+            3 => ErrorCode::NotInitialized,
+            4 => ErrorCode::NotFound,
+            5 => ErrorCode::PermissionDenied,
+            6 => ErrorCode::Timeout,
+            7 => ErrorCode::Network,
+            8 => ErrorCode::Config,
+            9 => ErrorCode::Monitor,
+            10 => ErrorCode::Io,
+            11 => ErrorCode::OutOfMemory,
+            12 => ErrorCode::Unsupported,
+            code => ErrorCode::Other(code),
+        }
+    }
+}
+
+impl From<ErrorCode> for c_int {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Unknown => 1,
+            ErrorCode::InvalidArgument => 2,
+            ErrorCode::NotInitialized => 3,
+            ErrorCode::NotFound => 4,
+            ErrorCode::PermissionDenied => 5,
+            ErrorCode::Timeout => 6,
+            ErrorCode::Network => 7,
+            ErrorCode::Config => 8,
+            ErrorCode::Monitor => 9,
+            ErrorCode::Io => 10,
+            ErrorCode::OutOfMemory => 11,
+            ErrorCode::Unsupported => 12,
+            ErrorCode::Other(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::Other(code) => write!(f, "error code {}", code),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Error most recently reported by the C++ side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// Get the error most recently reported by the C++ side on this thread,
+/// or `None` if its last call succeeded
+pub fn last_error() -> Option<LastError> {
+    let mut buffer = [0u8; 512];
+    let code = unsafe { crate::cba_get_last_error(buffer.as_mut_ptr().cast(), buffer.len() as c_int) };
+    if code == 0 {
+        return None;
+    }
+    let length = buffer.iter().position(|&byte| byte == 0).unwrap_or(buffer.len());
+    Some(LastError {
+        code: ErrorCode::from(code),
+        message: String::from_utf8_lossy(&buffer[..length]).into_owned(),
+    })
 }
 
 impl CoreBaseError {
     /// Convert error to log level based on severity
     pub fn to_log_level(&self) -> LogLevel {
         match self {
-            CoreBaseError::InitializationFailed(_) => LogLevel::Critical,
-            CoreBaseError::ShutdownFailed(_) => LogLevel::Critical,
-            CoreBaseError::InvalidString(_) => LogLevel::Error,
-            CoreBaseError::ConfigError(_) => LogLevel::Error,
-            CoreBaseError::NetworkError(_) => LogLevel::Error,
-            CoreBaseError::MonitorError(_) => LogLevel::Warning,
-            CoreBaseError::OperationFailed(_) => LogLevel::Error,
-            CoreBaseError::InvalidParameter(_) => LogLevel::Warning,
-            CoreBaseError::ResourceNotFound(_) => LogLevel::Warning,
-            CoreBaseError::PermissionDenied(_) => LogLevel::Error,
-            CoreBaseError::Timeout(_) => LogLevel::Warning,
-            CoreBaseError::Unknown(_) => LogLevel::Error,
+            CoreBaseError::InitializationFailed(..) => LogLevel::Critical,
+            CoreBaseError::ShutdownFailed(..) => LogLevel::Critical,
+            CoreBaseError::InvalidString(..) => LogLevel::Error,
+            CoreBaseError::ConfigError(..) => LogLevel::Error,
+            CoreBaseError::NetworkError(..) => LogLevel::Error,
+            CoreBaseError::MonitorError(..) => LogLevel::Warning,
+            CoreBaseError::OperationFailed(..) => LogLevel::Error,
+            CoreBaseError::InvalidParameter(..) => LogLevel::Warning,
+            CoreBaseError::InvalidData(..) => LogLevel::Error,
+            CoreBaseError::ResourceNotFound(..) => LogLevel::Warning,
+            CoreBaseError::PermissionDenied(..) => LogLevel::Error,
+            CoreBaseError::Timeout(..) => LogLevel::Warning,
+            CoreBaseError::Unknown(..) => LogLevel::Error,
+        }
+    }
+    
+    /// Check whether the operation may succeed if retried
+    ///
+    /// Timeouts are transient, as are failures with an `ErrorCode::Timeout`
+    /// or `ErrorCode::Network` cause, such as a connection the C++ side saw
+    /// reset or refused. Other network errors, invalid data, missing
+    /// resources and configuration errors are not.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, CoreBaseError::Timeout(..))
+            || matches!(self.native_code(), Some(ErrorCode::Timeout | ErrorCode::Network))
+    }
+    
+    /// Get the cause of the error
+    ///
+    /// This is the code reported by the C++ side if there is one, and
+    /// otherwise the code closest to the variant.
+    pub fn code(&self) -> ErrorCode {
+        if let Some(code) = self.native_code() {
+            return code;
+        }
+        match self {
+            CoreBaseError::InitializationFailed(..) => ErrorCode::NotInitialized,
+            CoreBaseError::ShutdownFailed(..) => ErrorCode::Unknown,
+            CoreBaseError::InvalidString(..) => ErrorCode::InvalidArgument,
+            CoreBaseError::ConfigError(..) => ErrorCode::Config,
+            CoreBaseError::NetworkError(..) => ErrorCode::Network,
+            CoreBaseError::MonitorError(..) => ErrorCode::Monitor,
+            CoreBaseError::OperationFailed(..) => ErrorCode::Unknown,
+            CoreBaseError::InvalidParameter(..) => ErrorCode::InvalidArgument,
+            CoreBaseError::InvalidData(..) => ErrorCode::InvalidArgument,
+            CoreBaseError::ResourceNotFound(..) => ErrorCode::NotFound,
+            CoreBaseError::PermissionDenied(..) => ErrorCode::PermissionDenied,
+            CoreBaseError::Timeout(..) => ErrorCode::Timeout,
+            CoreBaseError::Unknown(..) => ErrorCode::Unknown,
+        }
+    }
+    
+    /// Get the code reported by the C++ side, if the error comes from a
+    /// failed C++ call
+    pub fn native_code(&self) -> Option<ErrorCode> {
+        match self {
+            CoreBaseError::InitializationFailed(_, code)
+            | CoreBaseError::ShutdownFailed(_, code)
+            | CoreBaseError::InvalidString(_, code)
+            | CoreBaseError::ConfigError(_, code)
+            | CoreBaseError::NetworkError(_, code)
+            | CoreBaseError::MonitorError(_, code)
+            | CoreBaseError::OperationFailed(_, code)
+            | CoreBaseError::InvalidParameter(_, code)
+            | CoreBaseError::InvalidData(_, code)
+            | CoreBaseError::ResourceNotFound(_, code)
+            | CoreBaseError::PermissionDenied(_, code)
+            | CoreBaseError::Timeout(_, code)
+            | CoreBaseError::Unknown(_, code) => *code,
         }
     }
+    
+    /// Attach the cause the C++ side reported for its last failed call
+    pub(crate) fn with_last_error(self) -> Self {
+        self.with_native_error(last_error())
+    }
+    
+    /// Attach the code of a C++ error, keeping the variant
+    ///
+    /// The message keeps this error's description, followed by the C++
+    /// message.
+    fn with_native_error(mut self, last: Option<LastError>) -> Self {
+        let Some(last) = last else {
+            return self;
+        };
+        let (message, code) = match &mut self {
+            CoreBaseError::InitializationFailed(message, code)
+            | CoreBaseError::ShutdownFailed(message, code)
+            | CoreBaseError::InvalidString(message, code)
+            | CoreBaseError::ConfigError(message, code)
+            | CoreBaseError::NetworkError(message, code)
+            | CoreBaseError::MonitorError(message, code)
+            | CoreBaseError::OperationFailed(message, code)
+            | CoreBaseError::InvalidParameter(message, code)
+            | CoreBaseError::InvalidData(message, code)
+            | CoreBaseError::ResourceNotFound(message, code)
+            | CoreBaseError::PermissionDenied(message, code)
+            | CoreBaseError::Timeout(message, code)
+            | CoreBaseError::Unknown(message, code) => (message, code),
+        };
+        if !last.message.is_empty() {
+            message.push_str(": ");
+            message.push_str(&last.message);
+        }
+        *code = Some(last.code);
+        self
+    }
 }
 
 /// Result type alias for CoreBase operations
 pub type CoreBaseResult<T> = Result<T, CoreBaseError>;
 
+/// Error raised inside the C++ framework
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoreBaseEvent {
+    pub level: LogLevel,
+    /// Error code from the C++ side, 0 if it gave none
+    pub code: i32,
+    /// C++ component that raised the error, such as "NetworkManager"
+    pub component: String,
+    pub message: String,
+    /// Unix timestamp in milliseconds when the event reached Rust
+    pub timestamp: u64,
+}
+
+/// Signature of the callback registered with
+/// `cba_error_handler_set_callback`
+pub(crate) type CbaErrorCallback =
+    unsafe extern "C" fn(level: c_int, code: c_int, component: *const c_char, message: *const c_char);
+
+/// Error callback, returning false once it should be removed
+type ErrorEventCallback = Arc<dyn Fn(&CoreBaseEvent) -> bool + Send + Sync>;
+
+/// Callbacks receiving C++ errors, process-wide like the C++ ErrorHandler
+static ERROR_CALLBACKS: Mutex<Vec<ErrorEventCallback>> = Mutex::new(Vec::new());
+
+/// Log sink with the lowest level it receives
+struct SinkEntry {
+    min_level: LogLevel,
+    sink: Box<dyn LogSink>,
+}
+
 /// Error handler wrapper for the C++ ErrorHandler class
-#[derive(Debug)]
+///
+/// Log messages fan out to any number of `LogSink`s, each with its own
+/// minimum level.
 pub struct ErrorHandler {
     initialized: bool,
+    sinks: Vec<SinkEntry>,
 }
 
 impl ErrorHandler {
-    /// Create a new ErrorHandler instance
+    /// Create a new ErrorHandler instance logging to the C++ side
     pub fn new() -> CoreBaseResult<Self> {
         Ok(ErrorHandler {
             initialized: true,
+            sinks: vec![SinkEntry {
+                min_level: LogLevel::Debug,
+                sink: Box::new(CppSink),
+            }],
         })
     }
     
@@ -97,7 +333,8 @@ impl ErrorHandler {
     ) -> CoreBaseResult<()> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ErrorHandler not initialized".to_string()
+                "ErrorHandler not initialized".to_string(),
+                None,
             ));
         }
         
@@ -117,8 +354,9 @@ impl ErrorHandler {
                 Ok(())
             } else {
                 Err(CoreBaseError::OperationFailed(
-                    "Failed to handle error".to_string()
-                ))
+                    "Failed to handle error".to_string(),
+                    None,
+                ).with_last_error())
             }
         }
     }
@@ -127,7 +365,8 @@ impl ErrorHandler {
     pub fn set_log_level(&self, level: LogLevel) -> CoreBaseResult<()> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ErrorHandler not initialized".to_string()
+                "ErrorHandler not initialized".to_string(),
+                None,
             ));
         }
         
@@ -137,8 +376,9 @@ impl ErrorHandler {
                 Ok(())
             } else {
                 Err(CoreBaseError::OperationFailed(
-                    "Failed to set log level".to_string()
-                ))
+                    "Failed to set log level".to_string(),
+                    None,
+                ).with_last_error())
             }
         }
     }
@@ -147,7 +387,8 @@ impl ErrorHandler {
     pub fn get_log_level(&self) -> CoreBaseResult<LogLevel> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ErrorHandler not initialized".to_string()
+                "ErrorHandler not initialized".to_string(),
+                None,
             ));
         }
         
@@ -158,25 +399,136 @@ impl ErrorHandler {
     }
     
     /// Log a message with the specified level
+    ///
+    /// The message goes to every sink whose minimum level it reaches.
     pub fn log(&self, level: LogLevel, message: &str) -> CoreBaseResult<()> {
+        self.dispatch(&LogRecord {
+            level,
+            message,
+            fields: &[],
+        })
+    }
+    
+    /// Log a message with key-value fields
+    ///
+    /// Sinks receive the fields separately from the message: the C++ side
+    /// gets them as a JSON object, so log sinks can index them instead of
+    /// parsing the message, and text sinks append them as `key=value`
+    /// pairs.
+    ///
+    /// ```ignore
+    /// handler.log_kv(LogLevel::Info, "Message sent", &[("conn_id", id.into()), ("latency_ms", 12.5.into())])?;
+    /// ```
+    pub fn log_kv(&self, level: LogLevel, message: &str, fields: &[(&str, Value)]) -> CoreBaseResult<()> {
+        self.dispatch(&LogRecord {
+            level,
+            message,
+            fields,
+        })
+    }
+    
+    /// Add a destination for messages at or above `min_level`
+    ///
+    /// A new handler logs to the C++ ErrorHandler through a `CppSink`;
+    /// call `clear_sinks()` first to replace it.
+    pub fn add_sink<S: LogSink + 'static>(&mut self, sink: S, min_level: LogLevel) {
+        self.sinks.push(SinkEntry {
+            min_level,
+            sink: Box::new(sink),
+        });
+    }
+    
+    /// Remove every sink, including the default `CppSink`
+    pub fn clear_sinks(&mut self) {
+        self.sinks.clear();
+    }
+    
+    /// Get the number of sinks
+    pub fn sink_count(&self) -> usize {
+        self.sinks.len()
+    }
+    
+    /// Flush every sink
+    pub fn flush(&self) -> CoreBaseResult<()> {
+        self.sinks.iter().map(|entry| entry.sink.flush()).fold(Ok(()), Result::and)
+    }
+    
+    /// Call `callback` with every error raised inside the C++ framework
+    ///
+    /// Callbacks are process-wide, as the C++ ErrorHandler is, and may run
+    /// on any C++ thread. They stay registered until
+    /// `clear_error_callbacks()`, even after this handler is dropped.
+    ///
+    /// ```ignore
+    /// handler.on_error(|event| eprintln!("{} failed: {}", event.component, event.message))?;
+    /// ```
+    pub fn on_error<F>(&self, callback: F) -> CoreBaseResult<()>
+    where
+        F: Fn(&CoreBaseEvent) + Send + Sync + 'static,
+    {
+        self.register_error_callback(Arc::new(move |event: &CoreBaseEvent| {
+            callback(event);
+            true
+        }))
+    }
+    
+    /// Receive errors raised inside the C++ framework on a channel
+    ///
+    /// The channel is unregistered once the receiver is dropped and the
+    /// next error arrives.
+    pub fn error_events(&self) -> CoreBaseResult<Receiver<CoreBaseEvent>> {
+        let (sender, receiver) = mpsc::channel();
+        self.register_error_callback(Arc::new(move |event: &CoreBaseEvent| sender.send(event.clone()).is_ok()))?;
+        Ok(receiver)
+    }
+    
+    /// Remove every error callback and channel, and stop the C++ side
+    /// delivering errors
+    pub fn clear_error_callbacks(&self) -> CoreBaseResult<()> {
+        let mut callbacks = ERROR_CALLBACKS.lock().map_err(|_| error_callbacks_lock_error())?;
+        callbacks.clear();
+        unsafe { crate::cba_error_handler_set_callback(None) };
+        Ok(())
+    }
+    
+    /// Add a callback, installing the C++ callback with the first one
+    fn register_error_callback(&self, callback: ErrorEventCallback) -> CoreBaseResult<()> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "ErrorHandler not initialized".to_string()
+                "ErrorHandler not initialized".to_string(),
+                None,
             ));
         }
         
-        let c_message = to_c_string(message)?;
-        
-        unsafe {
-            let result = crate::cba_error_handler_log(level.into(), c_message.as_ptr());
-            if result == 0 {
-                Ok(())
-            } else {
-                Err(CoreBaseError::OperationFailed(
-                    "Failed to log message".to_string()
-                ))
+        let mut callbacks = ERROR_CALLBACKS.lock().map_err(|_| error_callbacks_lock_error())?;
+        if callbacks.is_empty() {
+            let result = unsafe { crate::cba_error_handler_set_callback(Some(deliver_error_event)) };
+            if result != 0 {
+                return Err(CoreBaseError::OperationFailed(
+                    "Failed to register error callback".to_string(),
+                    None,
+                ).with_last_error());
             }
         }
+        callbacks.push(callback);
+        Ok(())
+    }
+    
+    /// Write a record to every sink it reaches, returning the first error
+    /// after trying all of them
+    fn dispatch(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "ErrorHandler not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        self.sinks
+            .iter()
+            .filter(|entry| record.level >= entry.min_level)
+            .map(|entry| entry.sink.write(record))
+            .fold(Ok(()), Result::and)
     }
     
     /// Log a debug message
@@ -223,10 +575,59 @@ impl Default for ErrorHandler {
     fn default() -> Self {
         Self::new().unwrap_or(ErrorHandler {
             initialized: false,
+            sinks: Vec::new(),
         })
     }
 }
 
+impl fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let levels: Vec<LogLevel> = self.sinks.iter().map(|entry| entry.min_level).collect();
+        f.debug_struct("ErrorHandler")
+            .field("initialized", &self.initialized)
+            .field("sink_levels", &levels)
+            .finish()
+    }
+}
+
+/// Callback installed on the C++ ErrorHandler
+unsafe extern "C" fn deliver_error_event(level: c_int, code: c_int, component: *const c_char, message: *const c_char) {
+    let event = CoreBaseEvent {
+        level: LogLevel::from(level),
+        code,
+        component: unsafe { lossy_c_str(component) },
+        message: unsafe { lossy_c_str(message) },
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+    };
+    // Unwinding into C++ is undefined behaviour
+    let _ = std::panic::catch_unwind(|| dispatch_error_event(&event));
+}
+
+/// Pass an event to every error callback, removing those that are done
+fn dispatch_error_event(event: &CoreBaseEvent) {
+    // Callbacks run without the lock so they may register more callbacks
+    let callbacks = ERROR_CALLBACKS.lock().map(|callbacks| callbacks.clone()).unwrap_or_default();
+    let finished: Vec<ErrorEventCallback> = callbacks.into_iter().filter(|callback| !callback(event)).collect();
+    if !finished.is_empty() {
+        if let Ok(mut callbacks) = ERROR_CALLBACKS.lock() {
+            callbacks.retain(|callback| !finished.iter().any(|done| Arc::ptr_eq(callback, done)));
+        }
+    }
+}
+
+/// Copy a C string from the C++ side, empty if null
+unsafe fn lossy_c_str(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned()
+    }
+}
+
+fn error_callbacks_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access error callbacks".to_string(), None)
+}
+
 /// Macro for handling errors with automatic file/line/function information
 #[macro_export]
 macro_rules! handle_error {
@@ -249,6 +650,7 @@ macro_rules! cba_handle_error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::CString;
     
     #[test]
     fn test_error_handler_creation() {
@@ -259,24 +661,163 @@ mod tests {
     
     #[test]
     fn test_error_log_levels() {
-        let init_error = CoreBaseError::InitializationFailed("test".to_string());
+        let init_error = CoreBaseError::InitializationFailed("test".to_string(), None);
         assert_eq!(init_error.to_log_level(), LogLevel::Critical);
         
-        let config_error = CoreBaseError::ConfigError("test".to_string());
+        let config_error = CoreBaseError::ConfigError("test".to_string(), None);
         assert_eq!(config_error.to_log_level(), LogLevel::Error);
         
-        let monitor_error = CoreBaseError::MonitorError("test".to_string());
+        let monitor_error = CoreBaseError::MonitorError("test".to_string(), None);
         assert_eq!(monitor_error.to_log_level(), LogLevel::Warning);
     }
     
     #[test]
     fn test_error_display() {
-        let error = CoreBaseError::NetworkError("Connection failed".to_string());
+        let error = CoreBaseError::NetworkError("Connection failed".to_string(), None);
         let error_string = format!("{}", error);
         assert!(error_string.contains("Network error"));
         assert!(error_string.contains("Connection failed"));
     }
     
+    #[test]
+    fn test_log_kv() {
+        let fields = [
+            ("conn_id", Value::from("tcp-7")),
+            ("latency_ms", Value::from(12.5)),
+            ("peer", Value::from("db 2")),
+            ("retried", Value::from(false)),
+        ];
+        let record = LogRecord {
+            level: LogLevel::Info,
+            message: "Message sent",
+            fields: &fields,
+        };
+        assert_eq!(record.to_string(), r#"Message sent conn_id=tcp-7 latency_ms=12.5 peer="db 2" retried=false"#);
+        
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let captured = Arc::clone(&lines);
+        let mut handler = ErrorHandler::new().unwrap();
+        handler.clear_sinks();
+        handler.add_sink(move |record: &LogRecord<'_>| captured.lock().unwrap().push(record.to_string()), LogLevel::Debug);
+        handler.log_kv(LogLevel::Info, "Message sent", &fields).unwrap();
+        handler.log_kv(LogLevel::Info, "sent", &[]).unwrap();
+        assert_eq!(*lines.lock().unwrap(), [record.to_string(), "sent".to_string()]);
+        
+        let uninitialized = ErrorHandler {
+            initialized: false,
+            sinks: Vec::new(),
+        };
+        assert!(uninitialized.log_kv(LogLevel::Info, "lost", &[]).is_err());
+    }
+    
+    #[test]
+    fn test_error_codes() {
+        for raw in 1..=12 {
+            assert_eq!(c_int::from(ErrorCode::from(raw)), raw);
+        }
+        assert_eq!(ErrorCode::from(6), ErrorCode::Timeout);
+        assert_eq!(ErrorCode::from(99), ErrorCode::Other(99));
+        assert_eq!(ErrorCode::Other(99).to_string(), "error code 99");
+        
+        assert_eq!(CoreBaseError::Timeout("slow".to_string(), None).code(), ErrorCode::Timeout);
+        assert_eq!(CoreBaseError::ResourceNotFound("key".to_string(), None).code(), ErrorCode::NotFound);
+        assert!(CoreBaseError::Timeout("slow".to_string(), None).is_transient());
+        assert!(CoreBaseError::NetworkError("reset".to_string(), Some(ErrorCode::Network)).is_transient());
+        assert!(!CoreBaseError::NetworkError("HTTP 404 Not Found".to_string(), None).is_transient());
+        assert!(!CoreBaseError::InvalidData("Message too large".to_string(), None).is_transient());
+        assert!(!CoreBaseError::ConfigError("port".to_string(), None).is_transient());
+        
+        // A C++ error keeps the variant and attaches its code
+        let error = CoreBaseError::NetworkError("Failed to listen on 0.0.0.0:80".to_string(), None)
+            .with_native_error(Some(LastError {
+                code: ErrorCode::PermissionDenied,
+                message: "bind refused".to_string(),
+            }));
+        assert!(matches!(error, CoreBaseError::NetworkError(..)));
+        assert_eq!(error.code(), ErrorCode::PermissionDenied);
+        assert_eq!(error.native_code(), Some(ErrorCode::PermissionDenied));
+        assert_eq!(error.to_log_level(), LogLevel::Error);
+        assert!(!error.is_transient());
+        assert_eq!(error.to_string(), "Network error: Failed to listen on 0.0.0.0:80: bind refused");
+        
+        let error = CoreBaseError::MonitorError("Failed to get CPU usage".to_string(), None)
+            .with_native_error(Some(LastError {
+                code: ErrorCode::Timeout,
+                message: String::new(),
+            }));
+        assert_eq!(error.to_string(), "System monitor error: Failed to get CPU usage");
+        assert!(error.is_transient());
+        
+        // Without a C++ error the original error is kept
+        let error = CoreBaseError::NetworkError("Failed to close connection".to_string(), None).with_native_error(None);
+        assert!(matches!(error, CoreBaseError::NetworkError(_, None)));
+        assert_eq!(error.code(), ErrorCode::Network);
+    }
+    
+    #[test]
+    fn test_error_callback_dispatch() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let captured = Arc::clone(&seen);
+        let (sender, events) = mpsc::channel();
+        let (dropped_sender, dropped) = mpsc::channel();
+        drop(dropped);
+        {
+            let mut callbacks = ERROR_CALLBACKS.lock().unwrap();
+            callbacks.push(Arc::new(move |event: &CoreBaseEvent| {
+                captured.lock().unwrap().push(event.message.clone());
+                true
+            }));
+            callbacks.push(Arc::new(move |event: &CoreBaseEvent| sender.send(event.clone()).is_ok()));
+            callbacks.push(Arc::new(move |event: &CoreBaseEvent| dropped_sender.send(event.clone()).is_ok()));
+        }
+        
+        let component = CString::new("NetworkManager").unwrap();
+        let message = CString::new("socket closed").unwrap();
+        unsafe {
+            deliver_error_event(LogLevel::Error.into(), 104, component.as_ptr(), message.as_ptr());
+            deliver_error_event(LogLevel::Critical.into(), 0, std::ptr::null(), message.as_ptr());
+        }
+        
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.level, LogLevel::Error);
+        assert_eq!(event.code, 104);
+        assert_eq!(event.component, "NetworkManager");
+        assert_eq!(event.message, "socket closed");
+        assert!(event.timestamp > 0);
+        assert_eq!(events.try_recv().unwrap().component, "");
+        // The dropped channel is removed without keeping the others from being called
+        assert_eq!(*seen.lock().unwrap(), ["socket closed", "socket closed"]);
+        assert_eq!(ERROR_CALLBACKS.lock().unwrap().len(), 2);
+        ERROR_CALLBACKS.lock().unwrap().clear();
+        
+        let uninitialized = ErrorHandler {
+            initialized: false,
+            sinks: Vec::new(),
+        };
+        assert!(uninitialized.on_error(|_| {}).is_err());
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_error_callbacks() {
+        let handler = ErrorHandler::new().unwrap();
+        let events = handler.error_events().unwrap();
+        
+        let component = CString::new("NetworkManager").unwrap();
+        let message = CString::new("socket closed").unwrap();
+        unsafe {
+            deliver_error_event(LogLevel::Error.into(), 104, component.as_ptr(), message.as_ptr());
+            deliver_error_event(LogLevel::Critical.into(), 0, std::ptr::null(), message.as_ptr());
+        }
+        
+        assert_eq!(events.try_recv().unwrap().code, 104);
+        assert_eq!(events.try_recv().unwrap().component, "");
+        
+        handler.clear_error_callbacks().unwrap();
+        unsafe { deliver_error_event(LogLevel::Error.into(), 0, component.as_ptr(), message.as_ptr()) };
+        assert!(events.try_recv().is_err());
+    }
+    
     #[test]
     fn test_default_error_handler() {
         let handler = ErrorHandler::default();
@@ -0,0 +1,692 @@
+//! HTTP client module for CoreBase Rust bindings
+//!
+//! This module provides a request/response API over HTTP and HTTPS
+//! connections of the NetworkManager, so callers no longer need to build
+//! raw HTTP request strings for `send()`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{AccessToken, AuthScheme, NetworkConfig, NetworkConnection, NetworkManager, NetworkMessage, TokenRefresh};
+
+/// Default number of redirects followed before giving up
+const DEFAULT_MAX_REDIRECTS: u32 = 10;
+
+/// Headers carrying credentials, only sent to the origin of a request
+const CREDENTIAL_HEADERS: [&str; 3] = ["authorization", "cookie", "proxy-authorization"];
+
+/// HTTP request methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    Get,
+    Head,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Head => "HEAD",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Delete => "DELETE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// HTTP response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub reason: String,
+    /// Response headers with lowercased names
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Check for a 2xx status
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+    
+    /// Check for a 3xx status
+    pub fn is_redirect(&self) -> bool {
+        (300..400).contains(&self.status)
+    }
+    
+    /// Get a header value (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_ascii_lowercase()).map(String::as_str)
+    }
+    
+    /// Get the body as text
+    pub fn text(&self) -> CoreBaseResult<String> {
+        String::from_utf8(self.body.clone())
+            .map_err(|e| CoreBaseError::InvalidData(format!("Invalid UTF-8: {}", e), None))
+    }
+    
+    /// Deserialize the body as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> CoreBaseResult<T> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| CoreBaseError::InvalidData(format!("Invalid JSON response: {}", e), None))
+    }
+    
+    /// Turn non-2xx responses into errors
+    pub fn error_for_status(self) -> CoreBaseResult<Self> {
+        if self.is_success() {
+            Ok(self)
+        } else {
+            Err(CoreBaseError::NetworkError(
+                format!("HTTP {} {}", self.status, self.reason),
+                None,
+            ))
+        }
+    }
+}
+
+/// HTTP client over NetworkManager connections
+///
+/// Each request opens an HTTP or HTTPS connection for the target host and
+/// closes it once the response has been read.
+#[derive(Debug)]
+pub struct HttpClient {
+    manager: NetworkManager,
+    headers: HashMap<String, String>,
+    timeout: Duration,
+    max_redirects: u32,
+    auth: Option<AuthScheme>,
+    token_refresh: Option<TokenRefresh>,
+}
+
+impl HttpClient {
+    /// Create a new HttpClient instance
+    pub fn new() -> CoreBaseResult<Self> {
+        Ok(HttpClient {
+            manager: NetworkManager::new()?,
+            headers: HashMap::new(),
+            timeout: Duration::from_millis(NetworkConfig::default().timeout_ms as u64),
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            auth: None,
+            token_refresh: None,
+        })
+    }
+    
+    /// Add a header sent with every request
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+    
+    /// Set connection timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    /// Set how many redirects are followed (0 disables redirects)
+    pub fn with_max_redirects(mut self, max_redirects: u32) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+    
+    /// Send a bearer token with every request
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.auth = Some(AuthScheme::Bearer(token.to_string()));
+        self
+    }
+    
+    /// Send an API key in `header` with every request
+    pub fn with_api_key(mut self, header: &str, key: &str) -> Self {
+        self.auth = Some(AuthScheme::ApiKey {
+            header: header.to_string(),
+            key: key.to_string(),
+        });
+        self
+    }
+    
+    /// Fetch bearer tokens from `refresh`
+    ///
+    /// The token is refreshed once it expires, and a request answered with
+    /// 401 is retried once with a fresh token.
+    pub fn with_token_refresh<F>(mut self, refresh: F) -> Self
+    where
+        F: Fn() -> CoreBaseResult<AccessToken> + Send + Sync + 'static,
+    {
+        self.token_refresh = Some(TokenRefresh::new(refresh));
+        self
+    }
+    
+    /// Send a GET request
+    pub fn get(&self, url: &str) -> CoreBaseResult<HttpResponse> {
+        self.request(HttpMethod::Get, url, &[], &[])
+    }
+    
+    /// Send a POST request
+    pub fn post(&self, url: &str, body: &[u8], content_type: &str) -> CoreBaseResult<HttpResponse> {
+        self.request(HttpMethod::Post, url, &[("Content-Type", content_type)], body)
+    }
+    
+    /// Send a PUT request
+    pub fn put(&self, url: &str, body: &[u8], content_type: &str) -> CoreBaseResult<HttpResponse> {
+        self.request(HttpMethod::Put, url, &[("Content-Type", content_type)], body)
+    }
+    
+    /// Send a DELETE request
+    pub fn delete(&self, url: &str) -> CoreBaseResult<HttpResponse> {
+        self.request(HttpMethod::Delete, url, &[], &[])
+    }
+    
+    /// Send a GET request and deserialize the JSON response
+    pub fn get_json<T: DeserializeOwned>(&self, url: &str) -> CoreBaseResult<T> {
+        self.request(HttpMethod::Get, url, &[("Accept", "application/json")], &[])?
+            .error_for_status()?
+            .json()
+    }
+    
+    /// Send a POST request with a JSON body
+    pub fn post_json<T: Serialize>(&self, url: &str, body: &T) -> CoreBaseResult<HttpResponse> {
+        let body = serde_json::to_vec(body)
+            .map_err(|e| CoreBaseError::InvalidParameter(format!("JSON serialization error: {}", e), None))?;
+        self.post(url, &body, "application/json")
+    }
+    
+    /// Send a PUT request with a JSON body
+    pub fn put_json<T: Serialize>(&self, url: &str, body: &T) -> CoreBaseResult<HttpResponse> {
+        let body = serde_json::to_vec(body)
+            .map_err(|e| CoreBaseError::InvalidParameter(format!("JSON serialization error: {}", e), None))?;
+        self.put(url, &body, "application/json")
+    }
+    
+    /// Send a request, following redirects
+    ///
+    /// 301, 302 and 303 redirects are retried as GET without a body;
+    /// 307 and 308 keep the method and body. Once the redirect limit is
+    /// reached the redirect response itself is returned. With a token
+    /// refresh callback, the first 401 response is retried with a fresh
+    /// token.
+    ///
+    /// Credentials (the client's auth, and `Authorization`, `Cookie` and
+    /// `Proxy-Authorization` headers) are dropped once a redirect leaves
+    /// the scheme, host and port of `url`. Redirects from HTTPS to HTTP are
+    /// not followed; the redirect response is returned instead.
+    pub fn request(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> CoreBaseResult<HttpResponse> {
+        let mut target = HttpUrl::parse(url)?;
+        let origin = target.clone();
+        let mut method = method;
+        let mut body = body.to_vec();
+        
+        let mut redirects = 0;
+        let mut refreshed = false;
+        
+        loop {
+            let credentials = target.same_origin(&origin);
+            let response = self.send_once(method, &target, headers, &body, credentials)?;
+            
+            if let (401, Some(refresh), false, true) = (response.status, &self.token_refresh, refreshed, credentials) {
+                refreshed = true;
+                refresh.invalidate();
+                continue;
+            }
+            
+            let location = match response.header("location") {
+                Some(location) if response.is_redirect() && response.status != 304 && redirects < self.max_redirects => location,
+                _ => return Ok(response),
+            };
+            
+            let next = target.join(location)?;
+            // Never downgrade to cleartext
+            if target.https && !next.https {
+                return Ok(response);
+            }
+            redirects += 1;
+            target = next;
+            if matches!(response.status, 301..=303) && method != HttpMethod::Head {
+                method = HttpMethod::Get;
+                body.clear();
+            }
+        }
+    }
+    
+    fn send_once(
+        &self,
+        method: HttpMethod,
+        url: &HttpUrl,
+        headers: &[(&str, &str)],
+        body: &[u8],
+        credentials: bool,
+    ) -> CoreBaseResult<HttpResponse> {
+        let mut config = if url.https {
+            NetworkConfig::https(&url.host, url.port)
+        } else {
+            NetworkConfig::http(&url.host, url.port)
+        }
+        .with_timeout(self.timeout);
+        if credentials {
+            config.auth = self.auth.clone();
+            config.token_refresh = self.token_refresh.clone();
+        }
+        let auth_header = config.auth_header()?;
+        
+        let mut request_headers: Vec<(&str, &str)> = self.headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        request_headers.extend(auth_header.as_ref().map(|(k, v)| (k.as_str(), v.as_str())));
+        request_headers.extend_from_slice(headers);
+        if !credentials {
+            request_headers.retain(|(name, _)| !CREDENTIAL_HEADERS.iter().any(|header| name.eq_ignore_ascii_case(header)));
+        }
+        let request = build_request(method, url, &request_headers, body)?;
+        
+        let connection = self.manager.create_connection(config)?;
+        let result = connection
+            .send(&NetworkMessage::new_binary(request))
+            .and_then(|_| read_response(&connection, method));
+        let _ = self.manager.close_connection(&connection.id);
+        result
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new().unwrap_or(HttpClient {
+            manager: NetworkManager::default(),
+            headers: HashMap::new(),
+            timeout: Duration::from_millis(NetworkConfig::default().timeout_ms as u64),
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            auth: None,
+            token_refresh: None,
+        })
+    }
+}
+
+/// A parsed `http://` or `https://` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HttpUrl {
+    https: bool,
+    host: String,
+    port: u16,
+    /// Path and query, always starting with '/'
+    path: String,
+}
+
+impl HttpUrl {
+    fn parse(url: &str) -> CoreBaseResult<Self> {
+        let invalid = || CoreBaseError::InvalidParameter(format!("Invalid HTTP URL: {}", url), None);
+        
+        let (https, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            return Err(invalid());
+        };
+        
+        let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let (authority, path) = rest.split_at(authority_end);
+        let path = path.split('#').next().unwrap_or_default();
+        let path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
+        
+        let default_port = if https { 443 } else { 80 };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !host.ends_with(']') || authority.starts_with('[') => {
+                (host, port.parse().map_err(|_| invalid())?)
+            }
+            _ => (authority, default_port),
+        };
+        
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        
+        Ok(HttpUrl {
+            https,
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+    
+    /// Resolve a `Location` header against this URL
+    fn join(&self, location: &str) -> CoreBaseResult<Self> {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            return Self::parse(location);
+        }
+        
+        let scheme = if self.https { "https" } else { "http" };
+        if let Some(rest) = location.strip_prefix("//") {
+            return Self::parse(&format!("{}://{}", scheme, rest));
+        }
+        
+        let path = if location.starts_with('/') {
+            location.to_string()
+        } else {
+            let base = self.path.split('?').next().unwrap_or("/");
+            let dir = &base[..base.rfind('/').map_or(0, |i| i + 1)];
+            format!("{}{}", dir, location)
+        };
+        
+        Ok(HttpUrl {
+            path,
+            ..self.clone()
+        })
+    }
+    
+    /// Check whether both URLs have the same scheme, host and port
+    fn same_origin(&self, other: &HttpUrl) -> bool {
+        self.https == other.https && self.host.eq_ignore_ascii_case(&other.host) && self.port == other.port
+    }
+    
+    fn host_header(&self) -> String {
+        let default_port = if self.https { 443 } else { 80 };
+        if self.port == default_port {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Build an HTTP/1.1 request
+fn build_request(
+    method: HttpMethod,
+    url: &HttpUrl,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> CoreBaseResult<Vec<u8>> {
+    let mut request = format!("{} {} HTTP/1.1\r\nHost: {}\r\n", method, url.path, url.host_header());
+    let has_header = |name: &str| headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name));
+    
+    if !has_header("user-agent") {
+        request.push_str(concat!("User-Agent: corebase-bindings/", env!("CARGO_PKG_VERSION"), "\r\n"));
+    }
+    if !has_header("connection") {
+        request.push_str("Connection: close\r\n");
+    }
+    for (name, value) in headers {
+        if name.contains(['\r', '\n']) || value.contains(['\r', '\n']) {
+            return Err(CoreBaseError::InvalidParameter(
+                format!("Invalid header: {}", name),
+                None,
+            ));
+        }
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if !body.is_empty() || matches!(method, HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch) {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    
+    request.push_str("\r\n");
+    let mut request = request.into_bytes();
+    request.extend_from_slice(body);
+    Ok(request)
+}
+
+/// Read from the connection until a complete response has arrived
+fn read_response(connection: &NetworkConnection, method: HttpMethod) -> CoreBaseResult<HttpResponse> {
+    let mut buffer = Vec::new();
+    
+    loop {
+        let message = connection.receive()?;
+        let closed = message.data.is_empty();
+        buffer.extend_from_slice(&message.data);
+        
+        if let Some(response) = parse_response(&buffer, method, closed)? {
+            return Ok(response);
+        }
+        if closed {
+            return Err(CoreBaseError::NetworkError(
+                "Connection closed before the response was complete".to_string(),
+                None,
+            ));
+        }
+    }
+}
+
+/// Parse a response, returning `None` while more data is needed
+///
+/// `closed` signals that the peer has closed the connection, which ends
+/// bodies without a `Content-Length` or chunked encoding.
+fn parse_response(data: &[u8], method: HttpMethod, closed: bool) -> CoreBaseResult<Option<HttpResponse>> {
+    let Some(header_end) = find_subslice(data, b"\r\n\r\n") else {
+        return Ok(None);
+    };
+    
+    let head = std::str::from_utf8(&data[..header_end])
+        .map_err(|_| CoreBaseError::InvalidData("Invalid HTTP response headers".to_string(), None))?;
+    let mut lines = head.split("\r\n");
+    
+    let status_line = lines.next().unwrap_or_default();
+    let mut parts = status_line.splitn(3, ' ');
+    let status = match (parts.next(), parts.next()) {
+        (Some(version), Some(code)) if version.starts_with("HTTP/") => code.parse::<u16>().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| CoreBaseError::InvalidData(format!("Invalid HTTP status line: {}", status_line), None))?;
+    let reason = parts.next().unwrap_or_default().to_string();
+    
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    
+    let body = &data[header_end + 4..];
+    let no_body = method == HttpMethod::Head || status == 204 || status == 304 || (100..200).contains(&status);
+    
+    let body = if no_body {
+        Vec::new()
+    } else if headers.get("transfer-encoding").is_some_and(|v| v.to_ascii_lowercase().contains("chunked")) {
+        match decode_chunked(body)? {
+            Some(body) => body,
+            None => return Ok(None),
+        }
+    } else if let Some(length) = headers.get("content-length") {
+        let length: usize = length.parse()
+            .map_err(|_| CoreBaseError::InvalidData(format!("Invalid Content-Length: {}", length), None))?;
+        if body.len() < length {
+            return Ok(None);
+        }
+        body[..length].to_vec()
+    } else if closed {
+        body.to_vec()
+    } else {
+        return Ok(None);
+    };
+    
+    Ok(Some(HttpResponse {
+        status,
+        reason,
+        headers,
+        body,
+    }))
+}
+
+/// Decode a chunked body, returning `None` if it is incomplete
+fn decode_chunked(mut data: &[u8]) -> CoreBaseResult<Option<Vec<u8>>> {
+    let mut body = Vec::new();
+    
+    loop {
+        let Some(line_end) = find_subslice(data, b"\r\n") else {
+            return Ok(None);
+        };
+        let size_line = std::str::from_utf8(&data[..line_end]).unwrap_or_default();
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| CoreBaseError::InvalidData(format!("Invalid chunk size: {}", size_line), None))?;
+        data = &data[line_end + 2..];
+        
+        if size == 0 {
+            return Ok(Some(body));
+        }
+        let chunk_end = size.checked_add(2)
+            .ok_or_else(|| CoreBaseError::InvalidData(format!("Chunk size too large: {}", size_line), None))?;
+        if data.len() < chunk_end {
+            return Ok(None);
+        }
+        if &data[size..chunk_end] != b"\r\n" {
+            return Err(CoreBaseError::InvalidData("Chunk not terminated by CRLF".to_string(), None));
+        }
+        
+        body.extend_from_slice(&data[..size]);
+        data = &data[chunk_end..];
+    }
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_url_parsing() {
+        let url = HttpUrl::parse("https://example.com/api/items?page=2#top").unwrap();
+        assert!(url.https);
+        assert_eq!(url.host, "example.com");
+        assert_eq!(url.port, 443);
+        assert_eq!(url.path, "/api/items?page=2");
+        
+        let url = HttpUrl::parse("http://localhost:8080").unwrap();
+        assert_eq!(url.port, 8080);
+        assert_eq!(url.path, "/");
+        assert_eq!(url.host_header(), "localhost:8080");
+        
+        assert!(HttpUrl::parse("ftp://example.com").is_err());
+        assert!(HttpUrl::parse("http://:80/").is_err());
+        
+        let base = HttpUrl::parse("http://example.com/a/b?x=1").unwrap();
+        assert_eq!(base.join("/login").unwrap().path, "/login");
+        assert_eq!(base.join("c").unwrap().path, "/a/c");
+        assert_eq!(base.join("//other.org/x").unwrap().host, "other.org");
+        assert!(base.join("https://secure.org/").unwrap().https);
+        
+        assert!(base.same_origin(&base.join("/login").unwrap()));
+        assert!(base.same_origin(&HttpUrl::parse("http://EXAMPLE.com:80/").unwrap()));
+        assert!(!base.same_origin(&base.join("//other.org/x").unwrap()));
+        assert!(!base.same_origin(&HttpUrl::parse("http://example.com:8080/").unwrap()));
+        assert!(!base.same_origin(&HttpUrl::parse("https://example.com:80/").unwrap()));
+    }
+    
+    /// Accept one connection and answer it, returning the request received
+    fn serve_once(listener: std::net::TcpListener, response: String) -> std::thread::JoinHandle<String> {
+        use std::io::{Read, Write};
+        
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while find_subslice(&request, b"\r\n\r\n").is_none() {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        })
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_redirect_drops_credentials() {
+        let origin = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let other = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let other_port = other.local_addr().unwrap().port();
+        let origin_port = origin.local_addr().unwrap().port();
+        
+        let first = serve_once(origin, format!(
+            "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{}/next\r\nContent-Length: 0\r\n\r\n",
+            other_port
+        ));
+        let second = serve_once(other, "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_string());
+        
+        let client = HttpClient::new().unwrap()
+            .with_bearer_token("secret")
+            .with_header("Cookie", "session=1");
+        let response = client.request(
+            HttpMethod::Get,
+            &format!("http://127.0.0.1:{}/start", origin_port),
+            &[("Proxy-Authorization", "Basic eA=="), ("Accept", "text/plain")],
+            &[],
+        ).unwrap();
+        assert_eq!(response.text().unwrap(), "ok");
+        
+        let first = first.join().unwrap().to_ascii_lowercase();
+        assert!(first.contains("authorization: bearer secret"));
+        assert!(first.contains("cookie: session=1"));
+        let second = second.join().unwrap().to_ascii_lowercase();
+        assert!(second.starts_with("get /next "));
+        assert!(second.contains("accept: text/plain"));
+        assert!(!second.contains("authorization"));
+        assert!(!second.contains("cookie"));
+    }
+    
+    #[test]
+    fn test_build_request() {
+        let url = HttpUrl::parse("http://example.com:8080/items").unwrap();
+        let request = build_request(HttpMethod::Post, &url, &[("Content-Type", "application/json")], b"{}").unwrap();
+        let request = String::from_utf8(request).unwrap();
+        
+        assert!(request.starts_with("POST /items HTTP/1.1\r\nHost: example.com:8080\r\n"));
+        assert!(request.contains("Content-Type: application/json\r\n"));
+        assert!(request.contains("Content-Length: 2\r\n"));
+        assert!(request.ends_with("\r\n\r\n{}"));
+        
+        assert!(build_request(HttpMethod::Get, &url, &[("X-Bad", "a\r\nInjected: 1")], b"").is_err());
+        
+        let request = build_request(HttpMethod::Put, &url, &[], &[0xff, 0x00, 0xfe]).unwrap();
+        assert!(request.ends_with(b"Content-Length: 3\r\n\r\n\xff\x00\xfe"));
+    }
+    
+    #[test]
+    fn test_parse_response() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 13\r\n\r\n{\"ok\": true}\n";
+        assert_eq!(parse_response(&raw[..40], HttpMethod::Get, false).unwrap(), None);
+        
+        let response = parse_response(raw, HttpMethod::Get, false).unwrap().unwrap();
+        assert!(response.is_success());
+        assert_eq!(response.header("Content-Type"), Some("application/json"));
+        let value: serde_json::Value = response.json().unwrap();
+        assert_eq!(value["ok"], true);
+        
+        let chunked = b"HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
+        assert_eq!(parse_response(&chunked[..60], HttpMethod::Get, false).unwrap(), None);
+        let response = parse_response(chunked, HttpMethod::Get, false).unwrap().unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.reason, "Not Found");
+        assert_eq!(response.text().unwrap(), "Wikipedia");
+        assert!(response.error_for_status().is_err());
+        
+        assert!(matches!(decode_chunked(b"4\r\nWikiXX5\r\npedia\r\n0\r\n\r\n"), Err(CoreBaseError::InvalidData(..))));
+        let huge = format!("{:x}\r\n", usize::MAX);
+        assert!(matches!(decode_chunked(huge.as_bytes()), Err(CoreBaseError::InvalidData(..))));
+        
+        let until_close = b"HTTP/1.0 301 Moved\r\nLocation: /new\r\n\r\nmoved";
+        assert_eq!(parse_response(until_close, HttpMethod::Get, false).unwrap(), None);
+        let response = parse_response(until_close, HttpMethod::Get, true).unwrap().unwrap();
+        assert!(response.is_redirect());
+        assert_eq!(response.body, b"moved");
+        
+        assert!(parse_response(b"garbage\r\n\r\n", HttpMethod::Get, false).is_err());
+    }
+}
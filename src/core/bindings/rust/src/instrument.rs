@@ -0,0 +1,209 @@
+//! Tracing instrumentation for CoreBase Rust bindings
+//!
+//! With the "tracing" feature, connection operations run inside `tracing`
+//! spans under the `corebase::network` target, so network latency shows up
+//! in the application's tracing pipeline. Without the feature the helpers
+//! here only call the operation.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+#[cfg(feature = "tracing")]
+use tracing::field::{display, Empty};
+#[cfg(feature = "tracing")]
+use tracing::Span;
+
+use crate::error::CoreBaseResult;
+use crate::network::{NetworkConfig, NetworkConnection, NetworkProtocol};
+
+/// Connection operation recorded as a span of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Send,
+    Receive,
+    Close,
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! operation_span {
+    ($name:literal, $connection_id:expr, $protocol:expr) => {
+        tracing::debug_span!(
+            target: "corebase::network",
+            $name,
+            connection_id = %$connection_id,
+            protocol = ?$protocol,
+            bytes = Empty,
+            duration_us = Empty,
+            error = Empty,
+        )
+    };
+}
+
+/// Run a connection operation inside a span
+///
+/// The span records `connection_id`, `protocol`, `duration_us` and either
+/// `bytes`, taken from the result, or `error`.
+#[cfg(feature = "tracing")]
+pub(crate) fn traced<T>(
+    operation: Operation,
+    connection_id: &str,
+    protocol: NetworkProtocol,
+    bytes: impl FnOnce(&T) -> usize,
+    f: impl FnOnce() -> CoreBaseResult<T>,
+) -> CoreBaseResult<T> {
+    let span = match operation {
+        Operation::Send => operation_span!("send", connection_id, protocol),
+        Operation::Receive => operation_span!("receive", connection_id, protocol),
+        Operation::Close => operation_span!("close", connection_id, protocol),
+    };
+    let started = Instant::now();
+    let result = span.in_scope(f);
+    finish(&span, started, result, bytes)
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn traced<T>(
+    _operation: Operation,
+    _connection_id: &str,
+    _protocol: NetworkProtocol,
+    _bytes: impl FnOnce(&T) -> usize,
+    f: impl FnOnce() -> CoreBaseResult<T>,
+) -> CoreBaseResult<T> {
+    f()
+}
+
+/// Open a connection inside a `connect` span
+///
+/// The span records the protocol, host, port and `duration_us`, then the
+/// new `connection_id` or the `error`.
+#[cfg(feature = "tracing")]
+pub(crate) fn traced_connect(
+    config: NetworkConfig,
+    f: impl FnOnce(NetworkConfig) -> CoreBaseResult<NetworkConnection>,
+) -> CoreBaseResult<NetworkConnection> {
+    let span = tracing::debug_span!(
+        target: "corebase::network",
+        "connect",
+        connection_id = Empty,
+        protocol = ?config.protocol,
+        host = %config.host,
+        port = config.port,
+        duration_us = Empty,
+        error = Empty,
+    );
+    let started = Instant::now();
+    let result = span.in_scope(|| f(config));
+    if let Ok(connection) = &result {
+        span.record("connection_id", display(&connection.id));
+    }
+    finish(&span, started, result, |_| 0)
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn traced_connect(
+    config: NetworkConfig,
+    f: impl FnOnce(NetworkConfig) -> CoreBaseResult<NetworkConnection>,
+) -> CoreBaseResult<NetworkConnection> {
+    f(config)
+}
+
+#[cfg(feature = "tracing")]
+fn finish<T>(span: &Span, started: Instant, result: CoreBaseResult<T>, bytes: impl FnOnce(&T) -> usize) -> CoreBaseResult<T> {
+    span.record("duration_us", started.elapsed().as_micros() as u64);
+    match &result {
+        Ok(value) => {
+            span.record("bytes", bytes(value) as u64);
+        }
+        Err(e) => {
+            span.record("error", display(e));
+        }
+    }
+    result
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata};
+    
+    use crate::network::NetworkMessage;
+    use crate::transport::testing::{echo_config, echo_manager};
+    
+    type Fields = BTreeMap<String, String>;
+    
+    /// Subscriber keeping the name and fields of every span
+    #[derive(Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<(&'static str, Fields)>>>,
+    }
+    
+    struct FieldVisitor<'a>(&'a mut Fields);
+    
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+    
+    impl tracing::Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+        
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::new();
+            span.record(&mut FieldVisitor(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata().name(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+        
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+            values.record(&mut FieldVisitor(fields));
+        }
+        
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        
+        fn event(&self, _event: &Event<'_>) {}
+        
+        fn enter(&self, _span: &Id) {}
+        
+        fn exit(&self, _span: &Id) {}
+    }
+    
+    #[test]
+    fn test_connection_spans() {
+        let recorder = Recorder::default();
+        let spans = Arc::clone(&recorder.spans);
+        
+        tracing::subscriber::with_default(recorder, || {
+            let manager = echo_manager();
+            let connection = manager.create_connection(echo_config()).unwrap();
+            connection.send(&NetworkMessage::new_text("hello")).unwrap();
+            connection.receive().unwrap();
+            manager.close_connection(&connection.id).unwrap();
+        });
+        
+        let spans = spans.lock().unwrap();
+        let names: Vec<&str> = spans.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["connect", "send", "receive", "close"]);
+        
+        let (_, connect) = &spans[0];
+        assert_eq!(connect["protocol"], "Custom");
+        assert_eq!(connect["port"], "8080");
+        assert!(connect.contains_key("duration_us"));
+        let connection_id = connect["connection_id"].clone();
+        
+        for (name, fields) in &spans[1..] {
+            assert_eq!(fields["connection_id"], connection_id, "{}", name);
+            assert!(fields.contains_key("duration_us"), "{}", name);
+        }
+        assert_eq!(spans[1].1["bytes"], "5");
+        assert_eq!(spans[2].1["bytes"], "5");
+    }
+}
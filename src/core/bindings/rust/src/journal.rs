@@ -0,0 +1,311 @@
+//! Traffic journaling for CoreBase Rust bindings
+//!
+//! This module provides `Journal`, which records the messages sent and
+//! received by connections configured with `NetworkConfig::with_journal` to
+//! a file, and `ReplayTransport`, which feeds a recorded journal back to
+//! connections so protocol bugs can be reproduced without the original peer.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{NetworkConfig, NetworkConnection, NetworkManager, NetworkMessage, CORRELATION_ID_HEADER};
+use crate::transport::Transport;
+
+/// Direction of a journaled message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// One journaled message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    pub connection_id: String,
+    pub direction: Direction,
+    pub message: NetworkMessage,
+}
+
+type RedactFn = dyn Fn(&mut NetworkMessage) + Send + Sync;
+
+/// Recorder of connection traffic
+///
+/// Entries are written to the file as JSON lines. Clones share the file, so
+/// one journal can record several connections. Messages are recorded as
+/// the application sees them: before compression when sent and after
+/// decompression when received. Heartbeats are not recorded.
+#[derive(Clone)]
+pub struct Journal {
+    writer: Arc<Mutex<BufWriter<File>>>,
+    redact: Option<Arc<RedactFn>>,
+}
+
+impl Journal {
+    /// Create a journal file, replacing an existing one
+    pub fn create<P: AsRef<Path>>(path: P) -> CoreBaseResult<Self> {
+        let file = File::create(path.as_ref()).map_err(|e| journal_io_error(path.as_ref(), e))?;
+        Ok(Self::from_file(file))
+    }
+    
+    /// Open a journal file for appending, creating it if needed
+    pub fn append<P: AsRef<Path>>(path: P) -> CoreBaseResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .map_err(|e| journal_io_error(path.as_ref(), e))?;
+        Ok(Self::from_file(file))
+    }
+    
+    fn from_file(file: File) -> Self {
+        Journal {
+            writer: Arc::new(Mutex::new(BufWriter::new(file))),
+            redact: None,
+        }
+    }
+    
+    /// Pass every message through `redact` before it is written
+    ///
+    /// Use this to mask credentials or personal data. The hook works on a
+    /// copy; the message actually sent or received is not changed.
+    pub fn with_redaction<F>(mut self, redact: F) -> Self
+    where
+        F: Fn(&mut NetworkMessage) + Send + Sync + 'static,
+    {
+        self.redact = Some(Arc::new(redact));
+        self
+    }
+    
+    /// Record a message
+    pub fn record(&self, connection_id: &str, direction: Direction, message: &NetworkMessage) -> CoreBaseResult<()> {
+        let mut message = message.clone();
+        if let Some(redact) = &self.redact {
+            redact(&mut message);
+        }
+        let entry = JournalEntry {
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            connection_id: connection_id.to_string(),
+            direction,
+            message,
+        };
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to encode journal entry: {}", e), None))?;
+        
+        let mut writer = self.writer.lock().map_err(|_| journal_lock_error())?;
+        writeln!(writer, "{}", line)
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to write journal entry: {}", e), None))
+    }
+    
+    /// Write buffered entries to the file
+    pub fn flush(&self) -> CoreBaseResult<()> {
+        self.writer.lock().map_err(|_| journal_lock_error())?
+            .flush()
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to flush journal: {}", e), None))
+    }
+    
+    /// Read the entries of a journal file
+    pub fn read<P: AsRef<Path>>(path: P) -> CoreBaseResult<Vec<JournalEntry>> {
+        let file = File::open(path.as_ref()).map_err(|e| journal_io_error(path.as_ref(), e))?;
+        BufReader::new(file)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|(index, line)| {
+                let line = line.map_err(|e| journal_io_error(path.as_ref(), e))?;
+                serde_json::from_str(&line).map_err(|e| CoreBaseError::ConfigError(
+                    format!("Invalid journal entry on line {}: {}", index + 1, e),
+                    None,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Journal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Journal")
+            .field("redact", &self.redact.is_some())
+            .finish()
+    }
+}
+
+/// Transport replaying the received messages of a journal
+///
+/// Each `connect()` opens the next connection recorded in the journal, in
+/// order of first appearance, and takes its ID. Receives return that
+/// connection's recorded messages in order and then nothing; sends are
+/// kept so they can be compared with the journal's sent messages.
+#[derive(Debug, Default)]
+pub struct ReplayTransport {
+    /// Recorded connections not yet opened
+    pending: Mutex<VecDeque<String>>,
+    inbound: Mutex<HashMap<String, VecDeque<Vec<u8>>>>,
+    sent: Mutex<HashMap<String, Vec<Vec<u8>>>>,
+}
+
+impl ReplayTransport {
+    /// Create a transport replaying journal entries
+    pub fn new(entries: Vec<JournalEntry>) -> Self {
+        let mut pending = VecDeque::new();
+        let mut inbound: HashMap<String, VecDeque<Vec<u8>>> = HashMap::new();
+        
+        for entry in entries {
+            if !pending.contains(&entry.connection_id) {
+                pending.push_back(entry.connection_id.clone());
+            }
+            let queue = inbound.entry(entry.connection_id).or_default();
+            if entry.direction == Direction::Received {
+                queue.push_back(replayed_payload(&entry.message));
+            }
+        }
+        
+        ReplayTransport {
+            pending: Mutex::new(pending),
+            inbound: Mutex::new(inbound),
+            sent: Mutex::default(),
+        }
+    }
+    
+    /// Create a transport replaying a journal file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> CoreBaseResult<Self> {
+        Ok(Self::new(Journal::read(path)?))
+    }
+    
+    /// Get the payloads sent to a replayed connection
+    pub fn sent(&self, connection_id: &str) -> Vec<Vec<u8>> {
+        self.sent.lock().ok()
+            .and_then(|sent| sent.get(connection_id).cloned())
+            .unwrap_or_default()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn connect(&self, _config: &NetworkConfig) -> CoreBaseResult<String> {
+        self.pending.lock().map_err(|_| journal_lock_error())?
+            .pop_front()
+            .ok_or_else(|| CoreBaseError::ResourceNotFound("No more connections in journal".to_string(), None))
+    }
+    
+    fn send(&self, connection_id: &str, data: &[u8]) -> CoreBaseResult<()> {
+        self.sent.lock().map_err(|_| journal_lock_error())?
+            .entry(connection_id.to_string())
+            .or_default()
+            .push(data.to_vec());
+        Ok(())
+    }
+    
+    fn receive(&self, connection_id: &str, _timeout: Option<Duration>) -> CoreBaseResult<Option<Vec<u8>>> {
+        let mut inbound = self.inbound.lock().map_err(|_| journal_lock_error())?;
+        let queue = inbound.get_mut(connection_id).ok_or_else(|| CoreBaseError::NetworkError(
+            format!("Replayed connection {} is closed", connection_id),
+            None,
+        ))?;
+        Ok(queue.pop_front())
+    }
+    
+    fn close(&self, connection_id: &str) -> CoreBaseResult<()> {
+        if let Ok(mut inbound) = self.inbound.lock() {
+            inbound.remove(connection_id);
+        }
+        Ok(())
+    }
+}
+
+static NEXT_REPLAY: AtomicU64 = AtomicU64::new(1);
+
+impl NetworkManager {
+    /// Replay a journal file
+    ///
+    /// Registers a `ReplayTransport` for the journal and opens one
+    /// connection per recorded connection, in order of first appearance.
+    /// Receiving on them returns the recorded messages.
+    pub fn replay<P: AsRef<Path>>(&self, path: P) -> CoreBaseResult<Vec<NetworkConnection>> {
+        let transport = ReplayTransport::new(Journal::read(path)?);
+        let count = transport.pending.lock().map(|pending| pending.len()).unwrap_or(0);
+        
+        let name = format!("replay-{}", NEXT_REPLAY.fetch_add(1, Ordering::Relaxed));
+        self.register_transport(&name, transport)?;
+        (0..count)
+            .map(|_| self.create_connection(NetworkConfig::custom(&name, "journal", 0)))
+            .collect()
+    }
+}
+
+/// Bytes that make the connection layer rebuild a recorded message
+///
+/// Replies and other messages whose headers matter are replayed as the
+/// JSON envelopes they arrived in.
+fn replayed_payload(message: &NetworkMessage) -> Vec<u8> {
+    if message.headers.contains_key(CORRELATION_ID_HEADER) {
+        if let Ok(envelope) = serde_json::to_vec(message) {
+            return envelope;
+        }
+    }
+    message.data.clone()
+}
+
+fn journal_io_error(path: &Path, error: std::io::Error) -> CoreBaseError {
+    CoreBaseError::OperationFailed(format!("Journal {}: {}", path.display(), error), None)
+}
+
+fn journal_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access journal".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_journal_and_replay() {
+        let path = std::env::temp_dir().join(format!("cba-journal-{}.jsonl", std::process::id()));
+        let journal = Journal::create(&path).unwrap().with_redaction(|message| {
+            if message.headers.contains_key("authorization") {
+                message.headers.insert("authorization".to_string(), "<redacted>".to_string());
+            }
+        });
+        
+        let manager = NetworkManager::new().unwrap();
+        let config = NetworkConfig::in_memory().with_journal(journal.clone());
+        let (client, server) = manager.create_pair(config).unwrap();
+        client.send(&NetworkMessage::new_text("login").with_header("authorization", "secret")).unwrap();
+        server.receive().unwrap();
+        server.send(&NetworkMessage::new_text("welcome")).unwrap();
+        client.receive().unwrap();
+        journal.flush().unwrap();
+        
+        let entries = Journal::read(&path).unwrap();
+        let summary: Vec<(&str, Direction, String)> = entries
+            .iter()
+            .map(|entry| (entry.connection_id.as_str(), entry.direction, entry.message.as_text().unwrap()))
+            .collect();
+        assert_eq!(summary, [
+            (client.id.as_str(), Direction::Sent, "login".to_string()),
+            (server.id.as_str(), Direction::Received, "login".to_string()),
+            (server.id.as_str(), Direction::Sent, "welcome".to_string()),
+            (client.id.as_str(), Direction::Received, "welcome".to_string()),
+        ]);
+        assert_eq!(entries[0].message.headers["authorization"], "<redacted>");
+        
+        let replayed = manager.replay(&path).unwrap();
+        assert_eq!(replayed.len(), 2);
+        assert!(replayed[0].id.ends_with(&client.id));
+        assert_eq!(replayed[0].receive().unwrap().as_text().unwrap(), "welcome");
+        assert!(replayed[0].try_receive().unwrap().is_none());
+        assert_eq!(replayed[1].receive().unwrap().as_text().unwrap(), "login");
+        
+        std::fs::remove_file(&path).unwrap();
+        assert!(Journal::read(&path).is_err());
+    }
+}
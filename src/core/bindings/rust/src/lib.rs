@@ -11,9 +11,32 @@ use std::sync::{Arc, Mutex, Once};
 use std::collections::HashMap;
 
 pub mod error;
+pub mod logger;
+pub mod log_sink;
 pub mod config;
+pub mod config_format;
+pub mod config_store;
+pub mod codec;
+pub mod compression;
 pub mod network;
+pub mod network_pool;
+pub mod network_ops;
+pub mod circuit_breaker;
+pub mod retry;
+pub mod buffer_pool;
+pub mod mqtt;
+pub mod amqp;
+pub mod http;
+pub mod transport;
+pub mod router;
+pub mod journal;
+mod instrument;
+mod telemetry;
+mod util;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod monitor;
+pub mod session;
 
 use error::*;
 use config::*;
@@ -22,7 +45,7 @@ use monitor::*;
 
 /// Log levels matching the C++ LogLevel enum
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 pub enum LogLevel {
     Debug = 0,
     Info = 1,
@@ -52,6 +75,12 @@ impl From<LogLevel> for c_int {
 
 /// External C++ function declarations
 extern "C" {
+    // Last error reported by the C++ side on the calling thread
+    fn cba_get_last_error(message: *mut c_char, message_size: c_int) -> c_int;
+    
+    // Strings allocated by the C++ side
+    fn cba_free_string(s: *mut c_char);
+    
     // ErrorHandler functions
     fn cba_error_handler_initialize() -> c_int;
     fn cba_error_handler_shutdown() -> c_int;
@@ -59,26 +88,145 @@ extern "C" {
     fn cba_error_handler_set_log_level(level: c_int) -> c_int;
     fn cba_error_handler_get_log_level() -> c_int;
     fn cba_error_handler_log(level: c_int, message: *const c_char) -> c_int;
+    fn cba_error_handler_log_structured(level: c_int, message: *const c_char, fields_json: *const c_char) -> c_int;
+    fn cba_error_handler_set_callback(callback: Option<error::CbaErrorCallback>) -> c_int;
     
     // ConfigManager functions
     fn cba_config_load(filename: *const c_char) -> c_int;
     fn cba_config_get_value(key: *const c_char, buffer: *mut c_char, buffer_size: c_int) -> c_int;
     fn cba_config_set_value(key: *const c_char, value: *const c_char) -> c_int;
     fn cba_config_save(filename: *const c_char) -> c_int;
+    fn cba_config_remove_value(key: *const c_char) -> c_int;
     
     // NetworkManager functions
     fn cba_network_initialize() -> c_int;
-    fn cba_network_create_connection(host: *const c_char, port: c_int, protocol: c_int) -> *mut c_char;
-    fn cba_network_send_message(connection_id: *const c_char, message: *const c_char) -> c_int;
-    fn cba_network_receive_message(connection_id: *const c_char, buffer: *mut c_char, buffer_size: c_int) -> c_int;
+    fn cba_network_create_connection_to(host: *const c_char, address: *const c_char, port: c_int, protocol: c_int, headers: *const c_char, timeout_ms: c_int, timed_out: *mut c_int) -> *mut c_char;
+    fn cba_network_send_bytes_timeout(connection_id: *const c_char, data: *const u8, data_len: c_int, timeout_ms: c_int) -> c_int;
+    fn cba_network_send_batch(connection_id: *const c_char, data: *const u8, lengths: *const c_int, count: c_int, timeout_ms: c_int, sent: *mut c_int) -> c_int;
+    fn cba_network_receive_chunk(connection_id: *const c_char, buffer: *mut u8, buffer_size: c_int, timeout_ms: c_int, more: *mut c_int) -> c_int;
     fn cba_network_close_connection(connection_id: *const c_char) -> c_int;
+    fn cba_network_poll_readable(connection_id: *const c_char, timeout_ms: c_int) -> c_int;
+    fn cba_network_poll_any(connection_ids: *const *const c_char, count: c_int, timeout_ms: c_int, ready: *mut c_int) -> c_int;
+    #[cfg(feature = "async")]
+    fn cba_network_get_socket_fd(connection_id: *const c_char) -> c_int;
+    fn cba_network_listen(host: *const c_char, port: c_int, protocol: c_int) -> *mut c_char;
+    fn cba_network_accept(listener_id: *const c_char, buffer: *mut c_char, buffer_size: c_int, timeout_ms: c_int) -> c_int;
+    fn cba_network_close_listener(listener_id: *const c_char) -> c_int;
+    fn cba_network_join_multicast(connection_id: *const c_char, group: *const c_char, interface: *const c_char) -> c_int;
+    fn cba_network_leave_multicast(connection_id: *const c_char, group: *const c_char, interface: *const c_char) -> c_int;
+    fn cba_network_set_multicast_ttl(connection_id: *const c_char, ttl: c_int) -> c_int;
+    fn cba_network_set_broadcast(connection_id: *const c_char, enabled: c_int) -> c_int;
+    fn cba_network_set_nodelay(connection_id: *const c_char, enabled: c_int) -> c_int;
+    fn cba_network_set_tcp_keepalive(connection_id: *const c_char, enabled: c_int) -> c_int;
+    fn cba_network_set_send_buffer_size(connection_id: *const c_char, size: c_int) -> c_int;
+    fn cba_network_set_recv_buffer_size(connection_id: *const c_char, size: c_int) -> c_int;
+    fn cba_network_set_linger(connection_id: *const c_char, enabled: c_int, seconds: c_int) -> c_int;
+    fn cba_quic_open_stream(connection_id: *const c_char) -> *mut c_char;
+    fn cba_quic_accept_stream(connection_id: *const c_char, timeout_ms: c_int, timed_out: *mut c_int) -> *mut c_char;
+    
+    // MQTT functions
+    fn cba_mqtt_connect(
+        host: *const c_char,
+        port: c_int,
+        client_id: *const c_char,
+        username: *const c_char,
+        password: *const c_char,
+        keep_alive_secs: c_int,
+        clean_session: c_int,
+        will_topic: *const c_char,
+        will_payload: *const u8,
+        will_payload_len: c_int,
+        will_qos: c_int,
+        will_retain: c_int,
+    ) -> *mut c_char;
+    fn cba_mqtt_publish(connection_id: *const c_char, topic: *const c_char, payload: *const u8, payload_len: c_int, qos: c_int, retain: c_int) -> c_int;
+    fn cba_mqtt_subscribe(connection_id: *const c_char, filter: *const c_char, qos: c_int) -> c_int;
+    fn cba_mqtt_unsubscribe(connection_id: *const c_char, filter: *const c_char) -> c_int;
+    fn cba_mqtt_poll_message(
+        connection_id: *const c_char,
+        topic_buffer: *mut c_char,
+        topic_buffer_size: c_int,
+        payload_buffer: *mut u8,
+        payload_buffer_size: c_int,
+        payload_len: *mut c_int,
+        qos: *mut c_int,
+        retained: *mut c_int,
+        timeout_ms: c_int,
+    ) -> c_int;
+    fn cba_mqtt_disconnect(connection_id: *const c_char) -> c_int;
+    
+    // AMQP functions
+    fn cba_amqp_connect(
+        host: *const c_char,
+        port: c_int,
+        username: *const c_char,
+        password: *const c_char,
+        vhost: *const c_char,
+        heartbeat_secs: c_int,
+    ) -> *mut c_char;
+    fn cba_amqp_declare_queue(
+        connection_id: *const c_char,
+        queue: *const c_char,
+        durable: c_int,
+        exclusive: c_int,
+        auto_delete: c_int,
+        name_buffer: *mut c_char,
+        name_buffer_size: c_int,
+    ) -> c_int;
+    fn cba_amqp_bind_queue(connection_id: *const c_char, queue: *const c_char, exchange: *const c_char, routing_key: *const c_char) -> c_int;
+    fn cba_amqp_publish(connection_id: *const c_char, exchange: *const c_char, routing_key: *const c_char, payload: *const u8, payload_len: c_int, persistent: c_int) -> c_int;
+    fn cba_amqp_set_prefetch(connection_id: *const c_char, prefetch_count: c_int) -> c_int;
+    fn cba_amqp_consume(connection_id: *const c_char, queue: *const c_char, auto_ack: c_int, tag_buffer: *mut c_char, tag_buffer_size: c_int) -> c_int;
+    fn cba_amqp_cancel(connection_id: *const c_char, consumer_tag: *const c_char) -> c_int;
+    fn cba_amqp_poll_delivery(
+        connection_id: *const c_char,
+        tag_buffer: *mut c_char,
+        tag_buffer_size: c_int,
+        routing_key_buffer: *mut c_char,
+        routing_key_buffer_size: c_int,
+        payload_buffer: *mut u8,
+        payload_buffer_size: c_int,
+        payload_len: *mut c_int,
+        delivery_tag: *mut u64,
+        redelivered: *mut c_int,
+        timeout_ms: c_int,
+    ) -> c_int;
+    fn cba_amqp_ack(connection_id: *const c_char, delivery_tag: u64, multiple: c_int) -> c_int;
+    fn cba_amqp_nack(connection_id: *const c_char, delivery_tag: u64, multiple: c_int, requeue: c_int) -> c_int;
+    fn cba_amqp_close(connection_id: *const c_char) -> c_int;
     
     // SystemMonitor functions
     fn cba_monitor_get_cpu_usage() -> c_double;
+    fn cba_monitor_get_cpu_per_core(usage: *mut c_double, capacity: c_int) -> c_int;
     fn cba_monitor_get_memory_usage(available: *mut c_double, total: *mut c_double) -> c_int;
+    fn cba_monitor_get_memory_breakdown(
+        total: *mut c_double,
+        free: *mut c_double,
+        available: *mut c_double,
+        cached: *mut c_double,
+        buffers: *mut c_double,
+        shared: *mut c_double,
+        commit_charge: *mut c_double,
+        commit_limit: *mut c_double,
+    ) -> c_int;
     fn cba_monitor_get_disk_usage(available: *mut c_double, total: *mut c_double) -> c_int;
+    fn cba_monitor_list_disk_io() -> *mut c_char;
     fn cba_monitor_get_network_usage() -> c_double;
+    fn cba_monitor_list_network_io() -> *mut c_char;
+    fn cba_monitor_get_load_average(one: *mut c_double, five: *mut c_double, fifteen: *mut c_double) -> c_int;
+    fn cba_monitor_get_uptime() -> c_double;
+    fn cba_monitor_get_swap_usage(used: *mut c_double, total: *mut c_double) -> c_int;
     fn cba_monitor_get_gpu_usage() -> c_double;
+    fn cba_monitor_list_gpus() -> *mut c_char;
+    fn cba_monitor_get_process_usage(
+        cpu_percent: *mut c_double,
+        rss_bytes: *mut u64,
+        virtual_bytes: *mut u64,
+        open_fds: *mut c_int,
+        threads: *mut c_int,
+    ) -> c_int;
+    fn cba_monitor_list_processes() -> *mut c_char;
+    fn cba_monitor_get_thermal_info(cpu_celsius: *mut c_double, gpu_celsius: *mut c_double, fan_rpm: *mut c_double, fan_capacity: c_int) -> c_int;
 }
 
 /// Global initialization state
@@ -105,8 +253,9 @@ pub fn initialize() -> Result<(), CoreBaseError> {
                 INITIALIZED = true;
             } else {
                 result = Err(CoreBaseError::InitializationFailed(
-                    "Failed to initialize CoreBase components".to_string()
-                ));
+                    "Failed to initialize CoreBase components".to_string(),
+                    None,
+                ).with_last_error());
             }
         }
     });
@@ -127,8 +276,9 @@ pub fn shutdown() -> Result<(), CoreBaseError> {
                 Ok(())
             } else {
                 Err(CoreBaseError::ShutdownFailed(
-                    "Failed to shutdown CoreBase components".to_string()
-                ))
+                    "Failed to shutdown CoreBase components".to_string(),
+                    None,
+                ).with_last_error())
             }
         } else {
             Ok(()) // Already shutdown or never initialized
@@ -143,20 +293,50 @@ pub fn is_initialized() -> bool {
 
 /// Utility function to convert Rust string to C string
 fn to_c_string(s: &str) -> Result<CString, CoreBaseError> {
-    CString::new(s).map_err(|e| CoreBaseError::InvalidString(e.to_string()))
+    CString::new(s).map_err(|e| CoreBaseError::InvalidString(e.to_string(), None))
 }
 
-/// Utility function to convert C string to Rust string
-fn from_c_string(ptr: *const c_char) -> Result<String, CoreBaseError> {
-    if ptr.is_null() {
-        return Ok(String::new());
+/// Owned string allocated by the C++ side
+///
+/// Wraps the `char*` returned by functions such as
+/// `cba_network_create_connection_to` and releases it with
+/// `cba_free_string` when dropped.
+#[derive(Debug)]
+pub(crate) struct CbaString {
+    ptr: *mut c_char,
+}
+
+impl CbaString {
+    /// Take ownership of a C++-allocated string, or `None` for null
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or a string allocated by the C++ side that is
+    /// not freed elsewhere.
+    pub(crate) unsafe fn from_raw(ptr: *mut c_char) -> Option<Self> {
+        if ptr.is_null() {
+            None
+        } else {
+            Some(CbaString { ptr })
+        }
     }
     
-    unsafe {
-        CStr::from_ptr(ptr)
+    /// Borrow the string as UTF-8
+    pub(crate) fn to_str(&self) -> Result<&str, CoreBaseError> {
+        unsafe { CStr::from_ptr(self.ptr) }
             .to_str()
-            .map(|s| s.to_string())
-            .map_err(|e| CoreBaseError::InvalidString(e.to_string()))
+            .map_err(|e| CoreBaseError::InvalidString(e.to_string(), None))
+    }
+    
+    /// Copy the string into Rust memory and free the original
+    pub(crate) fn into_string(self) -> Result<String, CoreBaseError> {
+        self.to_str().map(str::to_string)
+    }
+}
+
+impl Drop for CbaString {
+    fn drop(&mut self) {
+        unsafe { cba_free_string(self.ptr) }
     }
 }
 
@@ -176,11 +356,16 @@ impl CoreBase {
     pub fn new() -> Result<Self, CoreBaseError> {
         initialize()?;
         
+        // Network health is recorded alongside the system resources
+        let network_manager = NetworkManager::new()?;
+        let mut system_monitor = SystemMonitor::new()?;
+        system_monitor.add_metric_source(network_manager.metrics_source());
+        
         Ok(CoreBase {
             error_handler: ErrorHandler::new()?,
             config_manager: ConfigManager::new()?,
-            network_manager: NetworkManager::new()?,
-            system_monitor: SystemMonitor::new()?,
+            network_manager,
+            system_monitor,
         })
     }
     
@@ -218,6 +403,11 @@ impl CoreBase {
     pub fn system_monitor(&self) -> &SystemMonitor {
         &self.system_monitor
     }
+    
+    /// Get a mutable reference to the system monitor
+    pub fn system_monitor_mut(&mut self) -> &mut SystemMonitor {
+        &mut self.system_monitor
+    }
 }
 
 impl Drop for CoreBase {
@@ -295,6 +485,11 @@ mod tests {
         assert!(cba.is_ok());
     }
     
+    #[test]
+    fn test_cba_string_null() {
+        assert!(unsafe { CbaString::from_raw(ptr::null_mut()) }.is_none());
+    }
+    
     #[test]
     fn test_log_level_conversion() {
         assert_eq!(LogLevel::from(0), LogLevel::Debug);
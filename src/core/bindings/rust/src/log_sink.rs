@@ -0,0 +1,772 @@
+//! Log sinks for CoreBase Rust bindings
+//!
+//! This module provides the `LogSink` trait, the destinations
+//! `ErrorHandler` fans log messages out to, and the built-in console, file,
+//! rotating file and C++ ErrorHandler sinks. Any `Fn(&LogRecord)` closure
+//! is a sink too. On Unix, the "syslog" and "journald" features add sinks
+//! for the system logger.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(all(unix, any(feature = "syslog", feature = "journald")))]
+use std::os::unix::net::UnixDatagram;
+
+use serde_json::{Map, Value};
+
+use crate::config::{config_bool, config_entry, config_number, config_string, ConfigManager};
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::{to_c_string, LogLevel};
+
+/// Seconds in a UTC day
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Message passed to log sinks
+#[derive(Debug, Clone, Copy)]
+pub struct LogRecord<'a> {
+    pub level: LogLevel,
+    pub message: &'a str,
+    /// Key-value fields from `ErrorHandler::log_kv`, empty otherwise
+    pub fields: &'a [(&'a str, Value)],
+}
+
+impl LogRecord<'_> {
+    /// Format the record as one line with a timestamp and level
+    pub fn to_line(&self) -> String {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        format!("{} [{:?}] {}", timestamp, self.level, self)
+    }
+}
+
+/// Shows the message with the fields appended in logfmt style, quoting
+/// strings that contain spaces, quotes or `=`
+impl fmt::Display for LogRecord<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        for (key, value) in self.fields {
+            match value {
+                Value::String(text) if !text.is_empty() && !text.contains([' ', '"', '=']) => {
+                    write!(f, " {}={}", key, text)?
+                }
+                value => write!(f, " {}={}", key, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Destination of log messages
+///
+/// Sinks are added to an `ErrorHandler` with a minimum level each, and
+/// receive every message at or above it.
+pub trait LogSink: Send + Sync {
+    /// Write one record
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()>;
+    
+    /// Write out buffered records
+    fn flush(&self) -> CoreBaseResult<()> {
+        Ok(())
+    }
+}
+
+impl<F> LogSink for F
+where
+    F: Fn(&LogRecord<'_>) + Send + Sync,
+{
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        self(record);
+        Ok(())
+    }
+}
+
+/// Sink forwarding records to the C++ ErrorHandler, which applies its own
+/// log level
+///
+/// Fields reach the C++ side as a JSON object. If it can't log structured
+/// records, they are appended to the message instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CppSink;
+
+impl LogSink for CppSink {
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        if !record.fields.is_empty() {
+            let object: Map<String, Value> = record.fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect();
+            let c_message = to_c_string(record.message)?;
+            let c_fields = to_c_string(&Value::Object(object).to_string())?;
+            let result = unsafe {
+                crate::cba_error_handler_log_structured(record.level.into(), c_message.as_ptr(), c_fields.as_ptr())
+            };
+            if result == 0 {
+                return Ok(());
+            }
+        }
+        
+        let c_message = to_c_string(&record.to_string())?;
+        let result = unsafe { crate::cba_error_handler_log(record.level.into(), c_message.as_ptr()) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(CoreBaseError::OperationFailed(
+                "Failed to log message".to_string(),
+                None,
+            ).with_last_error())
+        }
+    }
+}
+
+/// Sink writing lines to standard error
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleSink;
+
+impl LogSink for ConsoleSink {
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        writeln!(io::stderr(), "{}", record.to_line()).map_err(sink_io_error)
+    }
+}
+
+/// Sink appending lines to a file
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Open a file for appending, creating it if needed
+    pub fn open<P: AsRef<Path>>(path: P) -> CoreBaseResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .map_err(sink_io_error)?;
+        Ok(FileSink {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl LogSink for FileSink {
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        let mut file = self.file.lock().map_err(|_| sink_lock_error())?;
+        writeln!(file, "{}", record.to_line()).map_err(sink_io_error)
+    }
+    
+    fn flush(&self) -> CoreBaseResult<()> {
+        self.file.lock().map_err(|_| sink_lock_error())?.flush().map_err(sink_io_error)
+    }
+}
+
+/// When a `RotatingFileSink` moves the current file aside
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationPolicy {
+    /// Rotate before a write would take the file past this size
+    pub max_bytes: Option<u64>,
+    /// Rotate at the first write of each UTC day
+    pub daily: bool,
+    /// Rotated files kept as `<path>.1` (newest) to `<path>.<max_files>`
+    pub max_files: usize,
+    /// Gzip rotated files to `<path>.<n>.gz` (requires "gzip" feature)
+    pub compress: bool,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        RotationPolicy {
+            max_bytes: Some(10 * 1024 * 1024),
+            daily: false,
+            max_files: 5,
+            compress: false,
+        }
+    }
+}
+
+/// Sink appending lines to a file that is rotated by size or date
+#[derive(Debug)]
+pub struct RotatingFileSink {
+    path: PathBuf,
+    policy: RotationPolicy,
+    current: Mutex<CurrentFile>,
+}
+
+/// File being written by a `RotatingFileSink`
+#[derive(Debug)]
+struct CurrentFile {
+    file: File,
+    size: u64,
+    /// UTC day of the last write, in days since the Unix epoch
+    day: u64,
+}
+
+impl RotatingFileSink {
+    /// Open a file for appending, creating it if needed
+    pub fn open<P: AsRef<Path>>(path: P, policy: RotationPolicy) -> CoreBaseResult<Self> {
+        if policy.compress && cfg!(not(feature = "gzip")) {
+            return Err(CoreBaseError::InvalidParameter(
+                "Compressing rotated logs requires the \"gzip\" feature".to_string(),
+                None,
+            ));
+        }
+        
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path).map_err(sink_io_error)?;
+        let metadata = file.metadata().map_err(sink_io_error)?;
+        // A file last written on an earlier day is rotated on the first write
+        let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+        Ok(RotatingFileSink {
+            path,
+            policy,
+            current: Mutex::new(CurrentFile {
+                file,
+                size: metadata.len(),
+                day: utc_day(modified),
+            }),
+        })
+    }
+    
+    /// Open the file configured under `prefix`
+    ///
+    /// Reads `<prefix>.path`, which is required, and `.max_bytes`,
+    /// `.daily`, `.max_files` and `.compress`; missing keys keep the
+    /// `RotationPolicy` defaults. A `max_bytes` of 0 disables size-based
+    /// rotation.
+    pub fn from_config(config: &mut ConfigManager, prefix: &str) -> CoreBaseResult<Self> {
+        let key = |name: &str| format!("{}.{}", prefix, name);
+        let path = config_entry(config, &key("path"))
+            .ok_or_else(|| CoreBaseError::ConfigError(format!("Missing config key '{}'", key("path")), None))?;
+        let path = config_string(&key("path"), &path)?;
+        
+        let mut policy = RotationPolicy::default();
+        if let Some(max_bytes) = config_entry(config, &key("max_bytes")) {
+            let max_bytes: u64 = config_number(&key("max_bytes"), &max_bytes)?;
+            policy.max_bytes = (max_bytes > 0).then_some(max_bytes);
+        }
+        if let Some(daily) = config_entry(config, &key("daily")) {
+            policy.daily = config_bool(&key("daily"), &daily)?;
+        }
+        if let Some(max_files) = config_entry(config, &key("max_files")) {
+            policy.max_files = config_number(&key("max_files"), &max_files)?;
+        }
+        if let Some(compress) = config_entry(config, &key("compress")) {
+            policy.compress = config_bool(&key("compress"), &compress)?;
+        }
+        Self::open(path, policy)
+    }
+    
+    /// Get the rotation policy
+    pub fn policy(&self) -> &RotationPolicy {
+        &self.policy
+    }
+    
+    /// Path of the `index`th rotated file
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        if self.policy.compress {
+            path.push(".gz");
+        }
+        PathBuf::from(path)
+    }
+    
+    /// Shift the rotated files up by one, dropping the oldest, and start
+    /// an empty file
+    fn rotate(&self, current: &mut CurrentFile) -> CoreBaseResult<()> {
+        current.file.flush().map_err(sink_io_error)?;
+        if self.policy.max_files > 0 {
+            // Missing files are skipped
+            let _ = fs::remove_file(self.rotated_path(self.policy.max_files));
+            for index in (1..self.policy.max_files).rev() {
+                let _ = fs::rename(self.rotated_path(index), self.rotated_path(index + 1));
+            }
+            
+            #[cfg(feature = "gzip")]
+            if self.policy.compress {
+                let contents = fs::read(&self.path).map_err(sink_io_error)?;
+                let compressed = crate::compression::Compression::Gzip.compress(&contents)?;
+                fs::write(self.rotated_path(1), compressed).map_err(sink_io_error)?;
+            }
+            if !self.policy.compress {
+                fs::rename(&self.path, self.rotated_path(1)).map_err(sink_io_error)?;
+            }
+        }
+        
+        current.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)
+            .map_err(sink_io_error)?;
+        current.size = 0;
+        Ok(())
+    }
+}
+
+impl LogSink for RotatingFileSink {
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        let line = format!("{}\n", record.to_line());
+        let today = utc_day(SystemTime::now());
+        let mut current = self.current.lock().map_err(|_| sink_lock_error())?;
+        
+        let full = self.policy.max_bytes
+            .is_some_and(|max_bytes| current.size > 0 && current.size + line.len() as u64 > max_bytes);
+        if full || (self.policy.daily && current.day != today) {
+            self.rotate(&mut current)?;
+        }
+        current.file.write_all(line.as_bytes()).map_err(sink_io_error)?;
+        current.size += line.len() as u64;
+        current.day = today;
+        Ok(())
+    }
+    
+    fn flush(&self) -> CoreBaseResult<()> {
+        self.current.lock().map_err(|_| sink_lock_error())?.file.flush().map_err(sink_io_error)
+    }
+}
+
+impl LogLevel {
+    /// Get the syslog severity of a level, from 2 (critical) to 7 (debug)
+    pub fn syslog_severity(self) -> u8 {
+        match self {
+            LogLevel::Debug => 7,
+            LogLevel::Info => 6,
+            LogLevel::Warning => 4,
+            LogLevel::Error => 3,
+            LogLevel::Critical => 2,
+        }
+    }
+}
+
+/// Syslog facility, telling the system logger what kind of program logged
+/// a message
+#[cfg(all(unix, feature = "syslog"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyslogFacility {
+    #[default]
+    User = 1,
+    Daemon = 3,
+    Auth = 4,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// Sink sending RFC 5424 messages to the local syslog daemon
+///
+/// Fields are appended to the message as by `LogRecord`'s `Display`.
+/// Line breaks in the message are escaped as `\n` and `\r`, since daemons
+/// that split on them would log one record as several.
+#[cfg(all(unix, feature = "syslog"))]
+#[derive(Debug)]
+pub struct SyslogSink {
+    socket: UnixDatagram,
+    facility: SyslogFacility,
+    hostname: String,
+    app_name: String,
+}
+
+#[cfg(all(unix, feature = "syslog"))]
+impl SyslogSink {
+    /// Socket the syslog daemon listens on
+    pub const DEFAULT_SOCKET: &'static str = "/dev/log";
+    
+    /// Connect to the syslog daemon on `/dev/log`
+    pub fn new(app_name: &str, facility: SyslogFacility) -> CoreBaseResult<Self> {
+        Self::connect(Self::DEFAULT_SOCKET, app_name, facility)
+    }
+    
+    /// Connect to a syslog daemon listening on a Unix datagram socket
+    pub fn connect<P: AsRef<Path>>(socket_path: P, app_name: &str, facility: SyslogFacility) -> CoreBaseResult<Self> {
+        let socket = UnixDatagram::unbound().map_err(sink_io_error)?;
+        socket.connect(socket_path).map_err(sink_io_error)?;
+        
+        let mut buffer = [0u8; 256];
+        let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+        let hostname = if result == 0 {
+            let length = buffer.iter().position(|&byte| byte == 0).unwrap_or(buffer.len());
+            syslog_header_field(&String::from_utf8_lossy(&buffer[..length]), 255)
+        } else {
+            "-".to_string()
+        };
+        Ok(SyslogSink {
+            socket,
+            facility,
+            hostname,
+            app_name: syslog_header_field(app_name, 48),
+        })
+    }
+    
+    /// Format a record as an RFC 5424 message
+    fn format(&self, record: &LogRecord<'_>) -> String {
+        let priority = self.facility as u8 * 8 + record.level.syslog_severity();
+        format!(
+            "<{}>1 {} {} {} {} - - {}",
+            priority,
+            rfc3339_timestamp(SystemTime::now()),
+            self.hostname,
+            self.app_name,
+            std::process::id(),
+            record.to_string().replace('\r', "\\r").replace('\n', "\\n")
+        )
+    }
+}
+
+#[cfg(all(unix, feature = "syslog"))]
+impl LogSink for SyslogSink {
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        self.socket.send(self.format(record).as_bytes()).map_err(sink_io_error)?;
+        Ok(())
+    }
+}
+
+/// Header fields are printable ASCII without spaces, or `-` when empty
+#[cfg(all(unix, feature = "syslog"))]
+fn syslog_header_field(value: &str, max_len: usize) -> String {
+    let field: String = value
+        .chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .take(max_len)
+        .collect();
+    if field.is_empty() { "-".to_string() } else { field }
+}
+
+/// Format a time as an RFC 3339 UTC timestamp with milliseconds
+#[cfg(all(unix, feature = "syslog"))]
+fn rfc3339_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let days = (seconds / SECONDS_PER_DAY) as i64;
+    let seconds_of_day = seconds % SECONDS_PER_DAY;
+    
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Sink sending records to systemd-journald over its native protocol
+///
+/// The message, priority and identifier go in the standard `MESSAGE`,
+/// `PRIORITY` and `SYSLOG_IDENTIFIER` fields. Record fields become journal
+/// fields, with names upper-cased and characters other than letters,
+/// digits and `_` replaced by `_`; string values are sent as-is and others
+/// as JSON. Fields that would collide with the standard ones, such as
+/// `message`, are prefixed with `FIELD_`.
+#[cfg(all(unix, feature = "journald"))]
+#[derive(Debug)]
+pub struct JournaldSink {
+    socket: UnixDatagram,
+    identifier: String,
+}
+
+#[cfg(all(unix, feature = "journald"))]
+impl JournaldSink {
+    /// Socket journald listens on
+    pub const DEFAULT_SOCKET: &'static str = "/run/systemd/journal/socket";
+    
+    /// Connect to journald
+    pub fn new(identifier: &str) -> CoreBaseResult<Self> {
+        Self::connect(Self::DEFAULT_SOCKET, identifier)
+    }
+    
+    /// Connect to a journal listening on a Unix datagram socket
+    pub fn connect<P: AsRef<Path>>(socket_path: P, identifier: &str) -> CoreBaseResult<Self> {
+        let socket = UnixDatagram::unbound().map_err(sink_io_error)?;
+        socket.connect(socket_path).map_err(sink_io_error)?;
+        Ok(JournaldSink {
+            socket,
+            identifier: identifier.to_string(),
+        })
+    }
+    
+    /// Serialize a record as one native protocol datagram
+    fn encode(&self, record: &LogRecord<'_>) -> Vec<u8> {
+        let mut datagram = Vec::new();
+        push_journal_field(&mut datagram, "MESSAGE", record.message.as_bytes());
+        push_journal_field(&mut datagram, "PRIORITY", record.level.syslog_severity().to_string().as_bytes());
+        push_journal_field(&mut datagram, "SYSLOG_IDENTIFIER", self.identifier.as_bytes());
+        for (key, value) in record.fields {
+            let value = match value {
+                Value::String(text) => text.clone(),
+                value => value.to_string(),
+            };
+            push_journal_field(&mut datagram, &journal_field_name(key), value.as_bytes());
+        }
+        datagram
+    }
+}
+
+#[cfg(all(unix, feature = "journald"))]
+impl LogSink for JournaldSink {
+    fn write(&self, record: &LogRecord<'_>) -> CoreBaseResult<()> {
+        self.socket.send(&self.encode(record)).map_err(sink_io_error)?;
+        Ok(())
+    }
+}
+
+/// Field names are up to 64 upper-case letters, digits and `_`, not
+/// starting with a digit or `_`
+///
+/// Names the sink sets itself get a `FIELD_` prefix, so a record field
+/// never overrides the message, priority or identifier.
+#[cfg(all(unix, feature = "journald"))]
+fn journal_field_name(key: &str) -> String {
+    const RESERVED: [&str; 3] = ["MESSAGE", "PRIORITY", "SYSLOG_IDENTIFIER"];
+    
+    let name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    let name = name.trim_start_matches('_');
+    let prefixed = name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) || RESERVED.contains(&name);
+    let name = if prefixed {
+        format!("FIELD_{}", name)
+    } else {
+        name.to_string()
+    };
+    name.chars().take(64).collect()
+}
+
+/// Append `NAME=value\n`, or the length-prefixed form for multi-line values
+#[cfg(all(unix, feature = "journald"))]
+fn push_journal_field(datagram: &mut Vec<u8>, name: &str, value: &[u8]) {
+    datagram.extend_from_slice(name.as_bytes());
+    if value.contains(&b'\n') {
+        datagram.push(b'\n');
+        datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        datagram.push(b'=');
+    }
+    datagram.extend_from_slice(value);
+    datagram.push(b'\n');
+}
+
+fn utc_day(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() / SECONDS_PER_DAY
+}
+
+fn sink_io_error(error: io::Error) -> CoreBaseError {
+    CoreBaseError::OperationFailed(format!("Failed to write log: {}", error), None)
+}
+
+fn sink_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access log file".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    
+    use crate::error::ErrorHandler;
+    
+    #[test]
+    fn test_log_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        
+        // Replace the default CppSink so nothing reaches the native logger
+        let mut handler = ErrorHandler::new().unwrap();
+        assert_eq!(handler.sink_count(), 1);
+        handler.clear_sinks();
+        handler.add_sink(FileSink::open(&path).unwrap(), LogLevel::Warning);
+        let seen = Arc::clone(&captured);
+        handler.add_sink(move |record: &LogRecord<'_>| seen.lock().unwrap().push(record.to_string()), LogLevel::Debug);
+        
+        handler.debug("starting").unwrap();
+        handler.log_kv(LogLevel::Error, "send failed", &[("conn_id", "tcp-7".into()), ("peer", "db 2".into())]).unwrap();
+        handler.flush().unwrap();
+        
+        assert_eq!(*captured.lock().unwrap(), ["starting", r#"send failed conn_id=tcp-7 peer="db 2""#]);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.trim_end().ends_with(r#"[Error] send failed conn_id=tcp-7 peer="db 2""#));
+        
+        handler.clear_sinks();
+        assert_eq!(handler.sink_count(), 0);
+        handler.info("dropped").unwrap();
+        assert_eq!(captured.lock().unwrap().len(), 2);
+        
+        let sink = FileSink::open(&path).unwrap();
+        sink.write(&LogRecord { level: LogLevel::Info, message: "direct", fields: &[] }).unwrap();
+        sink.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.trim_end().ends_with("[Info] direct"));
+        
+        assert_eq!(LogLevel::Critical.syslog_severity(), 2);
+        assert_eq!(LogLevel::Warning.syslog_severity(), 4);
+        assert_eq!(LogLevel::Debug.syslog_severity(), 7);
+    }
+    
+    #[test]
+    fn test_rotating_file_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let record = |message| LogRecord {
+            level: LogLevel::Info,
+            message,
+            fields: &[],
+        };
+        
+        // Every line is over half the size limit, so each write rotates
+        let policy = RotationPolicy {
+            max_bytes: Some(30),
+            max_files: 2,
+            ..Default::default()
+        };
+        let sink = RotatingFileSink::open(&path, policy).unwrap();
+        for message in ["first", "second", "third", "fourth"] {
+            sink.write(&record(message)).unwrap();
+        }
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert!(read("app.log").ends_with("fourth\n"));
+        assert!(read("app.log.1").ends_with("third\n"));
+        assert!(read("app.log.2").ends_with("second\n"));
+        assert!(!dir.path().join("app.log.3").exists());
+        
+        // A new day starts a new file whatever its size
+        let daily = RotatingFileSink::open(dir.path().join("daily.log"), RotationPolicy {
+            max_bytes: None,
+            daily: true,
+            ..Default::default()
+        }).unwrap();
+        daily.write(&record("monday")).unwrap();
+        daily.write(&record("still monday")).unwrap();
+        daily.current.lock().unwrap().day -= 1;
+        daily.write(&record("tuesday")).unwrap();
+        assert_eq!(read("daily.log.1").lines().count(), 2);
+        assert_eq!(read("daily.log").lines().count(), 1);
+        
+        let mut config = ConfigManager::from_json_str(&format!(
+            r#"{{"logging": {{"file": {{"path": {:?}, "max_bytes": 0, "daily": true, "max_files": 3}}}}}}"#,
+            dir.path().join("configured.log").to_str().unwrap()
+        )).unwrap();
+        let configured = RotatingFileSink::from_config(&mut config, "logging.file").unwrap();
+        assert_eq!(configured.policy(), &RotationPolicy {
+            max_bytes: None,
+            daily: true,
+            max_files: 3,
+            compress: false,
+        });
+        assert!(RotatingFileSink::from_config(&mut config, "logging.missing").is_err());
+    }
+    
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_rotating_file_sink_compression() {
+        use crate::compression::Compression;
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let sink = RotatingFileSink::open(&path, RotationPolicy {
+            max_bytes: Some(1),
+            compress: true,
+            ..Default::default()
+        }).unwrap();
+        for message in ["first", "second"] {
+            sink.write(&LogRecord {
+                level: LogLevel::Warning,
+                message,
+                fields: &[],
+            }).unwrap();
+        }
+        
+        let rotated = fs::read(dir.path().join("app.log.1.gz")).unwrap();
+        let contents = String::from_utf8(Compression::Gzip.decompress(&rotated).unwrap()).unwrap();
+        assert!(contents.ends_with("[Warning] first\n"));
+        assert!(!dir.path().join("app.log.1").exists());
+    }
+    
+    #[cfg(all(unix, feature = "syslog"))]
+    #[test]
+    fn test_syslog_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.sock");
+        let daemon = UnixDatagram::bind(&path).unwrap();
+        
+        let sink = SyslogSink::connect(&path, "my app", SyslogFacility::Daemon).unwrap();
+        sink.write(&LogRecord {
+            level: LogLevel::Error,
+            message: "disk full",
+            fields: &[("mount", "/var".into())],
+        }).unwrap();
+        
+        let mut buffer = [0u8; 1024];
+        let length = daemon.recv(&mut buffer).unwrap();
+        let message = std::str::from_utf8(&buffer[..length]).unwrap().to_string();
+        // Daemon (3) * 8 + error (3)
+        assert!(message.starts_with("<27>1 "));
+        let header: Vec<&str> = message.splitn(8, ' ').collect();
+        assert_eq!(header[1].len(), "2026-01-01T00:00:00.000Z".len());
+        assert_eq!(header[3], "my_app");
+        assert_eq!(header[4], std::process::id().to_string());
+        assert_eq!(header[7], "disk full mount=/var");
+        
+        // Line breaks stay inside the one message
+        sink.write(&LogRecord {
+            level: LogLevel::Error,
+            message: "disk full\r\nforged entry",
+            fields: &[],
+        }).unwrap();
+        let length = daemon.recv(&mut buffer).unwrap();
+        let message = std::str::from_utf8(&buffer[..length]).unwrap();
+        assert!(message.ends_with(" disk full\\r\\nforged entry"), "{}", message);
+        
+        assert_eq!(rfc3339_timestamp(UNIX_EPOCH + std::time::Duration::from_millis(951_782_400_250)), "2000-02-29T00:00:00.250Z");
+        assert!(SyslogSink::connect(dir.path().join("missing.sock"), "app", SyslogFacility::User).is_err());
+    }
+    
+    #[cfg(all(unix, feature = "journald"))]
+    #[test]
+    fn test_journald_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.sock");
+        let journal = UnixDatagram::bind(&path).unwrap();
+        
+        let sink = JournaldSink::connect(&path, "corebase").unwrap();
+        sink.write(&LogRecord {
+            level: LogLevel::Warning,
+            message: "retrying\nin 5s",
+            fields: &[("conn-id", "tcp-7".into()), ("attempt", 2.into()), ("message", "spoofed".into())],
+        }).unwrap();
+        
+        let mut buffer = [0u8; 1024];
+        let length = journal.recv(&mut buffer).unwrap();
+        let mut expected = b"MESSAGE\n".to_vec();
+        expected.extend_from_slice(&14u64.to_le_bytes());
+        expected.extend_from_slice(b"retrying\nin 5s\nPRIORITY=4\nSYSLOG_IDENTIFIER=corebase\nCONN_ID=tcp-7\nATTEMPT=2\n");
+        expected.extend_from_slice(b"FIELD_MESSAGE=spoofed\n");
+        assert_eq!(&buffer[..length], &expected[..]);
+        
+        assert_eq!(journal_field_name("_private"), "PRIVATE");
+        assert_eq!(journal_field_name("2fa"), "FIELD_2FA");
+        assert_eq!(journal_field_name("priority"), "FIELD_PRIORITY");
+        assert_eq!(journal_field_name("syslog-identifier"), "FIELD_SYSLOG_IDENTIFIER");
+    }
+}
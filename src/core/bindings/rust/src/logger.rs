@@ -0,0 +1,116 @@
+//! `log` crate backend for CoreBase Rust bindings
+//!
+//! `CoreBaseLogger` forwards records from the `log` macros to the C++
+//! ErrorHandler, so crates that already log through `log` feed the C++
+//! logging pipeline. `init_logger()` installs it as the global logger.
+
+use std::os::raw::c_int;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::{to_c_string, LogLevel};
+
+/// `log::Log` implementation writing to the C++ ErrorHandler
+///
+/// Records below the ErrorHandler's log level are dropped before they are
+/// formatted. Messages are prefixed with the record's target, and `trace!`
+/// records are logged at `LogLevel::Debug`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoreBaseLogger;
+
+impl CoreBaseLogger {
+    /// Create a logger
+    pub fn new() -> Self {
+        CoreBaseLogger
+    }
+}
+
+impl Log for CoreBaseLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let threshold = unsafe { crate::cba_error_handler_get_log_level() };
+        c_int::from(LogLevel::from(metadata.level())) >= threshold
+    }
+    
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        
+        // Interior NULs can't cross into C++
+        let message = format!("{}: {}", record.target(), record.args()).replace('\0', "");
+        if let Ok(message) = to_c_string(&message) {
+            unsafe {
+                crate::cba_error_handler_log(LogLevel::from(record.level()).into(), message.as_ptr());
+            }
+        }
+    }
+    
+    fn flush(&self) {}
+}
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => LogLevel::Error,
+            Level::Warn => LogLevel::Warning,
+            Level::Info => LogLevel::Info,
+            Level::Debug | Level::Trace => LogLevel::Debug,
+        }
+    }
+}
+
+static LOGGER: CoreBaseLogger = CoreBaseLogger;
+
+/// Initialize the library and install `CoreBaseLogger` as the global
+/// `log` logger
+///
+/// Fails if the application already installed a logger, such as
+/// `env_logger`.
+pub fn init_logger() -> CoreBaseResult<()> {
+    crate::initialize()?;
+    log::set_logger(&LOGGER).map_err(|_| CoreBaseError::InitializationFailed(
+        "A global logger is already installed".to_string(),
+        None,
+    ))?;
+    // The ErrorHandler's level, which can change at any time, does the
+    // filtering
+    log::set_max_level(LevelFilter::Trace);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorHandler;
+    
+    #[test]
+    fn test_log_levels() {
+        assert_eq!(LogLevel::from(Level::Error), LogLevel::Error);
+        assert_eq!(LogLevel::from(Level::Warn), LogLevel::Warning);
+        assert_eq!(LogLevel::from(Level::Info), LogLevel::Info);
+        assert_eq!(LogLevel::from(Level::Debug), LogLevel::Debug);
+        assert_eq!(LogLevel::from(Level::Trace), LogLevel::Debug);
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_logger() {
+        let handler = ErrorHandler::new().unwrap();
+        let logger = CoreBaseLogger::new();
+        let debug = Metadata::builder().level(Level::Debug).target("app").build();
+        let before = handler.get_log_level().unwrap();
+        handler.set_log_level(LogLevel::Info).unwrap();
+        assert!(!logger.enabled(&debug));
+        assert!(logger.enabled(&Metadata::builder().level(Level::Error).build()));
+        handler.set_log_level(LogLevel::Debug).unwrap();
+        assert!(logger.enabled(&debug));
+        handler.set_log_level(before).unwrap();
+        
+        // Forwarding does not need the logger to be installed
+        logger.log(&Record::builder()
+            .level(Level::Info)
+            .args(format_args!("forwarded with a NUL \0 inside"))
+            .build());
+    }
+}
@@ -2,27 +2,103 @@
 //!
 //! This module provides system monitoring functionality that wraps the C++ SystemMonitor class.
 
-use std::os::raw::c_double;
-use std::time::{Duration, Instant};
-use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
-use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::{CbaString, LogLevel};
+use crate::codec::{Codec, JsonCodec};
+use crate::error::{CoreBaseError, CoreBaseResult, ErrorHandler};
+use crate::util::{random_delay, unique_path};
 
 /// System resource usage information
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SystemResources {
     pub cpu_usage_percent: f64,
+    /// Utilization of each logical core, in percent
+    #[serde(default)]
+    pub cpu_per_core: Vec<f64>,
+    /// 1, 5 and 15-minute load averages
+    #[serde(default)]
+    pub load_average: [f64; 3],
     pub available_memory_bytes: f64,
     pub total_memory_bytes: f64,
+    /// Free, cached, buffer and shared memory, if the platform reports them
+    #[serde(default)]
+    pub memory: Option<MemoryBreakdown>,
+    #[serde(default)]
+    pub swap_used_bytes: f64,
+    #[serde(default)]
+    pub swap_total_bytes: f64,
     pub available_disk_bytes: f64,
     pub total_disk_bytes: f64,
+    /// Throughput and IOPS of each block device since the previous sample
+    #[serde(default)]
+    pub disk_io: Vec<DiskIo>,
     pub network_usage_percent: f64,
+    /// Traffic of each network interface since the previous sample
+    #[serde(default)]
+    pub network_io: Vec<NetworkIo>,
     pub gpu_usage_percent: f64,
+    /// Device memory used over all GPUs
+    #[serde(default)]
+    pub gpu_memory_used_bytes: f64,
+    /// Device memory over all GPUs, 0 without GPU metrics
+    #[serde(default)]
+    pub gpu_memory_total_bytes: f64,
+    /// Temperatures and fan speeds, if thermal monitoring found sensors
+    #[serde(default)]
+    pub thermal: Option<ThermalInfo>,
+    /// Limits and usage of the enclosing cgroup, with container
+    /// monitoring enabled
+    #[serde(default)]
+    pub container: Option<ContainerUsage>,
+    /// Time since the host booted
+    #[serde(default)]
+    pub uptime_seconds: u64,
     pub timestamp: u64,
+    /// Values of custom metrics from `MetricSource`s, by name
+    #[serde(default)]
+    pub custom: BTreeMap<String, f64>,
 }
 
 impl SystemResources {
+    /// Get the index and usage of the busiest core
+    pub fn busiest_core(&self) -> Option<(usize, f64)> {
+        self.cpu_per_core
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+    
+    /// Get the usage of the busiest core, or 0 without per-core data
+    pub fn max_core_usage(&self) -> f64 {
+        self.busiest_core().map_or(0.0, |(_, usage)| usage)
+    }
+    
+    /// Get how far the busiest core is above the core average, in
+    /// percentage points
+    ///
+    /// A high imbalance with a moderate `cpu_usage_percent` points to a
+    /// saturated single thread.
+    pub fn core_imbalance(&self) -> f64 {
+        if self.cpu_per_core.is_empty() {
+            return 0.0;
+        }
+        let mean = self.cpu_per_core.iter().sum::<f64>() / self.cpu_per_core.len() as f64;
+        self.max_core_usage() - mean
+    }
+    
     /// Get memory usage percentage
     pub fn memory_usage_percent(&self) -> f64 {
         if self.total_memory_bytes > 0.0 {
@@ -41,6 +117,34 @@ impl SystemResources {
         }
     }
     
+    /// Get swap usage percentage
+    pub fn swap_usage_percent(&self) -> f64 {
+        if self.swap_total_bytes > 0.0 {
+            (self.swap_used_bytes / self.swap_total_bytes) * 100.0
+        } else {
+            0.0
+        }
+    }
+    
+    /// Get GPU memory usage percentage
+    pub fn gpu_memory_usage_percent(&self) -> f64 {
+        if self.gpu_memory_total_bytes > 0.0 {
+            (self.gpu_memory_used_bytes / self.gpu_memory_total_bytes) * 100.0
+        } else {
+            0.0
+        }
+    }
+    
+    /// Get the bytes received per second over all interfaces
+    pub fn network_received_bytes_per_sec(&self) -> f64 {
+        self.network_io.iter().map(|io| io.received_bytes_per_sec).sum()
+    }
+    
+    /// Get the bytes sent per second over all interfaces
+    pub fn network_sent_bytes_per_sec(&self) -> f64 {
+        self.network_io.iter().map(|io| io.sent_bytes_per_sec).sum()
+    }
+    
     /// Get used memory in bytes
     pub fn used_memory_bytes(&self) -> f64 {
         self.total_memory_bytes - self.available_memory_bytes
@@ -51,6 +155,39 @@ impl SystemResources {
         self.total_disk_bytes - self.available_disk_bytes
     }
     
+    /// Get how every metric changed since an earlier snapshot
+    ///
+    /// Covers the metrics of `MonitoringDataPoint::metrics()` present in
+    /// both snapshots, plus used memory, swap, disk and GPU memory bytes.
+    pub fn delta(&self, earlier: &SystemResources) -> ResourceDelta {
+        let before = MonitoringDataPoint::from(earlier);
+        let after = MonitoringDataPoint::from(self);
+        let elapsed = Duration::from_secs(self.timestamp.saturating_sub(earlier.timestamp));
+        
+        let mut metrics: BTreeMap<String, MetricChange> = before
+            .metrics()
+            .filter_map(|(name, before)| {
+                let after = after.metrics().find(|(other, _)| *other == name)?.1;
+                Some((name.to_string(), MetricChange::new(before, after, elapsed)))
+            })
+            .collect();
+        for (name, before, after) in [
+            ("memory_used_bytes", earlier.used_memory_bytes(), self.used_memory_bytes()),
+            ("swap_used_bytes", earlier.swap_used_bytes, self.swap_used_bytes),
+            ("disk_used_bytes", earlier.used_disk_bytes(), self.used_disk_bytes()),
+            ("gpu_memory_used_bytes", earlier.gpu_memory_used_bytes, self.gpu_memory_used_bytes),
+        ] {
+            metrics.insert(name.to_string(), MetricChange::new(before, after, elapsed));
+        }
+        
+        ResourceDelta {
+            start: earlier.timestamp,
+            end: self.timestamp,
+            elapsed,
+            metrics,
+        }
+    }
+    
     /// Convert bytes to human-readable format
     pub fn format_bytes(bytes: f64) -> String {
         const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB"];
@@ -90,29 +227,284 @@ impl Default for SystemResources {
     fn default() -> Self {
         SystemResources {
             cpu_usage_percent: 0.0,
+            cpu_per_core: Vec::new(),
+            load_average: [0.0; 3],
             available_memory_bytes: 0.0,
             total_memory_bytes: 0.0,
+            memory: None,
+            swap_used_bytes: 0.0,
+            swap_total_bytes: 0.0,
             available_disk_bytes: 0.0,
             total_disk_bytes: 0.0,
+            disk_io: Vec::new(),
             network_usage_percent: 0.0,
+            network_io: Vec::new(),
             gpu_usage_percent: 0.0,
+            gpu_memory_used_bytes: 0.0,
+            gpu_memory_total_bytes: 0.0,
+            thermal: None,
+            container: None,
+            uptime_seconds: 0,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
+            custom: BTreeMap::new(),
+        }
+    }
+}
+
+/// Breakdown of physical memory
+///
+/// `available_bytes` counts page cache and buffers the kernel can reclaim,
+/// so it is the figure to alert on; `free_bytes` is memory used for
+/// nothing at all and is normally low on a healthy host.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryBreakdown {
+    pub total_bytes: f64,
+    /// Memory not used for anything
+    pub free_bytes: f64,
+    /// Memory available to new allocations without swapping
+    pub available_bytes: f64,
+    /// Page cache
+    pub cached_bytes: f64,
+    /// Kernel block device buffers
+    pub buffers_bytes: f64,
+    /// Shared memory, such as tmpfs and System V segments
+    pub shared_bytes: f64,
+    /// Committed virtual memory, where the platform tracks it (Windows)
+    #[serde(default)]
+    pub commit_charge_bytes: Option<f64>,
+    /// Maximum commit charge before allocations fail
+    #[serde(default)]
+    pub commit_limit_bytes: Option<f64>,
+}
+
+impl MemoryBreakdown {
+    /// Get memory in use that cannot be reclaimed
+    pub fn used_bytes(&self) -> f64 {
+        self.total_bytes - self.available_bytes
+    }
+    
+    /// Get memory in use for caches the kernel can reclaim
+    pub fn reclaimable_bytes(&self) -> f64 {
+        (self.available_bytes - self.free_bytes).max(0.0)
+    }
+    
+    /// Get the commit charge as a percentage of the commit limit
+    pub fn commit_percent(&self) -> Option<f64> {
+        match (self.commit_charge_bytes, self.commit_limit_bytes) {
+            (Some(charge), Some(limit)) if limit > 0.0 => Some(charge / limit * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Resource usage of the current process
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessUsage {
+    /// CPU usage, in percent of one core
+    pub cpu_usage_percent: f64,
+    /// Resident set size
+    pub rss_bytes: u64,
+    pub virtual_memory_bytes: u64,
+    /// Open file descriptors, or handles on Windows
+    pub open_fds: u32,
+    pub thread_count: u32,
+}
+
+/// Temperature and fan sensor readings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermalInfo {
+    /// CPU package temperature in °C, if a sensor is available
+    pub cpu_temperature_celsius: Option<f64>,
+    /// GPU temperature in °C, if a sensor is available
+    pub gpu_temperature_celsius: Option<f64>,
+    /// Speed of each fan in RPM
+    pub fan_rpm: Vec<f64>,
+}
+
+/// Metrics of one GPU
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub index: u32,
+    pub name: String,
+    pub utilization_percent: f64,
+    pub memory_used_bytes: u64,
+    pub memory_total_bytes: u64,
+    /// Temperature in °C, if the device reports it
+    pub temperature_celsius: Option<f64>,
+    /// Power draw in watts, if the device reports it
+    pub power_watts: Option<f64>,
+}
+
+impl GpuInfo {
+    /// Get device memory usage percentage
+    pub fn memory_usage_percent(&self) -> f64 {
+        if self.memory_total_bytes > 0 {
+            (self.memory_used_bytes as f64 / self.memory_total_bytes as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// I/O rates of one block device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskIo {
+    /// Device name, such as "sda" or "nvme0n1"
+    pub device: String,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Completed reads per second
+    pub read_iops: f64,
+    /// Completed writes per second
+    pub write_iops: f64,
+}
+
+impl DiskIo {
+    /// Get the combined read and write throughput
+    pub fn total_bytes_per_sec(&self) -> f64 {
+        self.read_bytes_per_sec + self.write_bytes_per_sec
+    }
+    
+    /// Get the combined read and write operations per second
+    pub fn total_iops(&self) -> f64 {
+        self.read_iops + self.write_iops
+    }
+}
+
+/// Cumulative I/O counters of one block device
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct DiskCounters {
+    device: String,
+    read_bytes: u64,
+    write_bytes: u64,
+    reads: u64,
+    writes: u64,
+}
+
+impl DiskCounters {
+    /// Get the rates since an earlier reading of the same device
+    fn rates_since(&self, before: &DiskCounters, seconds: f64) -> DiskIo {
+        let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / seconds;
+        DiskIo {
+            device: self.device.clone(),
+            read_bytes_per_sec: rate(self.read_bytes, before.read_bytes),
+            write_bytes_per_sec: rate(self.write_bytes, before.write_bytes),
+            read_iops: rate(self.reads, before.reads),
+            write_iops: rate(self.writes, before.writes),
+        }
+    }
+}
+
+/// Traffic of one network interface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkIo {
+    /// Interface name, such as "eth0"
+    pub interface: String,
+    pub received_bytes_per_sec: f64,
+    pub sent_bytes_per_sec: f64,
+}
+
+/// Cumulative byte counters of one network interface
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct InterfaceCounters {
+    interface: String,
+    received_bytes: u64,
+    sent_bytes: u64,
+}
+
+impl InterfaceCounters {
+    /// Get the rates since an earlier reading of the same interface
+    fn rates_since(&self, before: &InterfaceCounters, seconds: f64) -> NetworkIo {
+        let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / seconds;
+        NetworkIo {
+            interface: self.interface.clone(),
+            received_bytes_per_sec: rate(self.received_bytes, before.received_bytes),
+            sent_bytes_per_sec: rate(self.sent_bytes, before.sent_bytes),
         }
     }
 }
 
+/// CPU and memory of the cgroup the process runs in
+///
+/// When the cgroup has a limit, `SystemResources` reports CPU and memory
+/// usage relative to it; the host-wide figures are kept here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerUsage {
+    /// cgroup version the values were read from, 1 or 2
+    pub cgroup_version: u8,
+    /// CPU quota in cores, if one is set
+    pub cpu_limit_cores: Option<f64>,
+    /// CPU time used since the previous sample, in percent of the quota
+    /// or, without one, of all host cores; `None` on the first sample
+    pub cpu_usage_percent: Option<f64>,
+    /// Memory limit, if one is set
+    pub memory_limit_bytes: Option<f64>,
+    /// Working set: memory charged to the cgroup minus inactive file cache
+    pub memory_used_bytes: f64,
+    pub host_cpu_usage_percent: f64,
+    pub host_available_memory_bytes: f64,
+    pub host_total_memory_bytes: f64,
+}
+
+impl ContainerUsage {
+    /// Get the working set in percent of the memory limit
+    pub fn memory_usage_percent(&self) -> Option<f64> {
+        self.memory_limit_bytes
+            .filter(|limit| *limit > 0.0)
+            .map(|limit| self.memory_used_bytes / limit * 100.0)
+    }
+}
+
+/// Values read from the cgroup filesystem
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CgroupSample {
+    version: u8,
+    cpu_limit_cores: Option<f64>,
+    /// Cumulative CPU time of the cgroup
+    cpu_usage_usec: Option<u64>,
+    memory_limit_bytes: Option<f64>,
+    memory_used_bytes: Option<f64>,
+}
+
+/// Resource usage of one process on the host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    /// CPU usage, in percent of one core
+    pub cpu_usage_percent: f64,
+    /// Resident set size
+    pub memory_bytes: u64,
+}
+
 /// Historical data point for monitoring trends
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringDataPoint {
     pub timestamp: u64,
     pub cpu_usage: f64,
+    /// Usage of the busiest core
+    #[serde(default)]
+    pub max_core_usage: f64,
+    /// 1-minute load average
+    #[serde(default)]
+    pub load_average: f64,
     pub memory_usage: f64,
+    #[serde(default)]
+    pub swap_usage: f64,
     pub disk_usage: f64,
     pub network_usage: f64,
+    /// Bytes received per second over all interfaces
+    #[serde(default)]
+    pub network_received_bytes_per_sec: f64,
+    /// Bytes sent per second over all interfaces
+    #[serde(default)]
+    pub network_sent_bytes_per_sec: f64,
     pub gpu_usage: f64,
+    #[serde(default)]
+    pub custom: BTreeMap<String, f64>,
 }
 
 impl From<&SystemResources> for MonitoringDataPoint {
@@ -120,19 +512,429 @@ impl From<&SystemResources> for MonitoringDataPoint {
         MonitoringDataPoint {
             timestamp: resources.timestamp,
             cpu_usage: resources.cpu_usage_percent,
+            max_core_usage: resources.max_core_usage(),
+            load_average: resources.load_average[0],
             memory_usage: resources.memory_usage_percent(),
+            swap_usage: resources.swap_usage_percent(),
             disk_usage: resources.disk_usage_percent(),
             network_usage: resources.network_usage_percent,
+            network_received_bytes_per_sec: resources.network_received_bytes_per_sec(),
+            network_sent_bytes_per_sec: resources.network_sent_bytes_per_sec(),
             gpu_usage: resources.gpu_usage_percent,
+            custom: resources.custom.clone(),
+        }
+    }
+}
+
+impl MonitoringDataPoint {
+    /// Iterate over every metric as (name, value), built-in metrics first
+    pub fn metrics(&self) -> impl Iterator<Item = (&str, f64)> {
+        POINT_METRICS
+            .into_iter()
+            .zip(self.builtin_values())
+            .chain(self.custom.iter().map(|(name, value)| (name.as_str(), *value)))
+    }
+    
+    /// Values of the built-in metrics in `POINT_METRICS` order
+    fn builtin_values(&self) -> [f64; 10] {
+        [
+            self.cpu_usage,
+            self.max_core_usage,
+            self.load_average,
+            self.memory_usage,
+            self.swap_usage,
+            self.disk_usage,
+            self.network_usage,
+            self.network_received_bytes_per_sec,
+            self.network_sent_bytes_per_sec,
+            self.gpu_usage,
+        ]
+    }
+    
+    /// Build a data point from per-metric values, as named in `metrics()`
+    ///
+    /// Missing built-in metrics are zero.
+    fn from_metrics(timestamp: u64, mut values: BTreeMap<String, f64>) -> Self {
+        let [
+            cpu_usage,
+            max_core_usage,
+            load_average,
+            memory_usage,
+            swap_usage,
+            disk_usage,
+            network_usage,
+            network_received_bytes_per_sec,
+            network_sent_bytes_per_sec,
+            gpu_usage,
+        ] = POINT_METRICS.map(|name| values.remove(name).unwrap_or_default());
+        MonitoringDataPoint {
+            timestamp,
+            cpu_usage,
+            max_core_usage,
+            load_average,
+            memory_usage,
+            swap_usage,
+            disk_usage,
+            network_usage,
+            network_received_bytes_per_sec,
+            network_sent_bytes_per_sec,
+            gpu_usage,
+            custom: values,
+        }
+    }
+}
+
+/// Summary statistics of one metric
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricStatistics {
+    /// Number of samples
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Population standard deviation
+    pub stddev: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+impl MetricStatistics {
+    /// Summarize a set of samples, or `None` if there are none
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let variance = sorted.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / count as f64;
+        
+        Some(MetricStatistics {
+            count,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean,
+            stddev: variance.sqrt(),
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+        })
+    }
+}
+
+/// Statistics of every metric over a span of history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryStatistics {
+    /// Timestamp of the first data point included
+    pub start: u64,
+    /// Timestamp of the last data point included
+    pub end: u64,
+    /// Number of data points included
+    pub samples: usize,
+    /// Statistics by metric name, as named in `MonitoringDataPoint::metrics()`
+    pub metrics: BTreeMap<String, MetricStatistics>,
+}
+
+impl HistoryStatistics {
+    /// Summarize a sequence of data points, or `None` if there are none
+    pub fn from_points<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a MonitoringDataPoint>,
+    {
+        let mut samples: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+        let mut span: Option<(u64, u64)> = None;
+        let mut count = 0;
+        for point in points {
+            for (name, value) in point.metrics() {
+                samples.entry(name).or_default().push(value);
+            }
+            span = Some(match span {
+                Some((start, end)) => (start.min(point.timestamp), end.max(point.timestamp)),
+                None => (point.timestamp, point.timestamp),
+            });
+            count += 1;
+        }
+        
+        let (start, end) = span?;
+        Some(HistoryStatistics {
+            start,
+            end,
+            samples: count,
+            metrics: samples
+                .into_iter()
+                .filter_map(|(name, values)| {
+                    MetricStatistics::from_samples(&values).map(|stats| (name.to_string(), stats))
+                })
+                .collect(),
+        })
+    }
+    
+    /// Get the statistics of one metric
+    pub fn get(&self, metric: &str) -> Option<&MetricStatistics> {
+        self.metrics.get(metric)
+    }
+    
+    /// Build a data point holding one statistic of every metric
+    fn to_point(&self, timestamp: u64, statistic: impl Fn(&MetricStatistics) -> f64) -> MonitoringDataPoint {
+        MonitoringDataPoint::from_metrics(
+            timestamp,
+            self.metrics.iter().map(|(name, stats)| (name.clone(), statistic(stats))).collect(),
+        )
+    }
+}
+
+/// Linearly interpolated percentile of sorted samples
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    let rank = percent / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Change of one metric between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricChange {
+    pub before: f64,
+    pub after: f64,
+    /// `after - before`
+    pub change: f64,
+    /// Change per second, or `None` if both snapshots share a timestamp
+    pub rate_per_sec: Option<f64>,
+}
+
+impl MetricChange {
+    fn new(before: f64, after: f64, elapsed: Duration) -> Self {
+        let change = after - before;
+        let seconds = elapsed.as_secs_f64();
+        MetricChange {
+            before,
+            after,
+            change,
+            rate_per_sec: (seconds > 0.0).then(|| change / seconds),
+        }
+    }
+}
+
+/// Changes between two `SystemResources` snapshots, from
+/// `SystemResources::delta`
+///
+/// Displays as one line per metric that changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDelta {
+    /// Timestamp of the earlier snapshot
+    pub start: u64,
+    /// Timestamp of the later snapshot
+    pub end: u64,
+    pub elapsed: Duration,
+    /// Changes by metric name
+    pub metrics: BTreeMap<String, MetricChange>,
+}
+
+impl ResourceDelta {
+    /// Get the change of one metric
+    pub fn get(&self, metric: &str) -> Option<&MetricChange> {
+        self.metrics.get(metric)
+    }
+    
+    /// Iterate over the metrics whose value changed
+    pub fn changed(&self) -> impl Iterator<Item = (&str, &MetricChange)> {
+        self.metrics
+            .iter()
+            .filter(|(_, change)| change.change != 0.0)
+            .map(|(name, change)| (name.as_str(), change))
+    }
+}
+
+impl fmt::Display for ResourceDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "changes over {}s", self.elapsed.as_secs())?;
+        if self.changed().next().is_none() {
+            return write!(f, ": none");
+        }
+        for (name, change) in self.changed() {
+            write!(f, "\n  {}: {:.2} -> {:.2} ({:+.2}", name, change.before, change.after, change.change)?;
+            if let Some(rate) = change.rate_per_sec {
+                write!(f, ", {:+.2}/s", rate)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Downsampled level of the history
+///
+/// Points evicted from the history are folded into the first tier, one
+/// aggregate per `resolution` interval; aggregates evicted from a tier are
+/// folded into the next one. Memory use is bounded by the capacities.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryTier {
+    /// Interval covered by each aggregate, in whole seconds
+    pub resolution: Duration,
+    /// Number of aggregates kept
+    pub capacity: usize,
+}
+
+impl HistoryTier {
+    /// Create a tier of `capacity` aggregates covering `resolution` each
+    pub fn new(resolution: Duration, capacity: usize) -> Self {
+        HistoryTier { resolution, capacity }
+    }
+    
+    /// One-minute aggregates for `minutes` minutes
+    pub fn minutes(minutes: usize) -> Self {
+        Self::new(Duration::from_secs(60), minutes)
+    }
+    
+    /// One-hour aggregates for `hours` hours
+    pub fn hours(hours: usize) -> Self {
+        Self::new(Duration::from_secs(3600), hours)
+    }
+    
+    fn step(&self) -> u64 {
+        self.resolution.as_secs().max(1)
+    }
+}
+
+/// Minimum, maximum and sum of one metric within an aggregate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricAggregate {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+}
+
+impl MetricAggregate {
+    fn new(value: f64) -> Self {
+        MetricAggregate {
+            count: 1,
+            min: value,
+            max: value,
+            sum: value,
+        }
+    }
+    
+    /// Average of the aggregated samples
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+    
+    fn merge(&mut self, other: &MetricAggregate) {
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+    }
+}
+
+/// Data points of one interval of a `HistoryTier`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryAggregate {
+    /// Start of the interval, in seconds since the Unix epoch
+    pub start: u64,
+    /// End of the interval, exclusive
+    pub end: u64,
+    /// Number of data points aggregated
+    pub samples: usize,
+    /// Aggregates by metric name, as named in `MonitoringDataPoint::metrics()`
+    pub metrics: BTreeMap<String, MetricAggregate>,
+}
+
+impl HistoryAggregate {
+    fn from_point(point: &MonitoringDataPoint) -> Self {
+        HistoryAggregate {
+            start: point.timestamp,
+            end: point.timestamp + 1,
+            samples: 1,
+            metrics: point.metrics()
+                .map(|(name, value)| (name.to_string(), MetricAggregate::new(value)))
+                .collect(),
+        }
+    }
+    
+    /// Get the aggregate of one metric
+    pub fn get(&self, metric: &str) -> Option<&MetricAggregate> {
+        self.metrics.get(metric)
+    }
+    
+    fn merge(&mut self, other: HistoryAggregate) {
+        self.samples += other.samples;
+        for (name, metric) in other.metrics {
+            self.metrics
+                .entry(name)
+                .and_modify(|existing| existing.merge(&metric))
+                .or_insert(metric);
         }
     }
 }
 
+/// Data point with the labels and metric tags of the monitor, as written
+/// by `ExportFormat::JsonLines`
+#[derive(Serialize)]
+struct LabeledPoint<'a> {
+    #[serde(flatten)]
+    point: &'a MonitoringDataPoint,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    labels: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    tags: &'a BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Format of an exported history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+/// Names of the built-in metrics of a `MonitoringDataPoint`
+pub const POINT_METRICS: [&str; 10] = [
+    "cpu_usage",
+    "max_core_usage",
+    "load_average",
+    "memory_usage",
+    "swap_usage",
+    "disk_usage",
+    "network_usage",
+    "network_received_bytes_per_sec",
+    "network_sent_bytes_per_sec",
+    "gpu_usage",
+];
+
+/// Source of custom metrics recorded along with the system resources
+///
+/// Sources are added with `SystemMonitor::add_metric_source` and sampled on
+/// every `get_system_resources()` call. Names should be prefixed with the
+/// subsystem, such as "network.error_rate".
+pub trait MetricSource: fmt::Debug + Send {
+    /// Sample the current value of each metric
+    fn sample(&mut self) -> Vec<(String, f64)>;
+}
+
 /// System monitoring configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringConfig {
     pub update_interval: Duration,
+    /// Schedule background samples on multiples of `update_interval` since
+    /// the Unix epoch, such as every full minute for a 60s interval
+    #[serde(default)]
+    pub align_samples: bool,
+    /// Upper bound of a random delay added to each scheduled background
+    /// sample, so a fleet of monitors doesn't sample at the same instant
+    #[serde(default)]
+    pub sample_jitter: Duration,
     pub history_size: usize,
+    /// Maximum age of history points, relative to the newest point
+    ///
+    /// Applies in addition to `history_size`.
+    #[serde(default)]
+    pub history_retention: Option<Duration>,
     pub enable_cpu_monitoring: bool,
     pub enable_memory_monitoring: bool,
     pub enable_disk_monitoring: bool,
@@ -143,13 +945,77 @@ pub struct MonitoringConfig {
     pub disk_threshold: f64,
     pub network_threshold: f64,
     pub gpu_threshold: f64,
+    /// GPU memory usage alert threshold, in percent of all GPU memory
+    #[serde(default = "default_gpu_memory_threshold")]
+    pub gpu_memory_threshold: f64,
+    /// 1-minute load average alert threshold, per logical core
+    #[serde(default = "default_load_threshold")]
+    pub load_threshold: f64,
+    /// Swap usage alert threshold, in percent
+    #[serde(default = "default_swap_threshold")]
+    pub swap_threshold: f64,
+    #[serde(default = "default_thermal_monitoring")]
+    pub enable_thermal_monitoring: bool,
+    /// Report CPU and memory usage relative to the limits of the
+    /// enclosing cgroup, for processes running in containers
+    #[serde(default)]
+    pub enable_container_monitoring: bool,
+    /// CPU temperature alert threshold, in °C
+    #[serde(default = "default_temperature_threshold")]
+    pub cpu_temperature_threshold: f64,
+    /// GPU temperature alert threshold, in °C
+    #[serde(default = "default_temperature_threshold")]
+    pub gpu_temperature_threshold: f64,
+    /// Per-device disk throughput alert threshold, reads and writes
+    /// combined, in bytes per second
+    #[serde(default)]
+    pub disk_throughput_threshold: Option<f64>,
+    /// Per-device disk IOPS alert threshold, reads and writes combined
+    #[serde(default)]
+    pub disk_iops_threshold: Option<f64>,
+    /// Alert thresholds of custom metrics, by name
+    #[serde(default)]
+    pub metric_thresholds: BTreeMap<String, f64>,
+    /// Critical alert thresholds, by metric name as in `Alert::metric`;
+    /// alerts of other metrics are warnings
+    #[serde(default)]
+    pub critical_thresholds: BTreeMap<String, f64>,
+    /// Hysteresis of `on_alert` notifications
+    #[serde(default)]
+    pub alert_policy: AlertPolicy,
+    /// Hysteresis overriding `alert_policy` for some metrics, by name
+    #[serde(default)]
+    pub metric_alert_policies: BTreeMap<String, AlertPolicy>,
+    /// Log alerts through `ErrorHandler`, in addition to `on_alert`
+    #[serde(default)]
+    pub alert_logging: Option<AlertLogging>,
+    /// Report samples far outside a metric's recent baseline to `on_alert`
+    #[serde(default)]
+    pub anomaly_detection: Option<AnomalyDetection>,
+    /// Age after which the latest sample makes `health()` critical,
+    /// three `update_interval`s if unset
+    #[serde(default)]
+    pub health_stale_after: Option<Duration>,
+    /// Downsampled tiers receiving points evicted from the history, finest
+    /// first, such as `[HistoryTier::minutes(1440), HistoryTier::hours(720)]`
+    #[serde(default)]
+    pub history_tiers: Vec<HistoryTier>,
+    /// Labels of every exported data point, such as host, region or service
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Tags of single metrics, by name as in `MonitoringDataPoint::metrics()`
+    #[serde(default)]
+    pub metric_tags: BTreeMap<String, BTreeMap<String, String>>,
 }
 
 impl Default for MonitoringConfig {
     fn default() -> Self {
         MonitoringConfig {
             update_interval: Duration::from_secs(1),
+            align_samples: false,
+            sample_jitter: Duration::ZERO,
             history_size: 100,
+            history_retention: None,
             enable_cpu_monitoring: true,
             enable_memory_monitoring: true,
             enable_disk_monitoring: true,
@@ -160,81 +1026,644 @@ impl Default for MonitoringConfig {
             disk_threshold: 90.0,
             network_threshold: 80.0,
             gpu_threshold: 80.0,
+            gpu_memory_threshold: default_gpu_memory_threshold(),
+            load_threshold: default_load_threshold(),
+            swap_threshold: default_swap_threshold(),
+            enable_thermal_monitoring: default_thermal_monitoring(),
+            enable_container_monitoring: false,
+            cpu_temperature_threshold: default_temperature_threshold(),
+            gpu_temperature_threshold: default_temperature_threshold(),
+            disk_throughput_threshold: None,
+            disk_iops_threshold: None,
+            metric_thresholds: BTreeMap::new(),
+            alert_policy: AlertPolicy::default(),
+            critical_thresholds: BTreeMap::new(),
+            metric_alert_policies: BTreeMap::new(),
+            alert_logging: None,
+            anomaly_detection: None,
+            health_stale_after: None,
+            history_tiers: Vec::new(),
+            labels: BTreeMap::new(),
+            metric_tags: BTreeMap::new(),
         }
     }
 }
 
-/// System monitor wrapper for the C++ SystemMonitor class
-#[derive(Debug)]
-pub struct SystemMonitor {
-    initialized: bool,
-    config: MonitoringConfig,
-    history: VecDeque<MonitoringDataPoint>,
-    last_update: Option<Instant>,
-}
-
-impl SystemMonitor {
-    /// Create a new SystemMonitor instance
-    pub fn new() -> CoreBaseResult<Self> {
-        Ok(SystemMonitor {
-            initialized: true,
-            config: MonitoringConfig::default(),
-            history: VecDeque::new(),
-            last_update: None,
-        })
+impl MonitoringConfig {
+    /// Add a label to every exported data point
+    pub fn with_label(mut self, key: &str, value: &str) -> Self {
+        self.labels.insert(key.to_string(), value.to_string());
+        self
     }
     
-    /// Create a new SystemMonitor with custom configuration
-    pub fn with_config(config: MonitoringConfig) -> CoreBaseResult<Self> {
-        Ok(SystemMonitor {
-            initialized: true,
-            config,
-            history: VecDeque::new(),
-            last_update: None,
-        })
+    /// Make alerts of `metric` critical above `threshold`
+    pub fn with_critical_threshold(mut self, metric: &str, threshold: f64) -> Self {
+        self.critical_thresholds.insert(metric.to_string(), threshold);
+        self
     }
     
-    /// Get current system resource usage
-    pub fn get_system_resources(&mut self) -> CoreBaseResult<SystemResources> {
-        if !self.initialized {
-            return Err(CoreBaseError::OperationFailed(
-                "SystemMonitor not initialized".to_string()
-            ));
+    /// Add a tag to one metric
+    pub fn with_metric_tag(mut self, metric: &str, key: &str, value: &str) -> Self {
+        self.metric_tags
+            .entry(metric.to_string())
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+    
+    /// Get the time from `now` until the next scheduled sample
+    ///
+    /// That is `update_interval`, or the time to the next multiple of it
+    /// with `align_samples`, plus a random part of `sample_jitter`.
+    pub fn next_sample_delay(&self, now: SystemTime) -> Duration {
+        let interval = self.update_interval.as_nanos();
+        let delay = if self.align_samples && interval > 0 {
+            let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+            Duration::from_nanos((interval - since_epoch % interval) as u64)
+        } else {
+            self.update_interval
+        };
+        delay + random_delay(self.sample_jitter)
+    }
+    
+    /// Get the time from `now` until the first scheduled sample, which is
+    /// immediate unless samples are aligned or jittered
+    fn first_sample_delay(&self, now: SystemTime) -> Duration {
+        if self.align_samples || !self.sample_jitter.is_zero() {
+            self.next_sample_delay(now)
+        } else {
+            Duration::ZERO
         }
-        
-        let mut resources = SystemResources::default();
-        
-        // Get CPU usage
-        if self.config.enable_cpu_monitoring {
-            unsafe {
-                resources.cpu_usage_percent = crate::cba_monitor_get_cpu_usage();
-            }
+    }
+    
+    /// Get the labels of a metric: the data point labels and the metric's
+    /// tags, with tags taking precedence
+    pub fn metric_labels(&self, metric: &str) -> BTreeMap<String, String> {
+        let mut labels = self.labels.clone();
+        if let Some(tags) = self.metric_tags.get(metric) {
+            labels.extend(tags.iter().map(|(key, value)| (key.clone(), value.clone())));
         }
-        
-        // Get memory usage
-        if self.config.enable_memory_monitoring {
-            let mut available = 0.0;
-            let mut total = 0.0;
-            unsafe {
-                let result = crate::cba_monitor_get_memory_usage(&mut available, &mut total);
-                if result == 0 {
-                    resources.available_memory_bytes = available;
-                    resources.total_memory_bytes = total;
-                }
-            }
+        labels
+    }
+}
+
+/// Levels at which alerts are logged through `ErrorHandler`
+///
+/// Triggered alerts are logged at the metric's level; resolved alerts at
+/// `LogLevel::Info`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertLogging {
+    /// Level of metrics without an entry in `metric_levels`
+    pub level: LogLevel,
+    /// Level by metric name, as in `Alert::metric`
+    #[serde(default)]
+    pub metric_levels: BTreeMap<String, LogLevel>,
+}
+
+impl AlertLogging {
+    /// Get the level a triggered alert of `metric` is logged at
+    pub fn level_for(&self, metric: &str) -> LogLevel {
+        self.metric_levels.get(metric).copied().unwrap_or(self.level)
+    }
+    
+    /// Log at `level` unless overridden per metric
+    pub fn with_metric_level(mut self, metric: &str, level: LogLevel) -> Self {
+        self.metric_levels.insert(metric.to_string(), level);
+        self
+    }
+}
+
+impl Default for AlertLogging {
+    fn default() -> Self {
+        AlertLogging {
+            level: LogLevel::Warning,
+            metric_levels: BTreeMap::new(),
         }
-        
-        // Get disk usage
-        if self.config.enable_disk_monitoring {
-            let mut available = 0.0;
-            let mut total = 0.0;
-            unsafe {
-                let result = crate::cba_monitor_get_disk_usage(&mut available, &mut total);
-                if result == 0 {
-                    resources.available_disk_bytes = available;
-                    resources.total_disk_bytes = total;
-                }
+    }
+}
+
+/// When threshold crossings become `on_alert` notifications
+///
+/// Margins are in the unit of the metric, such as percentage points.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertPolicy {
+    /// How far above its threshold a metric must rise to trigger
+    pub rising_margin: f64,
+    /// How far below its threshold a metric must fall to resolve
+    pub falling_margin: f64,
+    /// How long a metric must stay above threshold and margin to trigger
+    pub sustain: Duration,
+}
+
+/// Online detection of unusual samples
+///
+/// Each metric keeps an exponentially weighted moving average and variance
+/// of its samples. A sample outside the mean by more than `deviations`
+/// standard deviations is reported once as `AlertState::Anomaly`, and again
+/// only after the metric has returned to its band. Outliers still update
+/// the baseline, so a lasting shift becomes the new normal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyDetection {
+    /// Weight of the newest sample in the baseline, between 0 and 1
+    pub smoothing: f64,
+    /// Half-width of the normal band, in standard deviations
+    pub deviations: f64,
+    /// Samples a metric needs before it can be reported
+    pub warmup: usize,
+}
+
+impl Default for AnomalyDetection {
+    fn default() -> Self {
+        AnomalyDetection {
+            smoothing: 0.1,
+            deviations: 3.0,
+            warmup: 10,
+        }
+    }
+}
+
+/// Whether an alert started or ended, or a sample was unusual
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertState {
+    Triggered,
+    Resolved,
+    /// A sample deviated from the metric's baseline (see `AnomalyDetection`)
+    Anomaly,
+}
+
+/// Tier of an alert, from `MonitoringConfig::critical_thresholds`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AlertSeverity {
+    /// Above the metric's threshold
+    #[default]
+    Warning,
+    /// Above the metric's critical threshold
+    Critical,
+}
+
+/// Threshold crossing or anomaly, as returned by
+/// `SystemMonitor::check_thresholds` and reported to `on_alert` callbacks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// "cpu", "memory", "swap", "disk", "network", "gpu", "load",
+    /// "cpu_temperature", "gpu_temperature", "disk_throughput.<device>",
+    /// "disk_iops.<device>" or a custom metric name
+    pub metric: String,
+    pub severity: AlertSeverity,
+    pub value: f64,
+    /// Threshold, or for anomalies the edge of the band that was left
+    pub threshold: f64,
+    /// Unix timestamp of the first sample above the threshold in this
+    /// episode
+    pub first_seen: u64,
+    /// Samples above the threshold since `first_seen`
+    pub count: usize,
+    pub state: AlertState,
+    pub message: String,
+}
+
+/// Alert state transition sent to `SystemMonitor::alert_events` receivers
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEvent {
+    /// A metric triggered, or its active alert changed severity
+    Raised(Alert),
+    /// A metric resolved
+    Cleared(Alert),
+}
+
+impl AlertEvent {
+    /// Get the event of a threshold alert; anomalies are not transitions
+    fn from_alert(alert: &Alert) -> Option<Self> {
+        match alert.state {
+            AlertState::Triggered => Some(AlertEvent::Raised(alert.clone())),
+            AlertState::Resolved => Some(AlertEvent::Cleared(alert.clone())),
+            AlertState::Anomaly => None,
+        }
+    }
+    
+    /// Get the alert that caused the transition
+    pub fn alert(&self) -> &Alert {
+        match self {
+            AlertEvent::Raised(alert) | AlertEvent::Cleared(alert) => alert,
+        }
+    }
+}
+
+/// Overall state reported by `SystemMonitor::health`, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum HealthStatus {
+    Healthy,
+    /// Some metric is above its threshold
+    Degraded,
+    /// An alert is active, or the samples are missing or stale
+    Critical,
+}
+
+impl HealthStatus {
+    /// Get the HTTP status code for a health check endpoint
+    ///
+    /// Degraded hosts still answer 200 so load balancers keep routing to
+    /// them; only critical ones answer 503.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            HealthStatus::Healthy | HealthStatus::Degraded => 200,
+            HealthStatus::Critical => 503,
+        }
+    }
+}
+
+/// Health summary of a monitored host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Worst status of any reason, or healthy without reasons
+    pub status: HealthStatus,
+    /// Why the host is not healthy
+    pub reasons: Vec<String>,
+}
+
+impl HealthReport {
+    fn healthy() -> Self {
+        HealthReport {
+            status: HealthStatus::Healthy,
+            reasons: Vec::new(),
+        }
+    }
+    
+    fn add(&mut self, status: HealthStatus, reason: String) {
+        self.status = self.status.max(status);
+        self.reasons.push(reason);
+    }
+    
+    /// Check whether the status is healthy
+    pub fn is_healthy(&self) -> bool {
+        self.status == HealthStatus::Healthy
+    }
+}
+
+type AlertFn = dyn Fn(&Alert) + Send + Sync;
+
+#[derive(Default)]
+struct AlertCallbacks(Vec<Arc<AlertFn>>);
+
+impl fmt::Debug for AlertCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} callbacks", self.0.len())
+    }
+}
+
+/// Metric watched by `SystemMonitor::watchdog`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// CPU usage, in percent
+    Cpu,
+    /// Memory usage, in percent
+    Memory,
+    /// Swap usage, in percent
+    Swap,
+    /// Disk usage, in percent
+    Disk,
+    /// Network usage, in percent
+    Network,
+    /// GPU usage, in percent
+    Gpu,
+    /// 1-minute load average, not scaled by the core count
+    Load,
+    /// CPU temperature, in °C
+    CpuTemperature,
+    /// GPU temperature, in °C
+    GpuTemperature,
+    /// Custom metric from a `MetricSource`
+    Custom(String),
+}
+
+impl Metric {
+    /// Get the metric name, as in `Alert::metric`
+    pub fn name(&self) -> &str {
+        match self {
+            Metric::Cpu => "cpu",
+            Metric::Memory => "memory",
+            Metric::Swap => "swap",
+            Metric::Disk => "disk",
+            Metric::Network => "network",
+            Metric::Gpu => "gpu",
+            Metric::Load => "load",
+            Metric::CpuTemperature => "cpu_temperature",
+            Metric::GpuTemperature => "gpu_temperature",
+            Metric::Custom(name) => name,
+        }
+    }
+    
+    /// Read the metric from a sample, if it was measured
+    pub fn value(&self, resources: &SystemResources) -> Option<f64> {
+        let thermal = resources.thermal.as_ref();
+        match self {
+            Metric::Cpu => Some(resources.cpu_usage_percent),
+            Metric::Memory => Some(resources.memory_usage_percent()),
+            Metric::Swap => Some(resources.swap_usage_percent()),
+            Metric::Disk => Some(resources.disk_usage_percent()),
+            Metric::Network => Some(resources.network_usage_percent),
+            Metric::Gpu => Some(resources.gpu_usage_percent),
+            Metric::Load => Some(resources.load_average[0]),
+            Metric::CpuTemperature => thermal.and_then(|thermal| thermal.cpu_temperature_celsius),
+            Metric::GpuTemperature => thermal.and_then(|thermal| thermal.gpu_temperature_celsius),
+            Metric::Custom(name) => resources.custom.get(name).copied(),
+        }
+    }
+    
+    /// Read the metric from a history point, if the point records it
+    ///
+    /// Temperatures are not kept in the history.
+    pub fn point_value(&self, point: &MonitoringDataPoint) -> Option<f64> {
+        match self {
+            Metric::Cpu => Some(point.cpu_usage),
+            Metric::Memory => Some(point.memory_usage),
+            Metric::Swap => Some(point.swap_usage),
+            Metric::Disk => Some(point.disk_usage),
+            Metric::Network => Some(point.network_usage),
+            Metric::Gpu => Some(point.gpu_usage),
+            Metric::Load => Some(point.load_average),
+            Metric::CpuTemperature | Metric::GpuTemperature => None,
+            Metric::Custom(name) => point.custom.get(name).copied(),
+        }
+    }
+}
+
+/// Characters of a sparkline, from lowest to highest
+const SPARKLINE_LEVELS: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+
+/// Width of the sparklines of `SystemMonitor::render_dashboard`
+const DASHBOARD_WIDTH: usize = 40;
+
+/// Render values as a line of at most `width` ASCII characters
+///
+/// Longer series are averaged into `width` buckets. Heights are scaled
+/// between the lowest and highest value shown.
+fn sparkline(values: &[f64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+    
+    let columns: Vec<f64> = if values.len() <= width {
+        values.to_vec()
+    } else {
+        (0..width)
+            .map(|column| {
+                let bucket = &values[column * values.len() / width..(column + 1) * values.len() / width];
+                bucket.iter().sum::<f64>() / bucket.len() as f64
+            })
+            .collect()
+    };
+    
+    let min = columns.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = columns.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARKLINE_LEVELS.len() - 1) as f64;
+    columns
+        .iter()
+        .map(|value| {
+            let level = if max > min { ((value - min) / (max - min) * top).round() as usize } else { 0 };
+            SPARKLINE_LEVELS[level]
+        })
+        .collect()
+}
+
+type WatchdogFn = dyn Fn(&SystemResources) + Send + Sync;
+
+/// Action run once a metric has stayed above a limit for long enough
+struct Watchdog {
+    metric: Metric,
+    above: f64,
+    for_at_least: Duration,
+    action: Arc<WatchdogFn>,
+    /// When the metric last rose above the limit
+    above_since: Option<Instant>,
+    fired: bool,
+}
+
+impl fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("metric", &self.metric)
+            .field("above", &self.above)
+            .field("for_at_least", &self.for_at_least)
+            .field("fired", &self.fired)
+            .finish()
+    }
+}
+
+/// Alert state of one metric
+#[derive(Debug, Default)]
+struct AlertTracker {
+    /// When the metric last rose above threshold and margin
+    above_since: Option<Instant>,
+    /// Timestamp of the first sample above threshold, and the samples
+    /// above it since
+    first_seen: Option<u64>,
+    count: usize,
+    active: bool,
+    /// Severity last reported while active
+    severity: AlertSeverity,
+}
+
+impl AlertTracker {
+    /// Count a sample against its threshold
+    fn observe(&mut self, value: f64, threshold: f64, timestamp: u64) {
+        if value > threshold {
+            self.first_seen.get_or_insert(timestamp);
+            self.count += 1;
+        } else if !self.active {
+            self.first_seen = None;
+            self.count = 0;
+        }
+    }
+}
+
+/// Moving baseline of one metric for anomaly detection
+#[derive(Debug, Default)]
+struct AnomalyBaseline {
+    mean: f64,
+    variance: f64,
+    samples: usize,
+    /// Whether the last sample was outside the band
+    anomalous: bool,
+}
+
+impl AnomalyBaseline {
+    /// Add a sample and return the band it left, if newly anomalous
+    fn observe(&mut self, value: f64, detection: &AnomalyDetection) -> Option<(f64, f64)> {
+        if self.samples == 0 {
+            self.mean = value;
+            self.samples = 1;
+            return None;
+        }
+        
+        let width = detection.deviations * self.variance.sqrt();
+        let band = (self.mean - width, self.mean + width);
+        let outside = self.samples >= detection.warmup && (value < band.0 || value > band.1);
+        
+        let deviation = value - self.mean;
+        let increment = detection.smoothing * deviation;
+        self.mean += increment;
+        self.variance = (1.0 - detection.smoothing) * (self.variance + deviation * increment);
+        self.samples += 1;
+        
+        let reported = outside && !self.anomalous;
+        self.anomalous = outside;
+        reported.then_some(band)
+    }
+}
+
+/// Value of a metric compared against its threshold
+struct ThresholdReading {
+    metric: String,
+    label: String,
+    value: f64,
+    threshold: f64,
+    /// Message reporting the threshold as exceeded
+    exceeded: String,
+}
+
+fn default_load_threshold() -> f64 {
+    2.0
+}
+
+fn default_swap_threshold() -> f64 {
+    50.0
+}
+
+fn default_gpu_memory_threshold() -> f64 {
+    90.0
+}
+
+fn default_thermal_monitoring() -> bool {
+    true
+}
+
+fn default_temperature_threshold() -> f64 {
+    85.0
+}
+
+/// System monitor wrapper for the C++ SystemMonitor class
+///
+/// With the "fallback-sysinfo" feature, queries that fail on the C++
+/// side are answered from the `sysinfo` crate instead, after any Linux `/proc`
+/// and sysfs fallbacks. GPU and network usage have no such fallback. The
+/// native library is still linked, so it must be present at build time.
+#[derive(Debug)]
+pub struct SystemMonitor {
+    initialized: bool,
+    config: MonitoringConfig,
+    history: VecDeque<MonitoringDataPoint>,
+    /// Aggregates of each of `config.history_tiers`
+    tiers: Vec<VecDeque<HistoryAggregate>>,
+    last_update: Option<Instant>,
+    /// Sample of the last `get_system_resources()` call
+    latest: Option<SystemResources>,
+    sources: Vec<Box<dyn MetricSource>>,
+    alert_callbacks: AlertCallbacks,
+    alert_subscribers: Vec<Sender<AlertEvent>>,
+    alert_states: HashMap<String, AlertTracker>,
+    anomaly_baselines: HashMap<String, AnomalyBaseline>,
+    watchdogs: Vec<Watchdog>,
+    /// Disk counters of the previous `get_disk_io()` call
+    disk_counters: Option<(Instant, Vec<DiskCounters>)>,
+    /// Interface counters of the previous `get_network_io()` call
+    network_counters: Option<(Instant, Vec<InterfaceCounters>)>,
+    /// Mount point of the cgroup filesystem
+    cgroup_root: PathBuf,
+    /// CPU time of the cgroup at the previous sample
+    cgroup_cpu: Option<(Instant, u64)>,
+}
+
+impl SystemMonitor {
+    /// Create a new SystemMonitor instance
+    pub fn new() -> CoreBaseResult<Self> {
+        Ok(SystemMonitor {
+            initialized: true,
+            config: MonitoringConfig::default(),
+            history: VecDeque::new(),
+            tiers: Vec::new(),
+            last_update: None,
+            latest: None,
+            sources: Vec::new(),
+            alert_callbacks: AlertCallbacks::default(),
+            alert_subscribers: Vec::new(),
+            alert_states: HashMap::new(),
+            anomaly_baselines: HashMap::new(),
+            watchdogs: Vec::new(),
+            disk_counters: None,
+            network_counters: None,
+            cgroup_root: PathBuf::from("/sys/fs/cgroup"),
+            cgroup_cpu: None,
+        })
+    }
+    
+    /// Create a new SystemMonitor with custom configuration
+    pub fn with_config(config: MonitoringConfig) -> CoreBaseResult<Self> {
+        Ok(SystemMonitor {
+            initialized: true,
+            config,
+            history: VecDeque::new(),
+            tiers: Vec::new(),
+            last_update: None,
+            latest: None,
+            sources: Vec::new(),
+            alert_callbacks: AlertCallbacks::default(),
+            alert_subscribers: Vec::new(),
+            alert_states: HashMap::new(),
+            anomaly_baselines: HashMap::new(),
+            watchdogs: Vec::new(),
+            disk_counters: None,
+            network_counters: None,
+            cgroup_root: PathBuf::from("/sys/fs/cgroup"),
+            cgroup_cpu: None,
+        })
+    }
+    
+    /// Get current system resource usage
+    pub fn get_system_resources(&mut self) -> CoreBaseResult<SystemResources> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let mut resources = SystemResources::default();
+        
+        // Get CPU usage
+        if self.config.enable_cpu_monitoring {
+            resources.cpu_usage_percent = self.get_cpu_usage().unwrap_or_default();
+            resources.cpu_per_core = self.get_cpu_per_core().unwrap_or_default();
+            if let Ok((one, five, fifteen)) = self.get_load_average() {
+                resources.load_average = [one, five, fifteen];
+            }
+        }
+        
+        // Get memory usage
+        if self.config.enable_memory_monitoring {
+            if let Ok((available, total)) = self.get_memory_usage() {
+                resources.available_memory_bytes = available;
+                resources.total_memory_bytes = total;
+            }
+            // The breakdown's figure counts reclaimable cache as available
+            resources.memory = self.get_memory_breakdown().ok();
+            if let Some(memory) = resources.memory.as_ref().filter(|memory| memory.total_bytes > 0.0) {
+                resources.available_memory_bytes = memory.available_bytes;
+                resources.total_memory_bytes = memory.total_bytes;
             }
+            if let Ok((used, total)) = self.get_swap_usage() {
+                resources.swap_used_bytes = used;
+                resources.swap_total_bytes = total;
+            }
+        }
+        
+        // Get disk usage
+        if self.config.enable_disk_monitoring {
+            if let Ok((available, total)) = self.get_disk_usage() {
+                resources.available_disk_bytes = available;
+                resources.total_disk_bytes = total;
+            }
+            resources.disk_io = self.get_disk_io().unwrap_or_default();
         }
         
         // Get network usage
@@ -242,6 +1671,7 @@ impl SystemMonitor {
             unsafe {
                 resources.network_usage_percent = crate::cba_monitor_get_network_usage();
             }
+            resources.network_io = self.get_network_io().unwrap_or_default();
         }
         
         // Get GPU usage
@@ -249,405 +1679,3497 @@ impl SystemMonitor {
             unsafe {
                 resources.gpu_usage_percent = crate::cba_monitor_get_gpu_usage();
             }
+            // Utilization alone misses device memory exhaustion
+            if let Ok(gpus) = self.list_gpus() {
+                resources.gpu_memory_used_bytes = gpus.iter().map(|gpu| gpu.memory_used_bytes as f64).sum();
+                resources.gpu_memory_total_bytes = gpus.iter().map(|gpu| gpu.memory_total_bytes as f64).sum();
+            }
+        }
+        
+        // Get temperatures and fan speeds
+        if self.config.enable_thermal_monitoring {
+            resources.thermal = self.get_thermal_info().ok();
+        }
+        
+        if self.config.enable_container_monitoring {
+            self.apply_container_limits(&mut resources);
+        }
+        
+        // Sample custom metrics
+        for source in &mut self.sources {
+            resources.custom.extend(source.sample());
         }
         
+        resources.uptime_seconds = self.get_uptime().map_or(0, |uptime| uptime.as_secs());
+        
         // Update timestamp
         resources.timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
         
-        // Add to history
-        self.add_to_history(&resources);
+        self.record_sample(&resources);
+        Ok(resources)
+    }
+    
+    /// Add a sample to the history and check it against alerts and
+    /// watchdogs
+    fn record_sample(&mut self, resources: &SystemResources) {
+        self.add_to_history(resources);
         self.last_update = Some(Instant::now());
+        self.evaluate_alerts(resources);
+        self.evaluate_watchdogs(resources);
+        crate::telemetry::publish(&self.config, resources);
+        self.latest = Some(resources.clone());
+    }
+    
+    /// Report CPU and memory against the container's limits
+    fn apply_container_limits(&mut self, resources: &mut SystemResources) {
+        resources.container = self.get_container_usage(resources);
+        if let Some(container) = &resources.container {
+            if let (Some(_), Some(usage)) = (container.cpu_limit_cores, container.cpu_usage_percent) {
+                resources.cpu_usage_percent = usage;
+            }
+            if let Some(limit) = container.memory_limit_bytes {
+                resources.total_memory_bytes = limit;
+                resources.available_memory_bytes = (limit - container.memory_used_bytes).max(0.0);
+            }
+        }
+    }
+    
+    /// Read the enclosing cgroup, keeping the host-wide CPU and memory
+    /// figures of `host`
+    fn get_container_usage(&mut self, host: &SystemResources) -> Option<ContainerUsage> {
+        #[cfg(target_os = "linux")]
+        let sample = read_cgroup(&self.cgroup_root)?;
+        #[cfg(not(target_os = "linux"))]
+        let sample = CgroupSample::default();
+        if sample.version == 0 {
+            return None;
+        }
         
-        Ok(resources)
+        let now = Instant::now();
+        let cpu_usage_percent = match (self.cgroup_cpu.take(), sample.cpu_usage_usec) {
+            (Some((then, before)), Some(usage)) => {
+                let cores = sample.cpu_limit_cores.unwrap_or_else(|| {
+                    std::thread::available_parallelism().map_or(1, |cores| cores.get()) as f64
+                });
+                let elapsed_usec = now.duration_since(then).as_secs_f64() * 1e6;
+                (elapsed_usec > 0.0).then(|| usage.saturating_sub(before) as f64 / (elapsed_usec * cores) * 100.0)
+            }
+            _ => None,
+        };
+        self.cgroup_cpu = sample.cpu_usage_usec.map(|usage| (now, usage));
+        
+        Some(ContainerUsage {
+            cgroup_version: sample.version,
+            cpu_limit_cores: sample.cpu_limit_cores,
+            cpu_usage_percent,
+            memory_limit_bytes: sample.memory_limit_bytes,
+            memory_used_bytes: sample.memory_used_bytes.unwrap_or_default(),
+            host_cpu_usage_percent: host.cpu_usage_percent,
+            host_available_memory_bytes: host.available_memory_bytes,
+            host_total_memory_bytes: host.total_memory_bytes,
+        })
     }
     
     /// Get CPU usage percentage
     pub fn get_cpu_usage(&self) -> CoreBaseResult<f64> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "SystemMonitor not initialized".to_string()
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        unsafe {
-            Ok(crate::cba_monitor_get_cpu_usage())
+        let usage = unsafe { crate::cba_monitor_get_cpu_usage() };
+        // The C++ side reports a failed reading as a negative value
+        #[cfg(feature = "fallback-sysinfo")]
+        if usage.is_nan() || usage < 0.0 {
+            return Ok(sysinfo_backend::cpu_usage());
         }
+        Ok(usage)
     }
     
-    /// Get memory usage information
-    pub fn get_memory_usage(&self) -> CoreBaseResult<(f64, f64)> {
+    /// Get the utilization of each logical core, in percent
+    pub fn get_cpu_per_core(&self) -> CoreBaseResult<Vec<f64>> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "SystemMonitor not initialized".to_string()
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        let mut available = 0.0;
-        let mut total = 0.0;
-        
-        unsafe {
-            let result = crate::cba_monitor_get_memory_usage(&mut available, &mut total);
-            if result == 0 {
-                Ok((available, total))
-            } else {
-                Err(CoreBaseError::MonitorError(
-                    "Failed to get memory usage".to_string()
-                ))
+        let mut usage = vec![0.0; std::thread::available_parallelism().map_or(1, |cores| cores.get())];
+        loop {
+            let capacity = c_int::try_from(usage.len()).unwrap_or(c_int::MAX);
+            let count = unsafe {
+                crate::cba_monitor_get_cpu_per_core(usage.as_mut_ptr(), capacity)
+            };
+            if count < 0 {
+                #[cfg(feature = "fallback-sysinfo")]
+                return Ok(sysinfo_backend::cpu_per_core());
+                #[cfg(not(feature = "fallback-sysinfo"))]
+                return Err(CoreBaseError::MonitorError(
+                    "Failed to get per-core CPU usage".to_string(),
+                    None,
+                ).with_last_error());
+            }
+            
+            // The core count can exceed the estimate, such as when the
+            // process is restricted to some cores
+            let count = count as usize;
+            if count <= usage.len() {
+                usage.truncate(count);
+                return Ok(usage);
             }
+            usage.resize(count, 0.0);
         }
     }
     
-    /// Get disk usage information
-    pub fn get_disk_usage(&self) -> CoreBaseResult<(f64, f64)> {
+    /// Get the 1, 5 and 15-minute load averages
+    pub fn get_load_average(&self) -> CoreBaseResult<(f64, f64, f64)> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "SystemMonitor not initialized".to_string()
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        let mut available = 0.0;
-        let mut total = 0.0;
+        let mut one = 0.0;
+        let mut five = 0.0;
+        let mut fifteen = 0.0;
         
-        unsafe {
-            let result = crate::cba_monitor_get_disk_usage(&mut available, &mut total);
-            if result == 0 {
-                Ok((available, total))
-            } else {
-                Err(CoreBaseError::MonitorError(
-                    "Failed to get disk usage".to_string()
-                ))
-            }
+        let result = unsafe { crate::cba_monitor_get_load_average(&mut one, &mut five, &mut fifteen) };
+        if result == 0 {
+            return Ok((one, five, fifteen));
         }
+        
+        #[cfg(feature = "fallback-sysinfo")]
+        return Ok(sysinfo_backend::load_average());
+        #[cfg(not(feature = "fallback-sysinfo"))]
+        Err(CoreBaseError::MonitorError(
+            "Failed to get load average".to_string(),
+            None,
+        ).with_last_error())
     }
     
-    /// Get network usage percentage
-    pub fn get_network_usage(&self) -> CoreBaseResult<f64> {
+    /// Get the time since the host booted
+    pub fn get_uptime(&self) -> CoreBaseResult<Duration> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "SystemMonitor not initialized".to_string()
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        unsafe {
-            Ok(crate::cba_monitor_get_network_usage())
+        let seconds = unsafe { crate::cba_monitor_get_uptime() };
+        #[cfg(feature = "fallback-sysinfo")]
+        if seconds.is_nan() || seconds < 0.0 {
+            return Ok(Duration::from_secs(sysinfo_backend::uptime_seconds()));
         }
+        Duration::try_from_secs_f64(seconds).map_err(|_| CoreBaseError::MonitorError(
+            "Failed to get uptime".to_string(),
+            None,
+        ))
     }
     
-    /// Get GPU usage percentage
-    pub fn get_gpu_usage(&self) -> CoreBaseResult<f64> {
+    /// Get swap usage information as (used, total) bytes
+    pub fn get_swap_usage(&self) -> CoreBaseResult<(f64, f64)> {
         if !self.initialized {
             return Err(CoreBaseError::OperationFailed(
-                "SystemMonitor not initialized".to_string()
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        unsafe {
-            Ok(crate::cba_monitor_get_gpu_usage())
-        }
-    }
-    
-    /// Get monitoring configuration
-    pub fn get_config(&self) -> &MonitoringConfig {
-        &self.config
-    }
-    
-    /// Update monitoring configuration
-    pub fn set_config(&mut self, config: MonitoringConfig) {
-        self.config = config;
+        let mut used = 0.0;
+        let mut total = 0.0;
         
-        // Resize history if needed
-        while self.history.len() > self.config.history_size {
-            self.history.pop_front();
+        let result = unsafe { crate::cba_monitor_get_swap_usage(&mut used, &mut total) };
+        if result == 0 {
+            return Ok((used, total));
         }
+        
+        #[cfg(feature = "fallback-sysinfo")]
+        return Ok(sysinfo_backend::swap_usage());
+        #[cfg(not(feature = "fallback-sysinfo"))]
+        Err(CoreBaseError::MonitorError(
+            "Failed to get swap usage".to_string(),
+            None,
+        ).with_last_error())
     }
     
-    /// Get historical monitoring data
-    pub fn get_history(&self) -> &VecDeque<MonitoringDataPoint> {
-        &self.history
-    }
-    
-    /// Get historical data as vector
-    pub fn get_history_vec(&self) -> Vec<MonitoringDataPoint> {
-        self.history.iter().cloned().collect()
-    }
-    
-    /// Clear monitoring history
-    pub fn clear_history(&mut self) {
-        self.history.clear();
-    }
-    
-    /// Check if any resource usage exceeds thresholds
-    pub fn check_thresholds(&self, resources: &SystemResources) -> Vec<String> {
-        let mut alerts = Vec::new();
-        
-        if self.config.enable_cpu_monitoring && resources.cpu_usage_percent > self.config.cpu_threshold {
-            alerts.push(format!(
-                "CPU usage ({:.1}%) exceeds threshold ({:.1}%)",
-                resources.cpu_usage_percent, self.config.cpu_threshold
+    /// Get memory usage information
+    pub fn get_memory_usage(&self) -> CoreBaseResult<(f64, f64)> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        if self.config.enable_memory_monitoring && resources.memory_usage_percent() > self.config.memory_threshold {
-            alerts.push(format!(
-                "Memory usage ({:.1}%) exceeds threshold ({:.1}%)",
-                resources.memory_usage_percent(), self.config.memory_threshold
-            ));
-        }
+        let mut available = 0.0;
+        let mut total = 0.0;
         
-        if self.config.enable_disk_monitoring && resources.disk_usage_percent() > self.config.disk_threshold {
-            alerts.push(format!(
-                "Disk usage ({:.1}%) exceeds threshold ({:.1}%)",
-                resources.disk_usage_percent(), self.config.disk_threshold
-            ));
+        let result = unsafe { crate::cba_monitor_get_memory_usage(&mut available, &mut total) };
+        if result == 0 {
+            return Ok((available, total));
         }
         
-        if self.config.enable_network_monitoring && resources.network_usage_percent > self.config.network_threshold {
-            alerts.push(format!(
-                "Network usage ({:.1}%) exceeds threshold ({:.1}%)",
-                resources.network_usage_percent, self.config.network_threshold
+        #[cfg(feature = "fallback-sysinfo")]
+        return Ok(sysinfo_backend::memory_usage());
+        #[cfg(not(feature = "fallback-sysinfo"))]
+        Err(CoreBaseError::MonitorError(
+            "Failed to get memory usage".to_string(),
+            None,
+        ).with_last_error())
+    }
+    
+    /// Get free, available, cached, buffer and shared memory
+    ///
+    /// Falls back to `/proc/meminfo` on Linux when the C++ side cannot
+    /// report the breakdown.
+    pub fn get_memory_breakdown(&self) -> CoreBaseResult<MemoryBreakdown> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
             ));
         }
         
-        if self.config.enable_gpu_monitoring && resources.gpu_usage_percent > self.config.gpu_threshold {
-            alerts.push(format!(
-                "GPU usage ({:.1}%) exceeds threshold ({:.1}%)",
-                resources.gpu_usage_percent, self.config.gpu_threshold
-            ));
+        let mut memory = MemoryBreakdown::default();
+        let mut commit_charge = f64::NAN;
+        let mut commit_limit = f64::NAN;
+        let result = unsafe {
+            crate::cba_monitor_get_memory_breakdown(
+                &mut memory.total_bytes,
+                &mut memory.free_bytes,
+                &mut memory.available_bytes,
+                &mut memory.cached_bytes,
+                &mut memory.buffers_bytes,
+                &mut memory.shared_bytes,
+                &mut commit_charge,
+                &mut commit_limit,
+            )
+        };
+        if result == 0 {
+            // Counters the platform does not track are reported as NaN
+            memory.commit_charge_bytes = Some(commit_charge).filter(|bytes| !bytes.is_nan());
+            memory.commit_limit_bytes = Some(commit_limit).filter(|bytes| !bytes.is_nan());
+            return Ok(memory);
         }
         
-        alerts
+        #[cfg(target_os = "linux")]
+        if let Some(memory) = read_meminfo(Path::new("/proc/meminfo")) {
+            return Ok(memory);
+        }
+        Err(CoreBaseError::MonitorError(
+            "Failed to get memory breakdown".to_string(),
+            None,
+        ))
+    }
+    
+    /// Get disk usage information
+    pub fn get_disk_usage(&self) -> CoreBaseResult<(f64, f64)> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let mut available = 0.0;
+        let mut total = 0.0;
+        
+        let result = unsafe { crate::cba_monitor_get_disk_usage(&mut available, &mut total) };
+        if result == 0 {
+            return Ok((available, total));
+        }
+        
+        #[cfg(feature = "fallback-sysinfo")]
+        return sysinfo_backend::disk_usage().ok_or_else(|| CoreBaseError::MonitorError(
+            "Failed to get disk usage".to_string(),
+            None,
+        ));
+        #[cfg(not(feature = "fallback-sysinfo"))]
+        Err(CoreBaseError::MonitorError(
+            "Failed to get disk usage".to_string(),
+            None,
+        ).with_last_error())
+    }
+    
+    /// Get the throughput and IOPS of each block device
+    ///
+    /// Rates are computed from the device counters since the previous
+    /// call, so the first call returns an empty list. Falls back to
+    /// `/proc/diskstats` on Linux when the C++ side has no disk I/O backend.
+    pub fn get_disk_io(&mut self) -> CoreBaseResult<Vec<DiskIo>> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let counters = read_disk_counters()?;
+        let now = Instant::now();
+        let rates = match self.disk_counters.take() {
+            Some((then, previous)) => {
+                let seconds = now.duration_since(then).as_secs_f64();
+                counters
+                    .iter()
+                    .filter(|_| seconds > 0.0)
+                    .filter_map(|current| {
+                        let before = previous.iter().find(|before| before.device == current.device)?;
+                        Some(current.rates_since(before, seconds))
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        
+        self.disk_counters = Some((now, counters));
+        Ok(rates)
+    }
+    
+    /// Get the bytes received and sent per second on each interface
+    ///
+    /// Rates are computed from the interface counters since the previous
+    /// call, so the first call returns an empty list. Falls back to
+    /// `/proc/net/dev` on Linux when the C++ side has no interface
+    /// counters.
+    pub fn get_network_io(&mut self) -> CoreBaseResult<Vec<NetworkIo>> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let counters = read_interface_counters()?;
+        let now = Instant::now();
+        let rates = match self.network_counters.take() {
+            Some((then, previous)) => {
+                let seconds = now.duration_since(then).as_secs_f64();
+                counters
+                    .iter()
+                    .filter(|_| seconds > 0.0)
+                    .filter_map(|current| {
+                        let before = previous.iter().find(|before| before.interface == current.interface)?;
+                        Some(current.rates_since(before, seconds))
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        
+        self.network_counters = Some((now, counters));
+        Ok(rates)
+    }
+    
+    /// Get network usage percentage
+    pub fn get_network_usage(&self) -> CoreBaseResult<f64> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        unsafe {
+            Ok(crate::cba_monitor_get_network_usage())
+        }
+    }
+    
+    /// Get GPU usage percentage
+    pub fn get_gpu_usage(&self) -> CoreBaseResult<f64> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        unsafe {
+            Ok(crate::cba_monitor_get_gpu_usage())
+        }
+    }
+    
+    /// Get the resource usage of the current process
+    pub fn get_process_usage(&self) -> CoreBaseResult<ProcessUsage> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let mut cpu_percent = 0.0;
+        let mut rss_bytes = 0;
+        let mut virtual_bytes = 0;
+        let mut open_fds = 0;
+        let mut threads = 0;
+        
+        let result = unsafe {
+            crate::cba_monitor_get_process_usage(
+                &mut cpu_percent,
+                &mut rss_bytes,
+                &mut virtual_bytes,
+                &mut open_fds,
+                &mut threads,
+            )
+        };
+        if result == 0 {
+            return Ok(ProcessUsage {
+                cpu_usage_percent: cpu_percent,
+                rss_bytes,
+                virtual_memory_bytes: virtual_bytes,
+                open_fds: open_fds.max(0) as u32,
+                thread_count: threads.max(0) as u32,
+            });
+        }
+        
+        #[cfg(feature = "fallback-sysinfo")]
+        if let Some(usage) = sysinfo_backend::process_usage() {
+            return Ok(usage);
+        }
+        Err(CoreBaseError::MonitorError(
+            "Failed to get process usage".to_string(),
+            None,
+        ))
+    }
+    
+    /// Get temperatures and fan speeds
+    ///
+    /// Falls back to the kernel's thermal zones and hwmon sensors on Linux
+    /// when the C++ side cannot read them.
+    pub fn get_thermal_info(&self) -> CoreBaseResult<ThermalInfo> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        let mut cpu_celsius = f64::NAN;
+        let mut gpu_celsius = f64::NAN;
+        let mut fan_rpm = vec![0.0; INITIAL_FAN_CAPACITY];
+        loop {
+            let capacity = c_int::try_from(fan_rpm.len()).unwrap_or(c_int::MAX);
+            let count = unsafe {
+                crate::cba_monitor_get_thermal_info(&mut cpu_celsius, &mut gpu_celsius, fan_rpm.as_mut_ptr(), capacity)
+            };
+            if count < 0 {
+                break;
+            }
+            
+            let count = count as usize;
+            if count <= fan_rpm.len() {
+                fan_rpm.truncate(count);
+                // Missing sensors are reported as NaN
+                return Ok(ThermalInfo {
+                    cpu_temperature_celsius: Some(cpu_celsius).filter(|celsius| !celsius.is_nan()),
+                    gpu_temperature_celsius: Some(gpu_celsius).filter(|celsius| !celsius.is_nan()),
+                    fan_rpm,
+                });
+            }
+            fan_rpm.resize(count, 0.0);
+        }
+        
+        #[cfg(target_os = "linux")]
+        if let Some(thermal) = read_sysfs_thermal(std::path::Path::new("/sys/class")) {
+            return Ok(thermal);
+        }
+        #[cfg(feature = "fallback-sysinfo")]
+        if let Some(thermal) = sysinfo_backend::thermal_info() {
+            return Ok(thermal);
+        }
+        Err(CoreBaseError::MonitorError(
+            "No thermal sensors available".to_string(),
+            None,
+        ))
+    }
+    
+    /// List the processes running on the host
+    pub fn list_processes(&self) -> CoreBaseResult<Vec<ProcessInfo>> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        // The C++ side returns the list as a JSON array
+        let Some(list) = (unsafe { CbaString::from_raw(crate::cba_monitor_list_processes()) }) else {
+            #[cfg(feature = "fallback-sysinfo")]
+            return Ok(sysinfo_backend::list_processes());
+            #[cfg(not(feature = "fallback-sysinfo"))]
+            return Err(CoreBaseError::MonitorError("Failed to list processes".to_string(), None).with_last_error());
+        };
+        serde_json::from_str(list.to_str()?)
+            .map_err(|e| CoreBaseError::MonitorError(format!("Invalid process list: {}", e), None))
+    }
+    
+    /// Get the `n` processes using the most CPU, busiest first
+    pub fn top_by_cpu(&self, n: usize) -> CoreBaseResult<Vec<ProcessInfo>> {
+        Ok(busiest_processes(self.list_processes()?, n))
+    }
+    
+    /// Get the `n` processes using the most memory, largest first
+    pub fn top_by_memory(&self, n: usize) -> CoreBaseResult<Vec<ProcessInfo>> {
+        Ok(largest_processes(self.list_processes()?, n))
+    }
+    
+    /// List the GPUs with their individual metrics
+    ///
+    /// Uses NVML when the C++ side does not provide GPU metrics and the
+    /// "nvml" feature is enabled.
+    pub fn list_gpus(&self) -> CoreBaseResult<Vec<GpuInfo>> {
+        if !self.initialized {
+            return Err(CoreBaseError::OperationFailed(
+                "SystemMonitor not initialized".to_string(),
+                None,
+            ));
+        }
+        
+        // The C++ side returns the list as a JSON array, or null without
+        // a GPU backend
+        if let Some(list) = unsafe { CbaString::from_raw(crate::cba_monitor_list_gpus()) } {
+            return serde_json::from_str(list.to_str()?)
+                .map_err(|e| CoreBaseError::MonitorError(format!("Invalid GPU list: {}", e), None));
+        }
+        
+        #[cfg(feature = "nvml")]
+        return nvml::list_gpus();
+        #[cfg(not(feature = "nvml"))]
+        Err(CoreBaseError::MonitorError(
+            "No GPU metrics backend available".to_string(),
+            None,
+        ))
+    }
+    
+    /// Get monitoring configuration
+    pub fn get_config(&self) -> &MonitoringConfig {
+        &self.config
+    }
+    
+    /// Update monitoring configuration
+    pub fn set_config(&mut self, config: MonitoringConfig) {
+        self.config = config;
+        
+        // Resize history if needed
+        self.trim_history();
+        let tiers = &self.config.history_tiers;
+        self.tiers.truncate(tiers.len());
+        for (tier, aggregates) in tiers.iter().zip(&mut self.tiers) {
+            while aggregates.len() > tier.capacity {
+                aggregates.pop_front();
+            }
+        }
+    }
+    
+    /// Get historical monitoring data
+    pub fn get_history(&self) -> &VecDeque<MonitoringDataPoint> {
+        &self.history
+    }
+    
+    /// Get historical data as vector
+    pub fn get_history_vec(&self) -> Vec<MonitoringDataPoint> {
+        self.history.iter().cloned().collect()
+    }
+    
+    /// Iterate over the points taken from `from_ts` to `to_ts` inclusive,
+    /// oldest first
+    pub fn history_range(&self, from_ts: u64, to_ts: u64) -> impl Iterator<Item = &MonitoringDataPoint> {
+        // Points are recorded in timestamp order
+        let start = self.history.partition_point(|point| point.timestamp < from_ts);
+        self.history.range(start..).take_while(move |point| point.timestamp <= to_ts)
+    }
+    
+    /// Iterate over the points taken within `window` of the newest point,
+    /// oldest first
+    pub fn history_last(&self, window: Duration) -> impl Iterator<Item = &MonitoringDataPoint> {
+        let newest = self.history.back().map_or(0, |point| point.timestamp);
+        self.history_range(newest.saturating_sub(window.as_secs()), newest)
+    }
+    
+    /// Get the recorded values of a custom metric as (timestamp, value) pairs
+    pub fn get_metric_history(&self, name: &str) -> Vec<(u64, f64)> {
+        self.history
+            .iter()
+            .filter_map(|point| point.custom.get(name).map(|value| (point.timestamp, *value)))
+            .collect()
+    }
+    
+    /// Add a source of custom metrics
+    ///
+    /// Its metrics are sampled with every `get_system_resources()` call,
+    /// kept in the history and checked against `metric_thresholds`.
+    pub fn add_metric_source<S: MetricSource + 'static>(&mut self, source: S) {
+        self.sources.push(Box::new(source));
+    }
+    
+    /// Get the aggregates of a tier of `history_tiers`, oldest first
+    pub fn get_history_tier(&self, index: usize) -> Option<&VecDeque<HistoryAggregate>> {
+        self.tiers.get(index)
+    }
+    
+    /// Clear monitoring history, including the downsampled tiers
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.tiers.clear();
+    }
+    
+    /// Write the history to `writer`, oldest point first
+    ///
+    /// CSV output has one column per label and per custom metric seen
+    /// anywhere in the history, left empty for points that lack it. JSON
+    /// lines carry the `labels` and `metric_tags` of the configuration in
+    /// `labels` and `tags` fields. Returns the number of data points
+    /// written.
+    pub fn export_history<W: Write>(&self, format: ExportFormat, mut writer: W) -> CoreBaseResult<usize> {
+        let result = match format {
+            ExportFormat::Csv => write_csv(&self.history, &self.config.labels, &mut writer),
+            ExportFormat::JsonLines => write_json_lines(&self.history, &self.config, &mut writer),
+        };
+        result.and_then(|_| writer.flush())
+            .map(|_| self.history.len())
+            .map_err(|e| CoreBaseError::MonitorError(format!("Failed to export history: {}", e), None))
+    }
+    
+    /// Save the history to a JSON file
+    pub fn save_history<P: AsRef<Path>>(&self, path: P) -> CoreBaseResult<()> {
+        self.save_history_with(path, &JsonCodec)
+    }
+    
+    /// Save the history to a file in the format of `codec`
+    ///
+    /// Use a binary codec such as `PayloadCodec::Bincode` for a compact
+    /// file. The data is written to a temporary file first and then moved
+    /// into place, so a crash while saving keeps the previous file intact.
+    pub fn save_history_with<P: AsRef<Path>, C: Codec>(&self, path: P, codec: &C) -> CoreBaseResult<()> {
+        let path = path.as_ref();
+        let data = codec.encode(&self.history)?;
+        let staging = staging_path(path);
+        fs::write(&staging, data)
+            .and_then(|_| fs::rename(&staging, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&staging);
+                CoreBaseError::MonitorError(format!("Failed to save history to {}: {}", path.display(), e), None)
+            })
+    }
+    
+    /// Load the history from a JSON file written by `save_history()`
+    ///
+    /// Returns the number of data points kept.
+    pub fn load_history<P: AsRef<Path>>(&mut self, path: P) -> CoreBaseResult<usize> {
+        self.load_history_with(path, &JsonCodec)
+    }
+    
+    /// Load the history from a file written by `save_history_with()`
+    ///
+    /// The loaded points replace the current history. If the file holds
+    /// more than `history_size` points only the newest are kept. Returns
+    /// the number of data points kept.
+    pub fn load_history_with<P: AsRef<Path>, C: Codec>(&mut self, path: P, codec: &C) -> CoreBaseResult<usize> {
+        let path = path.as_ref();
+        let data = fs::read(path).map_err(|e| CoreBaseError::MonitorError(
+            format!("Failed to read history from {}: {}", path.display(), e),
+            None,
+        ))?;
+        let mut history: VecDeque<MonitoringDataPoint> = codec.decode(&data)?;
+        while history.len() > self.config.history_size {
+            history.pop_front();
+        }
+        
+        self.history = history;
+        Ok(self.history.len())
+    }
+    
+    /// Check which metrics of a sample exceed their thresholds
+    ///
+    /// `first_seen` and `count` continue the episode tracked over the
+    /// samples taken by `get_system_resources()`, if the metric is in one.
+    pub fn check_thresholds(&self, resources: &SystemResources) -> Vec<Alert> {
+        self.threshold_readings(resources)
+            .into_iter()
+            .filter(|reading| reading.value > reading.threshold)
+            .map(|reading| {
+                let tracker = self.alert_states.get(&reading.metric).filter(|tracker| tracker.count > 0);
+                let critical = self.config.critical_thresholds.get(&reading.metric);
+                Alert {
+                    severity: if critical.is_some_and(|critical| reading.value > *critical) {
+                        AlertSeverity::Critical
+                    } else {
+                        AlertSeverity::Warning
+                    },
+                    first_seen: tracker.and_then(|tracker| tracker.first_seen).unwrap_or(resources.timestamp),
+                    count: tracker.map_or(1, |tracker| tracker.count),
+                    state: AlertState::Triggered,
+                    message: reading.exceeded,
+                    metric: reading.metric,
+                    value: reading.value,
+                    threshold: reading.threshold,
+                }
+            })
+            .collect()
+    }
+    
+    /// Call `callback` whenever a metric crosses its threshold
+    ///
+    /// Samples taken by `get_system_resources()` are checked against the
+    /// thresholds with the margins and sustain time of `alert_policy`, or
+    /// of the metric's entry in `metric_alert_policies`. A metric triggers
+    /// once and is not reported again until it has resolved, or until it
+    /// crosses its entry in `critical_thresholds` in either direction. With
+    /// `anomaly_detection` set, unusual samples of every metric are also
+    /// reported, as `AlertState::Anomaly`.
+    pub fn on_alert<F>(&mut self, callback: F)
+    where
+        F: Fn(&Alert) + Send + Sync + 'static,
+    {
+        self.alert_callbacks.0.push(Arc::new(callback));
+    }
+    
+    /// Receive every alert raised or cleared from now on
+    ///
+    /// Alerts are raised and cleared as for `on_alert`, including when an
+    /// active alert changes severity; anomalies are only reported to
+    /// `on_alert`. A receiver is removed once it is dropped, and keeps
+    /// receiving after the monitor moves to a background thread.
+    pub fn alert_events(&mut self) -> Receiver<AlertEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.alert_subscribers.push(sender);
+        receiver
+    }
+    
+    /// Run `action` once `metric` has stayed above `above` for at least
+    /// `for_at_least`
+    ///
+    /// Samples taken by `get_system_resources()` are checked, so momentary
+    /// spikes between samples are ignored. The action receives the sample
+    /// that completed the violation and runs again only after the metric
+    /// has dropped back to the limit or below.
+    ///
+    /// ```ignore
+    /// monitor.watchdog(Metric::Cpu, 95.0, Duration::from_secs(60), |resources| dump_diagnostics(resources));
+    /// ```
+    pub fn watchdog<F>(&mut self, metric: Metric, above: f64, for_at_least: Duration, action: F)
+    where
+        F: Fn(&SystemResources) + Send + Sync + 'static,
+    {
+        self.watchdogs.push(Watchdog {
+            metric,
+            above,
+            for_at_least,
+            action: Arc::new(action),
+            above_since: None,
+            fired: false,
+        });
+    }
+    
+    /// Remove every watchdog of a metric
+    ///
+    /// Returns whether any watchdog was removed.
+    pub fn remove_watchdogs(&mut self, metric: &Metric) -> bool {
+        let before = self.watchdogs.len();
+        self.watchdogs.retain(|watchdog| watchdog.metric != *metric);
+        self.watchdogs.len() != before
+    }
+    
+    /// Each monitored metric with its current value and threshold
+    fn threshold_readings(&self, resources: &SystemResources) -> Vec<ThresholdReading> {
+        let config = &self.config;
+        let mut readings = Vec::new();
+        let mut percent = |metric: &str, label: &str, value: f64, threshold: f64| {
+            readings.push(ThresholdReading {
+                metric: metric.to_string(),
+                label: label.to_string(),
+                value,
+                threshold,
+                exceeded: format!("{} ({:.1}%) exceeds threshold ({:.1}%)", label, value, threshold),
+            });
+        };
+        
+        if config.enable_cpu_monitoring {
+            percent("cpu", "CPU usage", resources.cpu_usage_percent, config.cpu_threshold);
+        }
+        if config.enable_memory_monitoring {
+            percent("memory", "Memory usage", resources.memory_usage_percent(), config.memory_threshold);
+            percent("swap", "Swap usage", resources.swap_usage_percent(), config.swap_threshold);
+        }
+        if config.enable_disk_monitoring {
+            percent("disk", "Disk usage", resources.disk_usage_percent(), config.disk_threshold);
+        }
+        if config.enable_network_monitoring {
+            percent("network", "Network usage", resources.network_usage_percent, config.network_threshold);
+        }
+        if config.enable_gpu_monitoring {
+            percent("gpu", "GPU usage", resources.gpu_usage_percent, config.gpu_threshold);
+            if resources.gpu_memory_total_bytes > 0.0 {
+                percent(
+                    "gpu_memory",
+                    "GPU memory usage",
+                    resources.gpu_memory_usage_percent(),
+                    config.gpu_memory_threshold,
+                );
+            }
+        }
+        
+        if config.enable_disk_monitoring {
+            for io in &resources.disk_io {
+                if let Some(threshold) = config.disk_throughput_threshold {
+                    let value = io.total_bytes_per_sec();
+                    readings.push(ThresholdReading {
+                        metric: format!("disk_throughput.{}", io.device),
+                        label: format!("Disk {} throughput", io.device),
+                        value,
+                        threshold,
+                        exceeded: format!(
+                            "Disk {} throughput ({}/s) exceeds threshold ({}/s)",
+                            io.device,
+                            SystemResources::format_bytes(value),
+                            SystemResources::format_bytes(threshold)
+                        ),
+                    });
+                }
+                if let Some(threshold) = config.disk_iops_threshold {
+                    readings.push(ThresholdReading {
+                        metric: format!("disk_iops.{}", io.device),
+                        label: format!("Disk {} IOPS", io.device),
+                        value: io.total_iops(),
+                        threshold,
+                        exceeded: format!("Disk {} IOPS ({:.0}) exceeds threshold ({:.0})", io.device, io.total_iops(), threshold),
+                    });
+                }
+            }
+        }
+        
+        if config.enable_cpu_monitoring {
+            let cores = resources.cpu_per_core.len().max(1) as f64;
+            let threshold = config.load_threshold * cores;
+            readings.push(ThresholdReading {
+                metric: "load".to_string(),
+                label: "Load average".to_string(),
+                value: resources.load_average[0],
+                threshold,
+                exceeded: format!(
+                    "Load average ({:.2}) exceeds threshold ({:.2} for {} cores)",
+                    resources.load_average[0], threshold, cores
+                ),
+            });
+        }
+        
+        if let Some(thermal) = resources.thermal.as_ref().filter(|_| config.enable_thermal_monitoring) {
+            let sensors = [
+                ("cpu_temperature", "CPU temperature", thermal.cpu_temperature_celsius, config.cpu_temperature_threshold),
+                ("gpu_temperature", "GPU temperature", thermal.gpu_temperature_celsius, config.gpu_temperature_threshold),
+            ];
+            for (metric, label, celsius, threshold) in sensors {
+                if let Some(celsius) = celsius {
+                    readings.push(ThresholdReading {
+                        metric: metric.to_string(),
+                        label: label.to_string(),
+                        value: celsius,
+                        threshold,
+                        exceeded: format!("{} ({:.1}°C) exceeds threshold ({:.1}°C)", label, celsius, threshold),
+                    });
+                }
+            }
+        }
+        
+        for (name, threshold) in &config.metric_thresholds {
+            if let Some(value) = resources.custom.get(name) {
+                readings.push(ThresholdReading {
+                    metric: name.clone(),
+                    label: format!("Metric {}", name),
+                    value: *value,
+                    threshold: *threshold,
+                    exceeded: format!("Metric {} ({:.1}) exceeds threshold ({:.1})", name, value, threshold),
+                });
+            }
+        }
+        
+        readings
+    }
+    
+    /// Track threshold crossings of a sample, notify `on_alert` callbacks
+    /// and log the alerts if `alert_logging` is set
+    fn evaluate_alerts(&mut self, resources: &SystemResources) {
+        let now = Instant::now();
+        let readings = self.threshold_readings(resources);
+        let mut alerts = self.detect_anomalies(&readings, resources);
+        for reading in readings {
+            let policy = self.config.metric_alert_policies
+                .get(&reading.metric)
+                .unwrap_or(&self.config.alert_policy);
+            let critical = self.config.critical_thresholds.get(&reading.metric).copied();
+            let tracker = self.alert_states.entry(reading.metric.clone()).or_default();
+            tracker.observe(reading.value, reading.threshold, resources.timestamp);
+            let first_seen = tracker.first_seen.unwrap_or(resources.timestamp);
+            let count = tracker.count;
+            
+            // Critical alerts use the same margins against their own threshold
+            let severity = match critical {
+                Some(critical) if tracker.severity == AlertSeverity::Warning
+                    && reading.value > critical + policy.rising_margin => AlertSeverity::Critical,
+                Some(critical) if tracker.severity == AlertSeverity::Critical
+                    && reading.value >= critical - policy.falling_margin => AlertSeverity::Critical,
+                _ => AlertSeverity::Warning,
+            };
+            
+            // Raised on first crossing and again whenever the severity changes
+            let triggered = if tracker.active {
+                if reading.value < reading.threshold - policy.falling_margin {
+                    alerts.push(Alert {
+                        message: format!("{} ({:.1}) back below threshold ({:.1})", reading.label, reading.value, reading.threshold),
+                        metric: reading.metric.clone(),
+                        severity: tracker.severity,
+                        value: reading.value,
+                        threshold: reading.threshold,
+                        first_seen,
+                        count,
+                        state: AlertState::Resolved,
+                    });
+                    *tracker = AlertTracker::default();
+                    false
+                } else {
+                    severity != tracker.severity
+                }
+            } else if reading.value > reading.threshold + policy.rising_margin {
+                let since = *tracker.above_since.get_or_insert(now);
+                tracker.active = now.duration_since(since) >= policy.sustain;
+                tracker.active
+            } else {
+                tracker.above_since = None;
+                false
+            };
+            
+            if triggered {
+                tracker.severity = severity;
+                alerts.push(Alert {
+                    metric: reading.metric,
+                    severity,
+                    value: reading.value,
+                    threshold: reading.threshold,
+                    first_seen,
+                    count,
+                    state: AlertState::Triggered,
+                    message: reading.exceeded,
+                });
+            }
+        }
+        
+        if let Some(logging) = &self.config.alert_logging {
+            let handler = ErrorHandler::default();
+            for alert in &alerts {
+                let level = match (alert.state, alert.severity) {
+                    (AlertState::Resolved, _) => LogLevel::Info,
+                    (_, AlertSeverity::Critical) => LogLevel::Critical,
+                    _ => logging.level_for(&alert.metric),
+                };
+                let _ = handler.log(level, &format!("Monitor alert: {}", alert.message));
+            }
+        }
+        
+        for alert in &alerts {
+            for callback in &self.alert_callbacks.0 {
+                callback(alert);
+            }
+        }
+        
+        let events: Vec<AlertEvent> = alerts.iter().filter_map(AlertEvent::from_alert).collect();
+        if !events.is_empty() {
+            self.alert_subscribers.retain(|subscriber| events.iter().all(|event| subscriber.send(event.clone()).is_ok()));
+        }
+    }
+    
+    /// Track sustained violations of a sample and run the due watchdogs
+    fn evaluate_watchdogs(&mut self, resources: &SystemResources) {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        for watchdog in &mut self.watchdogs {
+            match watchdog.metric.value(resources) {
+                Some(value) if value > watchdog.above => {
+                    let since = *watchdog.above_since.get_or_insert(now);
+                    if !watchdog.fired && now.duration_since(since) >= watchdog.for_at_least {
+                        watchdog.fired = true;
+                        due.push(Arc::clone(&watchdog.action));
+                    }
+                }
+                _ => {
+                    watchdog.above_since = None;
+                    watchdog.fired = false;
+                }
+            }
+        }
+        
+        for action in due {
+            action(resources);
+        }
+    }
+    
+    /// Update the anomaly baselines with a sample and report the metrics
+    /// that left their band
+    ///
+    /// Custom metrics are checked whether or not they have a threshold.
+    fn detect_anomalies(&mut self, readings: &[ThresholdReading], resources: &SystemResources) -> Vec<Alert> {
+        let Some(detection) = &self.config.anomaly_detection else {
+            return Vec::new();
+        };
+        
+        let unchecked = resources.custom
+            .iter()
+            .filter(|(name, _)| !self.config.metric_thresholds.contains_key(*name))
+            .map(|(name, value)| (name.as_str(), format!("Metric {}", name), *value));
+        let samples = readings
+            .iter()
+            .map(|reading| (reading.metric.as_str(), reading.label.clone(), reading.value))
+            .chain(unchecked);
+        
+        let mut alerts = Vec::new();
+        for (metric, label, value) in samples {
+            let baseline = self.anomaly_baselines.entry(metric.to_string()).or_default();
+            if let Some((low, high)) = baseline.observe(value, detection) {
+                alerts.push(Alert {
+                    metric: metric.to_string(),
+                    severity: AlertSeverity::Warning,
+                    value,
+                    threshold: if value > high { high } else { low },
+                    first_seen: resources.timestamp,
+                    count: 1,
+                    state: AlertState::Anomaly,
+                    message: format!("{} ({:.1}) outside its usual range ({:.1} to {:.1})", label, value, low, high),
+                });
+            }
+        }
+        alerts
+    }
+    
+    /// Get statistics of every metric over the history
+    ///
+    /// Returns `None` if the history is empty.
+    pub fn get_statistics(&self) -> Option<HistoryStatistics> {
+        HistoryStatistics::from_points(&self.history)
+    }
+    
+    /// Get statistics of every metric over the data points with
+    /// timestamps from `start` to `end`, inclusive
+    ///
+    /// Returns `None` if no data point falls in the range.
+    pub fn get_statistics_between(&self, start: u64, end: u64) -> Option<HistoryStatistics> {
+        HistoryStatistics::from_points(
+            self.history.iter().filter(|point| (start..=end).contains(&point.timestamp))
+        )
     }
     
     /// Get average usage over the history
+    ///
+    /// Custom metrics are averaged over the points that have them. See
+    /// `get_statistics()` for spread and percentiles.
     pub fn get_average_usage(&self) -> Option<MonitoringDataPoint> {
-        if self.history.is_empty() {
-            return None;
+        self.get_statistics().map(|stats| stats.to_point(unix_now(), |metric| metric.mean))
+    }
+    
+    /// Get peak usage over the history
+    pub fn get_peak_usage(&self) -> Option<MonitoringDataPoint> {
+        self.get_statistics().map(|stats| stats.to_point(unix_now(), |metric| metric.max))
+    }
+    
+    /// Summarize the health of the host from the latest sample
+    ///
+    /// Metrics above their threshold make the report degraded. Active
+    /// alerts, which have passed the `alert_policy` margins and sustain
+    /// time, make it critical, as does a missing sample or one older than
+    /// `health_stale_after`.
+    pub fn health(&self) -> HealthReport {
+        let mut report = HealthReport::healthy();
+        if !self.initialized {
+            report.add(HealthStatus::Critical, "SystemMonitor not initialized".to_string());
+            return report;
+        }
+        let (Some(last_update), Some(resources)) = (self.last_update, &self.latest) else {
+            report.add(HealthStatus::Critical, "No sample taken yet".to_string());
+            return report;
+        };
+        
+        let stale_after = self.config.health_stale_after.unwrap_or(self.config.update_interval * 3);
+        let age = last_update.elapsed();
+        if age > stale_after {
+            report.add(HealthStatus::Critical, format!("Last sample is {:.1}s old", age.as_secs_f64()));
+        }
+        
+        for reading in self.threshold_readings(resources) {
+            let active = self.alert_states.get(&reading.metric).is_some_and(|tracker| tracker.active);
+            if reading.value > reading.threshold {
+                let status = if active { HealthStatus::Critical } else { HealthStatus::Degraded };
+                report.add(status, reading.exceeded);
+            } else if active {
+                report.add(HealthStatus::Critical, format!(
+                    "{} ({:.1}) has not recovered below threshold ({:.1})",
+                    reading.label, reading.value, reading.threshold
+                ));
+            }
+        }
+        report
+    }
+    
+    /// Render the history of a metric as an ASCII sparkline of at most
+    /// `width` characters, oldest on the left
+    ///
+    /// Returns an empty string if no history point records the metric.
+    pub fn render_sparkline(&self, metric: &Metric, width: usize) -> String {
+        let values: Vec<f64> = self.history.iter().filter_map(|point| metric.point_value(point)).collect();
+        sparkline(&values, width)
+    }
+    
+    /// Render a plain-text view of the latest values, their sparklines
+    /// and the health report, for terminals without a dashboard
+    pub fn render_dashboard(&self) -> String {
+        let health = self.health();
+        let mut metrics = vec![
+            Metric::Cpu,
+            Metric::Memory,
+            Metric::Swap,
+            Metric::Disk,
+            Metric::Network,
+            Metric::Gpu,
+            Metric::Load,
+        ];
+        if let Some(latest) = self.history.back() {
+            metrics.extend(latest.custom.keys().cloned().map(Metric::Custom));
         }
+        let label_width = metrics.iter().map(|metric| metric.name().len()).max().unwrap_or_default();
+        
+        let mut view = format!("System monitor: {:?} ({} samples)\n", health.status, self.history.len());
+        if let Some(latest) = self.history.back() {
+            for metric in &metrics {
+                let Some(value) = metric.point_value(latest) else {
+                    continue;
+                };
+                view.push_str(&format!(
+                    "{:<label_width$} {:>8.1}  {}\n",
+                    metric.name(),
+                    value,
+                    self.render_sparkline(metric, DASHBOARD_WIDTH),
+                ));
+            }
+        }
+        for reason in &health.reasons {
+            view.push_str(&format!("  ! {}\n", reason));
+        }
+        view
+    }
+    
+    /// Check if it's time to update based on the configured interval
+    pub fn should_update(&self) -> bool {
+        match self.last_update {
+            Some(last) => last.elapsed() >= self.config.update_interval,
+            None => true,
+        }
+    }
+    
+    /// Get the latest sample if it is less than `update_interval` old,
+    /// otherwise take a new one
+    ///
+    /// Lets callers that poll more often than the interval share one
+    /// sample instead of querying the system each time.
+    pub fn current(&mut self) -> CoreBaseResult<SystemResources> {
+        match &self.latest {
+            Some(latest) if !self.should_update() => Ok(latest.clone()),
+            _ => self.get_system_resources(),
+        }
+    }
+    
+    /// Sample resources on a background thread every `update_interval`
+    ///
+    /// The monitor, with its history, moves behind the returned handle.
+    pub fn start_background(config: MonitoringConfig) -> CoreBaseResult<MonitorHandle> {
+        Self::with_config(config)?.into_background()
+    }
+    
+    /// Move this monitor, with its metric sources and history, to a
+    /// background sampling thread
+    pub fn into_background(self) -> CoreBaseResult<MonitorHandle> {
+        let shared = Arc::new(BackgroundState::new(self));
+        let (stop, stopped) = mpsc::channel();
+        
+        let sampler_shared = Arc::clone(&shared);
+        let sampler = std::thread::Builder::new()
+            .name("cba-monitor".to_string())
+            .spawn(move || sample_loop(&sampler_shared, &stopped))
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to start monitor thread: {}", e), None))?;
+        
+        Ok(MonitorHandle {
+            shared,
+            stop: Some(stop),
+            sampler: Some(sampler),
+        })
+    }
+    
+    /// Wrap this monitor for sharing between threads
+    pub fn into_shared(self) -> SharedSystemMonitor {
+        SharedSystemMonitor::new(self)
+    }
+    
+    /// Add a data point to history
+    fn add_to_history(&mut self, resources: &SystemResources) {
+        let data_point = MonitoringDataPoint::from(resources);
+        
+        self.history.push_back(data_point);
+        
+        // Maintain history size limit
+        self.trim_history();
+    }
+    
+    /// Evict the oldest points beyond `history_size` or older than
+    /// `history_retention` into the tiers
+    fn trim_history(&mut self) {
+        let newest = self.history.back().map(|point| point.timestamp).unwrap_or_default();
+        let oldest_kept = self.config.history_retention
+            .map(|retention| newest.saturating_sub(retention.as_secs()))
+            .unwrap_or_default();
+        
+        while let Some(point) = self.history.front() {
+            if self.history.len() <= self.config.history_size && point.timestamp >= oldest_kept {
+                break;
+            }
+            if let Some(point) = self.history.pop_front() {
+                self.downsample(HistoryAggregate::from_point(&point));
+            }
+        }
+    }
+    
+    /// Fold an aggregate into the first tier, cascading the evicted
+    /// aggregates of each tier into the next
+    fn downsample(&mut self, mut aggregate: HistoryAggregate) {
+        let tiers = &self.config.history_tiers;
+        if self.tiers.len() < tiers.len() {
+            self.tiers.resize_with(tiers.len(), VecDeque::new);
+        }
+        
+        for (tier, aggregates) in tiers.iter().zip(&mut self.tiers) {
+            let step = tier.step();
+            let start = aggregate.start / step * step;
+            if let Some(last) = aggregates.back_mut().filter(|last| last.start == start) {
+                last.merge(aggregate);
+                return;
+            }
+            
+            aggregate.start = start;
+            aggregate.end = start + step;
+            aggregates.push_back(aggregate);
+            if aggregates.len() <= tier.capacity {
+                return;
+            }
+            match aggregates.pop_front() {
+                Some(evicted) => aggregate = evicted,
+                None => return,
+            }
+        }
+    }
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new().unwrap_or(SystemMonitor {
+            initialized: false,
+            config: MonitoringConfig::default(),
+            history: VecDeque::new(),
+            tiers: Vec::new(),
+            last_update: None,
+            latest: None,
+            sources: Vec::new(),
+            alert_callbacks: AlertCallbacks::default(),
+            alert_subscribers: Vec::new(),
+            alert_states: HashMap::new(),
+            anomaly_baselines: HashMap::new(),
+            watchdogs: Vec::new(),
+            disk_counters: None,
+            network_counters: None,
+            cgroup_root: PathBuf::from("/sys/fs/cgroup"),
+            cgroup_cpu: None,
+        })
+    }
+}
+
+/// NVML backend for per-GPU metrics (requires "nvml" feature)
+#[cfg(feature = "nvml")]
+mod nvml {
+    use std::sync::OnceLock;
+    use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+    use nvml_wrapper::Nvml;
+    
+    use super::GpuInfo;
+    use crate::error::{CoreBaseError, CoreBaseResult};
+    
+    /// NVML handle, loaded on first use
+    static NVML: OnceLock<Result<Nvml, String>> = OnceLock::new();
+    
+    pub(super) fn list_gpus() -> CoreBaseResult<Vec<GpuInfo>> {
+        let nvml = NVML
+            .get_or_init(|| Nvml::init().map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| CoreBaseError::MonitorError(format!("Failed to load NVML: {}", e), None))?;
+        let count = nvml.device_count().map_err(nvml_error)?;
+        
+        (0..count)
+            .map(|index| {
+                let device = nvml.device_by_index(index).map_err(nvml_error)?;
+                let memory = device.memory_info().map_err(nvml_error)?;
+                Ok(GpuInfo {
+                    index,
+                    name: device.name().map_err(nvml_error)?,
+                    utilization_percent: device.utilization_rates().map_err(nvml_error)?.gpu as f64,
+                    memory_used_bytes: memory.used,
+                    memory_total_bytes: memory.total,
+                    temperature_celsius: device.temperature(TemperatureSensor::Gpu).ok().map(f64::from),
+                    // NVML reports milliwatts
+                    power_watts: device.power_usage().ok().map(|milliwatts| milliwatts as f64 / 1000.0),
+                })
+            })
+            .collect()
+    }
+    
+    fn nvml_error(error: nvml_wrapper::error::NvmlError) -> CoreBaseError {
+        CoreBaseError::MonitorError(format!("NVML: {}", error), None)
+    }
+}
+
+/// Pure-Rust backend for queries the native monitor cannot answer
+/// (requires "fallback-sysinfo" feature)
+///
+/// Readings come from one shared `sysinfo::System`, so CPU usage is
+/// measured since the previous query and the first query reads 0%.
+#[cfg(feature = "fallback-sysinfo")]
+mod sysinfo_backend {
+    use std::collections::BTreeMap;
+    use std::sync::{Mutex, MutexGuard, OnceLock};
+    use sysinfo::{Components, Disks, Networks, ProcessesToUpdate, System};
+    
+    use super::{DiskCounters, InterfaceCounters, ProcessInfo, ProcessUsage, ThermalInfo};
+    
+    /// System handle, created on first use
+    static SYSTEM: OnceLock<Mutex<System>> = OnceLock::new();
+    
+    fn system() -> MutexGuard<'static, System> {
+        let system = SYSTEM.get_or_init(|| Mutex::new(System::new()));
+        system.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    pub(super) fn cpu_usage() -> f64 {
+        let mut system = system();
+        system.refresh_cpu_usage();
+        system.global_cpu_usage() as f64
+    }
+    
+    pub(super) fn cpu_per_core() -> Vec<f64> {
+        let mut system = system();
+        system.refresh_cpu_usage();
+        system.cpus().iter().map(|cpu| cpu.cpu_usage() as f64).collect()
+    }
+    
+    /// Get (available, total) memory bytes
+    pub(super) fn memory_usage() -> (f64, f64) {
+        let mut system = system();
+        system.refresh_memory();
+        (system.available_memory() as f64, system.total_memory() as f64)
+    }
+    
+    /// Get (used, total) swap bytes
+    pub(super) fn swap_usage() -> (f64, f64) {
+        let mut system = system();
+        system.refresh_memory();
+        (system.used_swap() as f64, system.total_swap() as f64)
+    }
+    
+    /// Get (available, total) bytes of the root filesystem, or of the
+    /// first disk without one
+    pub(super) fn disk_usage() -> Option<(f64, f64)> {
+        let disks = Disks::new_with_refreshed_list();
+        let disk = disks
+            .list()
+            .iter()
+            .find(|disk| disk.mount_point() == std::path::Path::new("/"))
+            .or_else(|| disks.list().first())?;
+        Some((disk.available_space() as f64, disk.total_space() as f64))
+    }
+    
+    pub(super) fn load_average() -> (f64, f64, f64) {
+        let load = System::load_average();
+        (load.one, load.five, load.fifteen)
+    }
+    
+    pub(super) fn uptime_seconds() -> u64 {
+        System::uptime()
+    }
+    
+    pub(super) fn process_usage() -> Option<ProcessUsage> {
+        let pid = sysinfo::get_current_pid().ok()?;
+        let mut system = system();
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        let process = system.process(pid)?;
+        Some(ProcessUsage {
+            cpu_usage_percent: process.cpu_usage() as f64,
+            rss_bytes: process.memory(),
+            virtual_memory_bytes: process.virtual_memory(),
+            open_fds: process.open_files().map_or(0, |files| files as u32),
+            thread_count: process.tasks().map_or(1, |tasks| tasks.len().max(1) as u32),
+        })
+    }
+    
+    pub(super) fn list_processes() -> Vec<ProcessInfo> {
+        let mut system = system();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        system
+            .processes()
+            .values()
+            .map(|process| ProcessInfo {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                cpu_usage_percent: process.cpu_usage() as f64,
+                memory_bytes: process.memory(),
+            })
+            .collect()
+    }
+    
+    /// Get byte counters per disk; sysinfo does not count operations
+    pub(super) fn disk_counters() -> Vec<DiskCounters> {
+        // A device mounted more than once is listed once per mount point
+        let mut devices = BTreeMap::new();
+        for disk in Disks::new_with_refreshed_list().list() {
+            let usage = disk.usage();
+            devices.insert(disk.name().to_string_lossy().into_owned(), usage);
+        }
+        devices
+            .into_iter()
+            .map(|(device, usage)| DiskCounters {
+                device,
+                read_bytes: usage.total_read_bytes,
+                write_bytes: usage.total_written_bytes,
+                reads: 0,
+                writes: 0,
+            })
+            .collect()
+    }
+    
+    /// Get interface counters, skipping loopback
+    pub(super) fn interface_counters() -> Vec<InterfaceCounters> {
+        let mut counters: Vec<InterfaceCounters> = Networks::new_with_refreshed_list()
+            .iter()
+            .filter(|(interface, _)| !matches!(interface.as_str(), "lo" | "lo0"))
+            .map(|(interface, data)| InterfaceCounters {
+                interface: interface.clone(),
+                received_bytes: data.total_received(),
+                sent_bytes: data.total_transmitted(),
+            })
+            .collect();
+        counters.sort_by(|a, b| a.interface.cmp(&b.interface));
+        counters
+    }
+    
+    /// Get the hottest CPU and GPU sensors; sysinfo does not read fans
+    pub(super) fn thermal_info() -> Option<ThermalInfo> {
+        let hottest = |components: &Components, names: &[&str]| -> Option<f64> {
+            components
+                .list()
+                .iter()
+                .filter(|component| {
+                    let label = component.label().to_lowercase();
+                    names.iter().any(|name| label.contains(name))
+                })
+                .filter_map(|component| component.temperature().map(f64::from))
+                .filter(|celsius| !celsius.is_nan())
+                .reduce(f64::max)
+        };
+        
+        let components = Components::new_with_refreshed_list();
+        let cpu = hottest(&components, &["cpu", "core", "package", "tctl", "k10temp"]);
+        let gpu = hottest(&components, &["gpu", "amdgpu", "nouveau", "nvidia"]);
+        (cpu.is_some() || gpu.is_some()).then(|| ThermalInfo {
+            cpu_temperature_celsius: cpu,
+            gpu_temperature_celsius: gpu,
+            fan_rpm: Vec::new(),
+        })
+    }
+}
+
+/// Number of fans `get_thermal_info()` first makes room for
+const INITIAL_FAN_CAPACITY: usize = 8;
+
+/// Read thermal sensors from sysfs below `class` (normally `/sys/class`)
+///
+/// The CPU temperature is the hottest CPU thermal zone; the GPU
+/// temperature comes from an amdgpu or nouveau hwmon device.
+#[cfg(target_os = "linux")]
+fn read_sysfs_thermal(class: &std::path::Path) -> Option<ThermalInfo> {
+    use std::fs;
+    
+    let read_number = |path: std::path::PathBuf| -> Option<f64> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+    let entries = |dir: &str| -> Vec<std::path::PathBuf> {
+        let mut paths: Vec<_> = fs::read_dir(class.join(dir))
+            .map(|entries| entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect())
+            .unwrap_or_default();
+        paths.sort();
+        paths
+    };
+    
+    let mut thermal = ThermalInfo::default();
+    for zone in entries("thermal") {
+        let kind = fs::read_to_string(zone.join("type")).unwrap_or_default();
+        let is_cpu = ["x86_pkg_temp", "cpu", "soc"].iter().any(|name| kind.to_lowercase().contains(name));
+        if let Some(millidegrees) = read_number(zone.join("temp")).filter(|_| is_cpu) {
+            let celsius = millidegrees / 1000.0;
+            thermal.cpu_temperature_celsius = Some(thermal.cpu_temperature_celsius.map_or(celsius, |hottest| hottest.max(celsius)));
+        }
+    }
+    
+    for device in entries("hwmon") {
+        let name = fs::read_to_string(device.join("name")).unwrap_or_default();
+        if matches!(name.trim(), "amdgpu" | "nouveau") {
+            if let Some(millidegrees) = read_number(device.join("temp1_input")) {
+                thermal.gpu_temperature_celsius = Some(millidegrees / 1000.0);
+            }
+        }
+        
+        // Sort by fan number so fan10 comes after fan2
+        let mut fans: Vec<(u32, std::path::PathBuf)> = fs::read_dir(&device)
+            .map(|files| files
+                .filter_map(|file| file.ok().map(|file| file.path()))
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_str()?;
+                    let number = name.strip_prefix("fan")?.strip_suffix("_input")?.parse().ok()?;
+                    Some((number, path))
+                })
+                .collect())
+            .unwrap_or_default();
+        fans.sort();
+        thermal.fan_rpm.extend(fans.into_iter().filter_map(|(_, path)| read_number(path)));
+    }
+    
+    (thermal != ThermalInfo::default()).then_some(thermal)
+}
+
+/// Read the cumulative I/O counters of every block device
+fn read_disk_counters() -> CoreBaseResult<Vec<DiskCounters>> {
+    // The C++ side returns the counters as a JSON array, or null without
+    // a disk I/O backend
+    if let Some(list) = unsafe { CbaString::from_raw(crate::cba_monitor_list_disk_io()) } {
+        return serde_json::from_str(list.to_str()?)
+            .map_err(|e| CoreBaseError::MonitorError(format!("Invalid disk I/O counters: {}", e), None));
+    }
+    
+    #[cfg(target_os = "linux")]
+    if let Some(counters) = read_diskstats(Path::new("/proc/diskstats"), Path::new("/sys/class/block")) {
+        return Ok(counters);
+    }
+    #[cfg(feature = "fallback-sysinfo")]
+    return Ok(sysinfo_backend::disk_counters());
+    #[cfg(not(feature = "fallback-sysinfo"))]
+    Err(CoreBaseError::MonitorError(
+        "No disk I/O backend available".to_string(),
+        None,
+    ))
+}
+
+/// Read block device counters from a `/proc/diskstats` file
+///
+/// Loop and RAM disks are skipped, and so are partitions, which `block`
+/// (normally `/sys/class/block`) marks with a `partition` file; their I/O
+/// is already counted on the whole disk. Sector counts are in 512-byte
+/// units whatever the device's sector size.
+#[cfg(target_os = "linux")]
+fn read_diskstats(path: &Path, block: &Path) -> Option<Vec<DiskCounters>> {
+    const SECTOR_BYTES: u64 = 512;
+    
+    let contents = fs::read_to_string(path).ok()?;
+    Some(contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let device = *fields.get(2)?;
+            let partition = block.join(device).join("partition").exists();
+            if device.starts_with("loop") || device.starts_with("ram") || partition {
+                return None;
+            }
+            let number = |index: usize| -> Option<u64> { fields.get(index)?.parse().ok() };
+            Some(DiskCounters {
+                device: device.to_string(),
+                reads: number(3)?,
+                read_bytes: number(5)? * SECTOR_BYTES,
+                writes: number(7)?,
+                write_bytes: number(9)? * SECTOR_BYTES,
+            })
+        })
+        .collect())
+}
+
+/// Read the cumulative byte counters of every network interface
+fn read_interface_counters() -> CoreBaseResult<Vec<InterfaceCounters>> {
+    // The C++ side returns the counters as a JSON array, or null without
+    // interface counters
+    if let Some(list) = unsafe { CbaString::from_raw(crate::cba_monitor_list_network_io()) } {
+        return serde_json::from_str(list.to_str()?)
+            .map_err(|e| CoreBaseError::MonitorError(format!("Invalid interface counters: {}", e), None));
+    }
+    
+    #[cfg(target_os = "linux")]
+    if let Some(counters) = read_net_dev(Path::new("/proc/net/dev")) {
+        return Ok(counters);
+    }
+    #[cfg(feature = "fallback-sysinfo")]
+    return Ok(sysinfo_backend::interface_counters());
+    #[cfg(not(feature = "fallback-sysinfo"))]
+    Err(CoreBaseError::MonitorError(
+        "No network counters available".to_string(),
+        None,
+    ))
+}
+
+/// Read interface counters from a `/proc/net/dev` file, skipping loopback
+#[cfg(target_os = "linux")]
+fn read_net_dev(path: &Path) -> Option<Vec<InterfaceCounters>> {
+    let contents = fs::read_to_string(path).ok()?;
+    Some(contents
+        .lines()
+        .filter_map(|line| {
+            let (interface, counters) = line.split_once(':')?;
+            let interface = interface.trim();
+            if interface == "lo" {
+                return None;
+            }
+            // Receive and transmit sections of eight counters each
+            let fields: Vec<u64> = counters.split_whitespace().filter_map(|field| field.parse().ok()).collect();
+            Some(InterfaceCounters {
+                interface: interface.to_string(),
+                received_bytes: *fields.first()?,
+                sent_bytes: *fields.get(8)?,
+            })
+        })
+        .collect())
+}
+
+/// Read the memory breakdown from a `/proc/meminfo` file
+///
+/// The commit fields are Linux's `Committed_AS` and `CommitLimit`.
+#[cfg(target_os = "linux")]
+fn read_meminfo(path: &Path) -> Option<MemoryBreakdown> {
+    let contents = fs::read_to_string(path).ok()?;
+    let fields: HashMap<&str, f64> = contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let kilobytes: f64 = value.trim().trim_end_matches("kB").trim().parse().ok()?;
+            Some((key, kilobytes * 1024.0))
+        })
+        .collect();
+    
+    let total_bytes = *fields.get("MemTotal")?;
+    let free_bytes = *fields.get("MemFree")?;
+    let cached_bytes = fields.get("Cached").copied().unwrap_or_default();
+    let buffers_bytes = fields.get("Buffers").copied().unwrap_or_default();
+    Some(MemoryBreakdown {
+        total_bytes,
+        free_bytes,
+        // Kernels before 3.14 lack MemAvailable
+        available_bytes: fields.get("MemAvailable").copied().unwrap_or(free_bytes + cached_bytes + buffers_bytes),
+        cached_bytes,
+        buffers_bytes,
+        shared_bytes: fields.get("Shmem").copied().unwrap_or_default(),
+        commit_charge_bytes: fields.get("Committed_AS").copied(),
+        commit_limit_bytes: fields.get("CommitLimit").copied(),
+    })
+}
+
+/// Read the CPU and memory limits and usage of a cgroup filesystem
+/// mounted at `root`, as seen from inside a container
+///
+/// Returns `None` if `root` holds neither a cgroup v2 hierarchy nor v1
+/// `cpu` or `memory` controllers.
+#[cfg(target_os = "linux")]
+fn read_cgroup(root: &Path) -> Option<CgroupSample> {
+    // v1 reports "no limit" as a value near i64::MAX
+    const UNLIMITED: f64 = (1u64 << 62) as f64;
+    
+    let read = |path: &str| fs::read_to_string(root.join(path)).ok();
+    let number = |path: &str| read(path)?.trim().parse::<f64>().ok();
+    let stat = |path: &str, key: &str| -> Option<f64> {
+        read(path)?.lines().find_map(|line| {
+            let (name, value) = line.split_once(' ')?;
+            if name == key { value.trim().parse().ok() } else { None }
+        })
+    };
+    let working_set = |usage: Option<f64>, inactive_file: Option<f64>| {
+        usage.map(|usage| (usage - inactive_file.unwrap_or_default()).max(0.0))
+    };
+    
+    if root.join("cgroup.controllers").exists() {
+        let cpu_max = read("cpu.max");
+        let mut quota = cpu_max.as_deref().unwrap_or_default().split_whitespace();
+        let cpu_limit_cores = match (quota.next(), quota.next()) {
+            (Some(quota), Some(period)) => quota.parse::<f64>().ok().zip(period.parse::<f64>().ok())
+                .map(|(quota, period)| quota / period),
+            _ => None,
+        };
+        return Some(CgroupSample {
+            version: 2,
+            cpu_limit_cores,
+            cpu_usage_usec: stat("cpu.stat", "usage_usec").map(|usec| usec as u64),
+            // "max" does not parse and means no limit
+            memory_limit_bytes: number("memory.max"),
+            memory_used_bytes: working_set(number("memory.current"), stat("memory.stat", "inactive_file")),
+        });
+    }
+    
+    if !root.join("cpu").exists() && !root.join("memory").exists() {
+        return None;
+    }
+    let cpu_limit_cores = match (number("cpu/cpu.cfs_quota_us"), number("cpu/cpu.cfs_period_us")) {
+        (Some(quota), Some(period)) if quota > 0.0 && period > 0.0 => Some(quota / period),
+        _ => None,
+    };
+    Some(CgroupSample {
+        version: 1,
+        cpu_limit_cores,
+        cpu_usage_usec: number("cpuacct/cpuacct.usage").map(|nanoseconds| (nanoseconds / 1000.0) as u64),
+        memory_limit_bytes: number("memory/memory.limit_in_bytes").filter(|limit| *limit < UNLIMITED),
+        memory_used_bytes: working_set(
+            number("memory/memory.usage_in_bytes"),
+            stat("memory/memory.stat", "total_inactive_file"),
+        ),
+    })
+}
+
+/// Monitor shared by `MonitorHandle` and `SharedSystemMonitor`
+#[derive(Debug)]
+struct BackgroundState {
+    monitor: Mutex<SystemMonitor>,
+    /// Kept apart from the monitor so reading it never waits on a sample
+    /// being taken
+    latest: RwLock<Option<Arc<SystemResources>>>,
+    subscribers: Mutex<Vec<Sender<SystemResources>>>,
+    paused: AtomicBool,
+}
+
+impl BackgroundState {
+    fn new(monitor: SystemMonitor) -> Self {
+        let latest = monitor.latest.clone().map(Arc::new);
+        BackgroundState {
+            monitor: Mutex::new(monitor),
+            latest: RwLock::new(latest),
+            subscribers: Mutex::new(Vec::new()),
+            paused: AtomicBool::new(false),
+        }
+    }
+    
+    /// Take a sample, make it the latest one and pass it to the subscribers
+    fn sample(&self) -> CoreBaseResult<Arc<SystemResources>> {
+        let resources = Arc::new(self.monitor()?.get_system_resources()?);
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send((*resources).clone()).is_ok());
+        }
+        if let Ok(mut latest) = self.latest.write() {
+            *latest = Some(Arc::clone(&resources));
+        }
+        Ok(resources)
+    }
+    
+    fn latest(&self) -> Option<Arc<SystemResources>> {
+        self.latest.read().ok().and_then(|latest| latest.clone())
+    }
+    
+    fn monitor(&self) -> CoreBaseResult<MutexGuard<'_, SystemMonitor>> {
+        self.monitor.lock().map_err(|_| monitor_lock_error())
+    }
+    
+    /// Summarize the health of the host, critical if the monitor is
+    /// unavailable
+    fn health(&self) -> HealthReport {
+        match self.monitor() {
+            Ok(monitor) => monitor.health(),
+            Err(e) => {
+                let mut report = HealthReport::healthy();
+                report.add(HealthStatus::Critical, e.to_string());
+                report
+            }
+        }
+    }
+}
+
+/// Handle to a `SystemMonitor` sampling on a background thread
+///
+/// Created with `SystemMonitor::start_background`. The first sample is
+/// taken immediately, then one every `update_interval`, unless the config
+/// aligns or jitters samples (see `MonitoringConfig::next_sample_delay`).
+/// Dropping the handle stops the thread.
+#[derive(Debug)]
+pub struct MonitorHandle {
+    shared: Arc<BackgroundState>,
+    stop: Option<Sender<()>>,
+    sampler: Option<JoinHandle<()>>,
+}
+
+impl MonitorHandle {
+    /// Get the most recent sample, if one was taken yet
+    pub fn latest(&self) -> Option<SystemResources> {
+        self.shared.latest().map(|latest| (*latest).clone())
+    }
+    
+    /// Receive every sample taken from now on
+    ///
+    /// A subscriber is removed once its receiver is dropped.
+    pub fn subscribe(&self) -> Receiver<SystemResources> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut subscribers) = self.shared.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+    
+    /// Summarize the health of the host, critical if the monitor is
+    /// unavailable
+    pub fn health(&self) -> HealthReport {
+        self.shared.health()
+    }
+    
+    /// Get the sampled history
+    pub fn history(&self) -> Vec<MonitoringDataPoint> {
+        self.monitor().map(|monitor| monitor.get_history_vec()).unwrap_or_default()
+    }
+    
+    /// Access the monitor, such as to read averages or change its config
+    ///
+    /// Sampling waits while the guard is held.
+    pub fn monitor(&self) -> CoreBaseResult<MutexGuard<'_, SystemMonitor>> {
+        self.shared.monitor()
+    }
+    
+    /// Suspend scheduled sampling, such as during a maintenance window
+    ///
+    /// The thread keeps running; `sample_once()` still works while paused.
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::SeqCst);
+    }
+    
+    /// Continue scheduled sampling from the next interval
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::SeqCst);
+    }
+    
+    /// Check whether scheduled sampling is paused
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::SeqCst)
+    }
+    
+    /// Take a sample now, outside the schedule
+    ///
+    /// The sample is recorded and passed to subscribers like a scheduled
+    /// one, whether or not sampling is paused.
+    pub fn sample_once(&self) -> CoreBaseResult<SystemResources> {
+        self.shared.sample().map(|resources| (*resources).clone())
+    }
+    
+    /// Stop sampling and wait for the thread to finish
+    pub fn stop(&mut self) {
+        self.stop.take();
+        if let Some(sampler) = self.sampler.take() {
+            let _ = sampler.join();
+        }
+    }
+}
+
+impl Drop for MonitorHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Sample until the handle is stopped or dropped
+fn sample_loop(shared: &BackgroundState, stopped: &Receiver<()>) {
+    let mut next = match shared.monitor.lock() {
+        Ok(monitor) => Instant::now() + monitor.config.first_sample_delay(SystemTime::now()),
+        Err(_) => return,
+    };
+    loop {
+        match stopped.recv_timeout(next.saturating_duration_since(Instant::now())) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => return,
+        }
+        
+        // Scheduling from the wake-up keeps sampling time from adding up
+        next = match shared.monitor.lock() {
+            Ok(monitor) => Instant::now() + monitor.config.next_sample_delay(SystemTime::now()),
+            Err(_) => return,
+        };
+        if !shared.paused.load(Ordering::SeqCst) {
+            let _ = shared.sample();
+        }
+    }
+}
+
+fn write_csv<W: Write>(
+    history: &VecDeque<MonitoringDataPoint>,
+    labels: &BTreeMap<String, String>,
+    writer: &mut W,
+) -> io::Result<()> {
+    let custom: Vec<&String> = history
+        .iter()
+        .flat_map(|point| point.custom.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    
+    let header: Vec<String> = std::iter::once("timestamp".to_string())
+        .chain(labels.keys().map(|key| csv_field(key)))
+        .chain(POINT_METRICS.map(str::to_string))
+        .chain(custom.iter().map(|name| csv_field(name)))
+        .collect();
+    writeln!(writer, "{}", header.join(","))?;
+    let label_fields: String = labels.values().map(|value| format!(",{}", csv_field(value))).collect();
+    
+    for point in history {
+        write!(writer, "{}{}", point.timestamp, label_fields)?;
+        for value in point.builtin_values() {
+            write!(writer, ",{}", value)?;
+        }
+        for name in &custom {
+            match point.custom.get(*name) {
+                Some(value) => write!(writer, ",{}", value)?,
+                None => write!(writer, ",")?,
+            }
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+fn write_json_lines<W: Write>(
+    history: &VecDeque<MonitoringDataPoint>,
+    config: &MonitoringConfig,
+    writer: &mut W,
+) -> io::Result<()> {
+    for point in history {
+        let labeled = LabeledPoint {
+            point,
+            labels: &config.labels,
+            tags: &config.metric_tags,
+        };
+        serde_json::to_writer(&mut *writer, &labeled)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Current time in seconds since the Unix epoch
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Keep the `n` processes using the most CPU, busiest first
+fn busiest_processes(mut processes: Vec<ProcessInfo>, n: usize) -> Vec<ProcessInfo> {
+    processes.sort_by(|a, b| b.cpu_usage_percent.total_cmp(&a.cpu_usage_percent));
+    processes.truncate(n);
+    processes
+}
+
+/// Keep the `n` processes using the most memory, largest first
+fn largest_processes(mut processes: Vec<ProcessInfo>, n: usize) -> Vec<ProcessInfo> {
+    processes.sort_by_key(|process| std::cmp::Reverse(process.memory_bytes));
+    processes.truncate(n);
+    processes
+}
+
+/// Temporary file a history is written to before replacing `path`
+///
+/// Unique per call, so concurrent saves never write the same file, and
+/// next to `path`, so the final rename stays on one filesystem.
+fn staging_path(path: &Path) -> PathBuf {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    unique_path(dir, &format!(".{}", name), ".tmp")
+}
+
+/// `SystemMonitor` shared between a sampling thread and readers
+///
+/// Clones refer to the same monitor. Metric sources and callbacks need
+/// not be `Sync`, so the monitor itself sits behind a mutex; the latest
+/// sample is kept in its own cell, so `latest()` never waits on a sample
+/// being taken and only clones an `Arc`. Unlike `MonitorHandle`, callers
+/// decide when samples are taken.
+///
+/// ```ignore
+/// let shared = SystemMonitor::new()?.into_shared();
+/// let sampler = shared.clone();
+/// std::thread::spawn(move || loop {
+///     let _ = sampler.sample();
+///     std::thread::sleep(Duration::from_secs(1));
+/// });
+/// let cpu = shared.latest().map(|resources| resources.cpu_usage_percent);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedSystemMonitor {
+    shared: Arc<BackgroundState>,
+}
+
+impl SharedSystemMonitor {
+    /// Share a monitor
+    pub fn new(monitor: SystemMonitor) -> Self {
+        SharedSystemMonitor {
+            shared: Arc::new(BackgroundState::new(monitor)),
+        }
+    }
+    
+    /// Take a sample and make it the latest one
+    pub fn sample(&self) -> CoreBaseResult<Arc<SystemResources>> {
+        self.shared.sample()
+    }
+    
+    /// Get the most recent sample, if one was taken yet
+    pub fn latest(&self) -> Option<Arc<SystemResources>> {
+        self.shared.latest()
+    }
+    
+    /// Summarize the health of the host, critical if the monitor is
+    /// unavailable
+    pub fn health(&self) -> HealthReport {
+        self.shared.health()
+    }
+    
+    /// Access the monitor, such as to read its history or change its config
+    ///
+    /// Sampling waits while the guard is held, and samples taken through
+    /// the guard do not update `latest()`.
+    pub fn monitor(&self) -> CoreBaseResult<MutexGuard<'_, SystemMonitor>> {
+        self.shared.monitor()
+    }
+}
+
+impl From<SystemMonitor> for SharedSystemMonitor {
+    fn from(monitor: SystemMonitor) -> Self {
+        Self::new(monitor)
+    }
+}
+
+fn monitor_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access system monitor".to_string(), None)
+}
+
+/// Async monitoring operations (requires "async" feature)
+#[cfg(feature = "async")]
+pub mod async_ops {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::mpsc;
+    use tokio::task::JoinHandle;
+    use tokio::time::{sleep_until, Instant};
+    
+    /// Async system monitor that continuously monitors system resources
+    ///
+    /// The monitor is shared with the sampling task, which takes one
+    /// sample every `update_interval` and adds it to the history.
+    #[derive(Debug)]
+    pub struct AsyncSystemMonitor {
+        monitor: Arc<Mutex<SystemMonitor>>,
+        paused: Arc<AtomicBool>,
+        task: Option<JoinHandle<()>>,
+    }
+    
+    impl AsyncSystemMonitor {
+        /// Create a new async system monitor
+        pub fn new(config: MonitoringConfig) -> CoreBaseResult<Self> {
+            Ok(AsyncSystemMonitor {
+                monitor: Arc::new(Mutex::new(SystemMonitor::with_config(config)?)),
+                paused: Arc::new(AtomicBool::new(false)),
+                task: None,
+            })
+        }
+        
+        /// Start continuous monitoring
+        ///
+        /// Samples are delivered through the returned receiver; the first
+        /// one immediately unless the config aligns or jitters samples (see
+        /// `MonitoringConfig::next_sample_delay`). Starting again stops the
+        /// previous task, whose
+        /// receiver then closes. Sampling stops by itself once the receiver
+        /// is dropped.
+        pub async fn start_monitoring(&mut self) -> CoreBaseResult<mpsc::UnboundedReceiver<SystemResources>> {
+            self.stop_monitoring();
+            let (sender, receiver) = mpsc::unbounded_channel();
+            
+            let config = self.monitor()?.config.clone();
+            let mut next = Instant::now() + config.first_sample_delay(SystemTime::now());
+            let monitor = Arc::clone(&self.monitor);
+            let paused = Arc::clone(&self.paused);
+            
+            self.task = Some(tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = sleep_until(next) => {}
+                        _ = sender.closed() => break,
+                    }
+                    next = Instant::now() + config.next_sample_delay(SystemTime::now());
+                    if paused.load(Ordering::SeqCst) {
+                        continue;
+                    }
+                    
+                    let sample = match monitor.lock() {
+                        Ok(mut monitor) => monitor.get_system_resources(),
+                        Err(_) => break,
+                    };
+                    if let Ok(resources) = sample {
+                        if sender.send(resources).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }));
+            
+            Ok(receiver)
+        }
+        
+        /// Stop monitoring
+        pub fn stop_monitoring(&mut self) {
+            if let Some(task) = self.task.take() {
+                task.abort();
+            }
+        }
+        
+        /// Skip samples until `resume_monitoring()` is called
+        pub fn pause_monitoring(&self) {
+            self.paused.store(true, Ordering::SeqCst);
+        }
+        
+        /// Take samples again after `pause_monitoring()`
+        pub fn resume_monitoring(&self) {
+            self.paused.store(false, Ordering::SeqCst);
+        }
+        
+        /// Check whether sampling is paused
+        pub fn is_paused(&self) -> bool {
+            self.paused.load(Ordering::SeqCst)
+        }
+        
+        /// Check whether the sampling task is running
+        pub fn is_running(&self) -> bool {
+            self.task.as_ref().is_some_and(|task| !task.is_finished())
+        }
+        
+        /// Get the underlying monitor
+        ///
+        /// Sampling waits while the guard is held. A new `update_interval`
+        /// takes effect on the next `start_monitoring()`.
+        pub fn monitor(&self) -> CoreBaseResult<MutexGuard<'_, SystemMonitor>> {
+            self.monitor.lock().map_err(|_| monitor_lock_error())
+        }
+    }
+    
+    impl Drop for AsyncSystemMonitor {
+        fn drop(&mut self) {
+            self.stop_monitoring();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_system_monitor_creation() {
+        let monitor = SystemMonitor::new();
+        assert!(monitor.is_ok());
+        assert!(monitor.unwrap().initialized);
+    }
+    
+    #[test]
+    fn test_system_resources_calculations() {
+        let resources = SystemResources {
+            cpu_usage_percent: 50.0,
+            cpu_per_core: vec![20.0, 100.0, 40.0, 40.0],
+            load_average: [3.5, 2.0, 1.0],
+            available_memory_bytes: 2_000_000_000.0, // 2GB
+            total_memory_bytes: 8_000_000_000.0,     // 8GB
+            memory: None,
+            swap_used_bytes: 1_000_000_000.0,        // 1GB
+            swap_total_bytes: 4_000_000_000.0,       // 4GB
+            available_disk_bytes: 100_000_000_000.0, // 100GB
+            total_disk_bytes: 500_000_000_000.0,     // 500GB
+            disk_io: Vec::new(),
+            network_usage_percent: 25.0,
+            network_io: vec![
+                NetworkIo {
+                    interface: "eth0".to_string(),
+                    received_bytes_per_sec: 3000.0,
+                    sent_bytes_per_sec: 1000.0,
+                },
+                NetworkIo {
+                    interface: "eth1".to_string(),
+                    received_bytes_per_sec: 500.0,
+                    sent_bytes_per_sec: 0.0,
+                },
+            ],
+            gpu_usage_percent: 75.0,
+            gpu_memory_used_bytes: 6e9,
+            gpu_memory_total_bytes: 24e9,
+            thermal: None,
+            container: None,
+            uptime_seconds: 86_400,
+            timestamp: 1234567890,
+            custom: BTreeMap::new(),
+        };
+        
+        assert_eq!(resources.memory_usage_percent(), 75.0); // (8-2)/8 * 100
+        assert_eq!(resources.disk_usage_percent(), 80.0);   // (500-100)/500 * 100
+        assert_eq!(resources.used_memory_bytes(), 6_000_000_000.0);
+        assert_eq!(resources.used_disk_bytes(), 400_000_000_000.0);
+        assert_eq!(resources.swap_usage_percent(), 25.0);
+        assert_eq!(resources.gpu_memory_usage_percent(), 25.0);
+        assert_eq!(resources.busiest_core(), Some((1, 100.0)));
+        assert_eq!(resources.core_imbalance(), 50.0);
+        assert_eq!(SystemResources::default().core_imbalance(), 0.0);
+        assert_eq!(MonitoringDataPoint::from(&resources).max_core_usage, 100.0);
+        assert_eq!(resources.network_received_bytes_per_sec(), 3500.0);
+        assert_eq!(MonitoringDataPoint::from(&resources).network_sent_bytes_per_sec, 1000.0);
+    }
+    
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(SystemResources::format_bytes(1024.0), "1.00 KB");
+        assert_eq!(SystemResources::format_bytes(1_048_576.0), "1.00 MB");
+        assert_eq!(SystemResources::format_bytes(1_073_741_824.0), "1.00 GB");
+        assert_eq!(SystemResources::format_bytes(500.0), "500.00 B");
+    }
+    
+    #[test]
+    fn test_monitoring_config() {
+        let config = MonitoringConfig {
+            update_interval: Duration::from_millis(500),
+            history_size: 50,
+            cpu_threshold: 90.0,
+            ..Default::default()
+        };
+        
+        let mut monitor = SystemMonitor::with_config(config.clone()).unwrap();
+        assert_eq!(monitor.get_config().update_interval, Duration::from_millis(500));
+        assert_eq!(monitor.get_config().history_size, 50);
+        assert_eq!(monitor.get_config().cpu_threshold, 90.0);
+    }
+    
+    #[test]
+    fn test_threshold_checking() {
+        let monitor = SystemMonitor::new().unwrap();
+        let resources = SystemResources {
+            cpu_usage_percent: 85.0, // Above default threshold of 80%
+            available_memory_bytes: 1_000_000_000.0,
+            total_memory_bytes: 8_000_000_000.0, // 87.5% usage, above 85% threshold
+            ..Default::default()
+        };
+        
+        let alerts = monitor.check_thresholds(&resources);
+        assert_eq!(alerts.len(), 2); // CPU and memory alerts
+        assert!(alerts[0].message.contains("CPU usage"));
+        assert!(alerts[1].message.contains("Memory usage"));
+        assert_eq!(alerts[0].metric, "cpu");
+        assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+        assert_eq!(alerts[0].count, 1);
+        
+        let config = MonitoringConfig::default().with_critical_threshold("memory", 87.0);
+        let alerts = SystemMonitor::with_config(config).unwrap().check_thresholds(&resources);
+        assert_eq!(alerts[1].severity, AlertSeverity::Critical);
+        assert_eq!(alerts[1].first_seen, resources.timestamp);
+        
+        let resources = SystemResources {
+            gpu_usage_percent: 20.0,
+            gpu_memory_used_bytes: 15e9,
+            gpu_memory_total_bytes: 16e9,
+            ..Default::default()
+        };
+        let alerts = monitor.check_thresholds(&resources);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].metric, "gpu_memory");
+        assert!(alerts[0].message.contains("GPU memory usage (93.8%)"));
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_process_usage() {
+        let monitor = SystemMonitor::new().unwrap();
+        let usage = monitor.get_process_usage().unwrap();
+        assert!(usage.rss_bytes > 0);
+        assert!(usage.virtual_memory_bytes >= usage.rss_bytes);
+        assert!(usage.thread_count >= 1);
+        
+        let uninitialized = SystemMonitor { initialized: false, ..SystemMonitor::default() };
+        assert!(uninitialized.get_process_usage().is_err());
+    }
+    
+    #[test]
+    fn test_top_processes() {
+        let process = |pid: u32, cpu_usage_percent: f64, memory_bytes: u64| ProcessInfo {
+            pid,
+            name: format!("proc{}", pid),
+            cpu_usage_percent,
+            memory_bytes,
+        };
+        let processes = vec![process(1, 5.0, 300), process(2, 80.0, 100), process(3, 20.0, 900), process(4, 0.0, 200)];
+        
+        let busiest: Vec<u32> = busiest_processes(processes.clone(), 2).iter().map(|p| p.pid).collect();
+        assert_eq!(busiest, [2, 3]);
+        let largest: Vec<u32> = largest_processes(processes.clone(), 3).iter().map(|p| p.pid).collect();
+        assert_eq!(largest, [3, 1, 4]);
+        assert!(largest_processes(processes.clone(), 0).is_empty());
+        assert_eq!(busiest_processes(processes, 10).len(), 4);
+        
+        let uninitialized = SystemMonitor { initialized: false, ..SystemMonitor::default() };
+        assert!(uninitialized.top_by_cpu(1).is_err());
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_process_list() {
+        let monitor = SystemMonitor::new().unwrap();
+        assert!(!monitor.list_processes().unwrap().is_empty());
+        
+        let busiest = monitor.top_by_cpu(2).unwrap();
+        assert!(busiest.len() <= 2);
+        assert!(busiest.windows(2).all(|pair| pair[0].cpu_usage_percent >= pair[1].cpu_usage_percent));
+    }
+    
+    #[test]
+    fn test_thermal_info() {
+        let monitor = SystemMonitor::new().unwrap();
+        // Hosts without sensors have nothing to report
+        if let Ok(thermal) = monitor.get_thermal_info() {
+            let celsius = [thermal.cpu_temperature_celsius, thermal.gpu_temperature_celsius];
+            assert!(celsius.into_iter().flatten().all(|celsius| (-50.0..150.0).contains(&celsius)));
+            assert!(thermal.fan_rpm.iter().all(|rpm| *rpm >= 0.0));
+        }
+        
+        let resources = SystemResources {
+            thermal: Some(ThermalInfo {
+                cpu_temperature_celsius: Some(92.5),
+                gpu_temperature_celsius: None,
+                fan_rpm: vec![1200.0, 0.0],
+            }),
+            ..Default::default()
+        };
+        let alerts = monitor.check_thresholds(&resources);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].message, "CPU temperature (92.5°C) exceeds threshold (85.0°C)");
+        
+        let config = MonitoringConfig {
+            enable_thermal_monitoring: false,
+            ..Default::default()
+        };
+        assert!(SystemMonitor::with_config(config).unwrap().check_thresholds(&resources).is_empty());
+    }
+    
+    #[test]
+    fn test_memory_breakdown() {
+        let monitor = SystemMonitor::new().unwrap();
+        // Hosts without a breakdown report an error instead
+        if let Ok(memory) = monitor.get_memory_breakdown() {
+            assert!(memory.available_bytes <= memory.total_bytes);
+            assert!(memory.reclaimable_bytes() >= 0.0);
+            let resources = SystemMonitor::new().unwrap().get_system_resources().unwrap();
+            assert!(resources.memory.is_some());
+        }
+        
+        let memory = MemoryBreakdown {
+            total_bytes: 8e9,
+            free_bytes: 1e9,
+            available_bytes: 4e9,
+            cached_bytes: 2.5e9,
+            buffers_bytes: 0.5e9,
+            ..Default::default()
+        };
+        assert_eq!(memory.used_bytes(), 4e9);
+        assert_eq!(memory.reclaimable_bytes(), 3e9);
+        assert_eq!(memory.commit_percent(), None);
+        
+        let memory = MemoryBreakdown {
+            commit_charge_bytes: Some(2e9),
+            commit_limit_bytes: Some(8e9),
+            ..memory
+        };
+        assert_eq!(memory.commit_percent(), Some(25.0));
+    }
+    
+    #[test]
+    fn test_disk_io() {
+        let mut monitor = SystemMonitor::with_config(MonitoringConfig {
+            disk_throughput_threshold: Some(1_000_000.0),
+            disk_iops_threshold: Some(1_000_000.0),
+            ..Default::default()
+        }).unwrap();
+        // Hosts without disk counters report an error instead
+        if let Ok(io) = monitor.get_disk_io() {
+            assert!(io.is_empty());
+            std::thread::sleep(Duration::from_millis(10));
+            let io = monitor.get_disk_io().unwrap();
+            assert!(io.iter().all(|disk| disk.read_bytes_per_sec >= 0.0 && disk.write_iops >= 0.0));
+        }
+        
+        let before = DiskCounters {
+            device: "sda".to_string(),
+            read_bytes: 1_000_000,
+            write_bytes: 500_000,
+            reads: 100,
+            writes: 50,
+        };
+        let after = DiskCounters {
+            read_bytes: 7_000_000,
+            write_bytes: 2_500_000,
+            reads: 500,
+            writes: 250,
+            ..before.clone()
+        };
+        assert_eq!(after.rates_since(&before, 2.0), DiskIo {
+            device: "sda".to_string(),
+            read_bytes_per_sec: 3_000_000.0,
+            write_bytes_per_sec: 1_000_000.0,
+            read_iops: 200.0,
+            write_iops: 100.0,
+        });
+        // Counters that went backwards after a reset read as idle
+        assert_eq!(before.rates_since(&after, 2.0).read_bytes_per_sec, 0.0);
+        
+        let resources = SystemResources {
+            disk_io: vec![DiskIo {
+                device: "sda".to_string(),
+                read_bytes_per_sec: 3_000_000.0,
+                write_bytes_per_sec: 1_000_000.0,
+                read_iops: 200.0,
+                write_iops: 100.0,
+            }],
+            ..Default::default()
+        };
+        let alerts = monitor.check_thresholds(&resources);
+        assert!(alerts.iter().any(|alert| alert.message.starts_with("Disk sda throughput (3.81 MB/s)")));
+        assert!(!alerts.iter().any(|alert| alert.message.contains("IOPS")));
+    }
+    
+    #[test]
+    fn test_network_io() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        // Hosts without interface counters report an error instead
+        if let Ok(io) = monitor.get_network_io() {
+            assert!(io.is_empty());
+            std::thread::sleep(Duration::from_millis(10));
+            let io = monitor.get_network_io().unwrap();
+            assert!(io.iter().all(|interface| interface.received_bytes_per_sec >= 0.0));
+        }
+        
+        let before = InterfaceCounters {
+            interface: "eth0".to_string(),
+            received_bytes: 1_000_000,
+            sent_bytes: 200_000,
+        };
+        let after = InterfaceCounters {
+            received_bytes: 5_000_000,
+            sent_bytes: 1_200_000,
+            ..before.clone()
+        };
+        assert_eq!(after.rates_since(&before, 4.0), NetworkIo {
+            interface: "eth0".to_string(),
+            received_bytes_per_sec: 1_000_000.0,
+            sent_bytes_per_sec: 250_000.0,
+        });
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_proc_net_dev() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dev");
+        std::fs::write(&path, "Inter-|   Receive                                                |  Transmit\n\
+             face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n\
+                lo:    1000      10    0    0    0     0          0         0     1000      10    0    0    0     0       0          0\n\
+              eth0: 5000000    4000    0    0    0     0          0         0  2000000    3000    0    0    0     0       0          0\n").unwrap();
+        
+        assert_eq!(read_net_dev(&path).unwrap(), vec![InterfaceCounters {
+            interface: "eth0".to_string(),
+            received_bytes: 5_000_000,
+            sent_bytes: 2_000_000,
+        }]);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_proc_diskstats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("diskstats");
+        std::fs::write(&path, "   7       0 loop0 10 0 20 0 0 0 0 0 0 0 0\n\
+            8       0 sda 1000 5 2048 30 500 2 4096 40 0 50 70\n\
+            8       1 sda1 900 5 2000 30 400 2 4000 40 0 50 70\n").unwrap();
+        let block = dir.path().join("block");
+        std::fs::create_dir_all(block.join("sda")).unwrap();
+        std::fs::create_dir_all(block.join("sda1")).unwrap();
+        std::fs::write(block.join("sda1").join("partition"), "1\n").unwrap();
+        
+        let counters = read_diskstats(&path, &block).unwrap();
+        assert_eq!(counters, vec![DiskCounters {
+            device: "sda".to_string(),
+            read_bytes: 2048 * 512,
+            write_bytes: 4096 * 512,
+            reads: 1000,
+            writes: 500,
+        }]);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_proc_meminfo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meminfo");
+        std::fs::write(&path, "MemTotal:       16000 kB\nMemFree:         1000 kB\nMemAvailable:    9000 kB\n\
+            Buffers:          500 kB\nCached:          7000 kB\nShmem:            300 kB\n\
+            CommitLimit:    20000 kB\nCommitted_AS:    5000 kB\n").unwrap();
+        
+        let memory = read_meminfo(&path).unwrap();
+        assert_eq!(memory.total_bytes, 16000.0 * 1024.0);
+        assert_eq!(memory.available_bytes, 9000.0 * 1024.0);
+        assert_eq!(memory.cached_bytes, 7000.0 * 1024.0);
+        assert_eq!(memory.shared_bytes, 300.0 * 1024.0);
+        assert_eq!(memory.commit_percent(), Some(25.0));
+        assert!(read_meminfo(&dir.path().join("missing")).is_none());
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cgroup_limits() {
+        let v2 = tempfile::tempdir().unwrap();
+        let write = |root: &Path, path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        assert!(read_cgroup(v2.path()).is_none());
+        
+        write(v2.path(), "cgroup.controllers", "cpu memory\n");
+        write(v2.path(), "cpu.max", "max 100000\n");
+        write(v2.path(), "memory.max", "max\n");
+        assert_eq!(read_cgroup(v2.path()).unwrap(), CgroupSample { version: 2, ..Default::default() });
+        
+        write(v2.path(), "cpu.max", "150000 100000\n");
+        write(v2.path(), "cpu.stat", "usage_usec 5000000\nuser_usec 4000000\n");
+        write(v2.path(), "memory.max", "2000000000\n");
+        write(v2.path(), "memory.current", "1200000000\n");
+        write(v2.path(), "memory.stat", "anon 900000000\ninactive_file 200000000\n");
+        assert_eq!(read_cgroup(v2.path()).unwrap(), CgroupSample {
+            version: 2,
+            cpu_limit_cores: Some(1.5),
+            cpu_usage_usec: Some(5_000_000),
+            memory_limit_bytes: Some(2e9),
+            memory_used_bytes: Some(1e9),
+        });
+        
+        let v1 = tempfile::tempdir().unwrap();
+        write(v1.path(), "cpu/cpu.cfs_quota_us", "-1\n");
+        write(v1.path(), "cpu/cpu.cfs_period_us", "100000\n");
+        write(v1.path(), "cpuacct/cpuacct.usage", "3000000000\n");
+        write(v1.path(), "memory/memory.limit_in_bytes", "9223372036854771712\n");
+        write(v1.path(), "memory/memory.usage_in_bytes", "500000000\n");
+        write(v1.path(), "memory/memory.stat", "cache 100\ntotal_inactive_file 100000000\n");
+        assert_eq!(read_cgroup(v1.path()).unwrap(), CgroupSample {
+            version: 1,
+            cpu_limit_cores: None,
+            cpu_usage_usec: Some(3_000_000),
+            memory_limit_bytes: None,
+            memory_used_bytes: Some(4e8),
+        });
+        
+        let host = SystemResources {
+            cpu_usage_percent: 10.0,
+            available_memory_bytes: 4e9,
+            total_memory_bytes: 8e9,
+            ..Default::default()
+        };
+        let mut monitor = SystemMonitor::new().unwrap();
+        monitor.cgroup_root = v2.path().to_path_buf();
+        let mut resources = host.clone();
+        monitor.apply_container_limits(&mut resources);
+        let container = resources.container.clone().unwrap();
+        assert_eq!(container.cpu_usage_percent, None);
+        assert_eq!(container.memory_usage_percent(), Some(50.0));
+        assert_eq!(container.host_total_memory_bytes, 8e9);
+        assert_eq!(container.host_cpu_usage_percent, 10.0);
+        assert_eq!(resources.total_memory_bytes, 2e9);
+        assert_eq!(resources.memory_usage_percent(), 50.0);
+        assert_eq!(resources.cpu_usage_percent, 10.0);
+        
+        std::thread::sleep(Duration::from_millis(20));
+        write(v2.path(), "cpu.stat", "usage_usec 5015000\n");
+        let mut resources = host;
+        monitor.apply_container_limits(&mut resources);
+        let usage = resources.container.unwrap().cpu_usage_percent.unwrap();
+        assert!(usage > 0.0 && usage <= 50.0, "{}", usage);
+        assert_eq!(resources.cpu_usage_percent, usage);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sysfs_thermal() {
+        let class = tempfile::tempdir().unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = class.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+        assert!(read_sysfs_thermal(class.path()).is_none());
+        
+        write("thermal/thermal_zone0/type", "acpitz\n");
+        write("thermal/thermal_zone0/temp", "99000\n");
+        write("thermal/thermal_zone1/type", "x86_pkg_temp\n");
+        write("thermal/thermal_zone1/temp", "61500\n");
+        write("hwmon/hwmon0/name", "amdgpu\n");
+        write("hwmon/hwmon0/temp1_input", "70000\n");
+        write("hwmon/hwmon1/name", "nct6775\n");
+        write("hwmon/hwmon1/fan1_input", "1200\n");
+        write("hwmon/hwmon1/fan2_input", "0\n");
+        write("hwmon/hwmon1/fan10_input", "900\n");
+        
+        assert_eq!(read_sysfs_thermal(class.path()), Some(ThermalInfo {
+            cpu_temperature_celsius: Some(61.5),
+            gpu_temperature_celsius: Some(70.0),
+            fan_rpm: vec![1200.0, 0.0, 900.0],
+        }));
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_load_uptime_and_swap() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        let (one, five, fifteen) = monitor.get_load_average().unwrap();
+        assert!(one >= 0.0 && five >= 0.0 && fifteen >= 0.0);
+        assert!(monitor.get_uptime().unwrap() > Duration::ZERO);
+        let sampled = monitor.get_system_resources().unwrap();
+        assert!((0.0..=100.0).contains(&sampled.swap_usage_percent()));
+    }
+    
+    #[test]
+    fn test_load_and_swap_thresholds() {
+        let resources = SystemResources {
+            cpu_per_core: vec![10.0; 4],
+            load_average: [9.0, 6.0, 3.0],
+            swap_used_bytes: 3e9,
+            swap_total_bytes: 4e9,
+            ..Default::default()
+        };
+        let mut monitor = SystemMonitor::new().unwrap();
+        monitor.record_sample(&resources);
+        
+        // Four cores at 2.0 each put the load threshold at 8.0
+        let alerts = monitor.check_thresholds(&resources);
+        assert!(alerts.iter().any(|alert| alert.message.starts_with("Load average (9.00)")));
+        assert!(alerts.iter().any(|alert| alert.message.starts_with("Swap usage (75.0%)")));
+        assert_eq!(monitor.get_average_usage().unwrap().load_average, 9.0);
+    }
+    
+    #[test]
+    fn test_list_gpus() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        // Hosts without a GPU backend report an error instead of a list
+        if let Ok(gpus) = monitor.list_gpus() {
+            assert!(gpus.iter().all(|gpu| gpu.memory_used_bytes <= gpu.memory_total_bytes));
+        }
+        let resources = monitor.get_system_resources().unwrap();
+        assert!(resources.gpu_memory_used_bytes <= resources.gpu_memory_total_bytes);
+        
+        // Same shape as the list the C++ side returns
+        let gpus: Vec<GpuInfo> = serde_json::from_str(r#"[
+            {"index": 0, "name": "GPU 0", "utilization_percent": 40.0, "memory_used_bytes": 8,
+             "memory_total_bytes": 16, "temperature_celsius": 64.0, "power_watts": 120.0},
+            {"index": 1, "name": "GPU 1", "utilization_percent": 0.0, "memory_used_bytes": 4,
+             "memory_total_bytes": 16, "temperature_celsius": null, "power_watts": null}
+        ]"#).unwrap();
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[1].name, "GPU 1");
+        assert_eq!(gpus[1].memory_usage_percent(), 25.0);
+        assert_eq!(gpus[0].temperature_celsius, Some(64.0));
+        assert_eq!(gpus[1].power_watts, None);
+        
+        let resources = SystemResources {
+            gpu_memory_used_bytes: gpus.iter().map(|gpu| gpu.memory_used_bytes as f64).sum(),
+            gpu_memory_total_bytes: gpus.iter().map(|gpu| gpu.memory_total_bytes as f64).sum(),
+            ..Default::default()
+        };
+        assert_eq!(resources.gpu_memory_usage_percent(), 37.5);
+    }
+    
+    #[test]
+    fn test_alert_callbacks() {
+        let mut config = MonitoringConfig::default();
+        config.metric_thresholds.insert("jobs.queue_depth".to_string(), 25.0);
+        config.metric_alert_policies.insert("jobs.queue_depth".to_string(), AlertPolicy {
+            rising_margin: 10.0,
+            falling_margin: 10.0,
+            sustain: Duration::from_millis(20),
+        });
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&alerts);
+        monitor.on_alert(move |alert| seen.lock().unwrap().push(alert.clone()));
+        
+        let mut sample = |depth: f64| {
+            let resources = SystemResources {
+                custom: BTreeMap::from([("jobs.queue_depth".to_string(), depth)]),
+                ..Default::default()
+            };
+            monitor.evaluate_alerts(&resources);
+            alerts.lock().unwrap().drain(..).map(|alert| (alert.metric, alert.state)).collect::<Vec<_>>()
+        };
+        
+        // Within the rising margin, then above it but not for long enough
+        assert!(sample(30.0).is_empty());
+        assert!(sample(40.0).is_empty());
+        std::thread::sleep(Duration::from_millis(25));
+        assert_eq!(sample(40.0), vec![("jobs.queue_depth".to_string(), AlertState::Triggered)]);
+        assert!(sample(50.0).is_empty());
         
-        let count = self.history.len() as f64;
-        let mut avg = MonitoringDataPoint {
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
-            cpu_usage: 0.0,
-            memory_usage: 0.0,
-            disk_usage: 0.0,
-            network_usage: 0.0,
-            gpu_usage: 0.0,
+        // Inside the falling margin the alert stays active
+        assert!(sample(20.0).is_empty());
+        assert_eq!(sample(10.0), vec![("jobs.queue_depth".to_string(), AlertState::Resolved)]);
+        assert!(sample(10.0).is_empty());
+    }
+    
+    #[test]
+    fn test_alert_severity() {
+        let mut config = MonitoringConfig::default().with_critical_threshold("jobs.queue_depth", 50.0);
+        config.metric_thresholds.insert("jobs.queue_depth".to_string(), 25.0);
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&alerts);
+        monitor.on_alert(move |alert| seen.lock().unwrap().push(alert.clone()));
+        
+        let mut timestamp = 1000;
+        let mut sample = |depth: f64| {
+            timestamp += 10;
+            let resources = SystemResources {
+                custom: BTreeMap::from([("jobs.queue_depth".to_string(), depth)]),
+                timestamp,
+                ..Default::default()
+            };
+            monitor.evaluate_alerts(&resources);
+            alerts.lock().unwrap()
+                .drain(..)
+                .map(|alert| (alert.state, alert.severity, alert.first_seen, alert.count))
+                .collect::<Vec<_>>()
+        };
+        
+        assert!(sample(10.0).is_empty());
+        assert_eq!(sample(30.0), [(AlertState::Triggered, AlertSeverity::Warning, 1020, 1)]);
+        assert!(sample(40.0).is_empty());
+        assert_eq!(sample(60.0), [(AlertState::Triggered, AlertSeverity::Critical, 1020, 3)]);
+        assert!(sample(70.0).is_empty());
+        assert_eq!(sample(40.0), [(AlertState::Triggered, AlertSeverity::Warning, 1020, 5)]);
+        assert_eq!(sample(10.0), [(AlertState::Resolved, AlertSeverity::Warning, 1020, 5)]);
+        assert_eq!(sample(60.0), [(AlertState::Triggered, AlertSeverity::Critical, 1080, 1)]);
+    }
+    
+    #[test]
+    fn test_alert_events() {
+        let mut config = MonitoringConfig {
+            anomaly_detection: Some(AnomalyDetection {
+                warmup: 2,
+                ..Default::default()
+            }),
+            ..Default::default()
         };
+        config.metric_thresholds.insert("jobs.queue_depth".to_string(), 25.0);
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        let events = monitor.alert_events();
+        let dropped = monitor.alert_events();
+        drop(dropped);
         
-        for point in &self.history {
-            avg.cpu_usage += point.cpu_usage;
-            avg.memory_usage += point.memory_usage;
-            avg.disk_usage += point.disk_usage;
-            avg.network_usage += point.network_usage;
-            avg.gpu_usage += point.gpu_usage;
+        for depth in [10.0, 10.0, 10.0, 30.0, 35.0, 10.0] {
+            monitor.evaluate_alerts(&SystemResources {
+                custom: BTreeMap::from([("jobs.queue_depth".to_string(), depth)]),
+                ..Default::default()
+            });
         }
+        assert_eq!(monitor.alert_subscribers.len(), 1);
+        
+        let events: Vec<AlertEvent> = events.try_iter().collect();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], AlertEvent::Raised(alert) if alert.value == 30.0));
+        assert!(matches!(&events[1], AlertEvent::Cleared(_)));
+        assert_eq!(events[1].alert().metric, "jobs.queue_depth");
+    }
+    
+    #[test]
+    fn test_export_labels() {
+        let config = MonitoringConfig::default()
+            .with_label("host", "web-1")
+            .with_label("region", "eu, west")
+            .with_metric_tag("cpu_usage", "unit", "percent")
+            .with_metric_tag("cpu_usage", "host", "web-1a");
+        assert_eq!(config.metric_labels("cpu_usage")["host"], "web-1a");
+        assert_eq!(config.metric_labels("disk_usage").len(), 2);
+        
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        monitor.get_system_resources().unwrap();
         
-        avg.cpu_usage /= count;
-        avg.memory_usage /= count;
-        avg.disk_usage /= count;
-        avg.network_usage /= count;
-        avg.gpu_usage /= count;
+        let mut csv = Vec::new();
+        monitor.export_history(ExportFormat::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("timestamp,host,region,cpu_usage,"));
+        assert!(lines[1].contains(",web-1,\"eu, west\","));
         
-        Some(avg)
+        let mut json_lines = Vec::new();
+        monitor.export_history(ExportFormat::JsonLines, &mut json_lines).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&json_lines).unwrap();
+        assert_eq!(line["labels"]["host"], "web-1");
+        assert_eq!(line["tags"]["cpu_usage"]["unit"], "percent");
+        assert!(line["cpu_usage"].is_number());
     }
     
-    /// Get peak usage over the history
-    pub fn get_peak_usage(&self) -> Option<MonitoringDataPoint> {
-        if self.history.is_empty() {
-            return None;
+    #[test]
+    fn test_sparkline_and_dashboard() {
+        assert_eq!(sparkline(&[0.0, 50.0, 100.0, 25.0], 10), "_=#-");
+        assert_eq!(sparkline(&[0.0, 0.0, 100.0, 100.0], 2), "_#");
+        assert_eq!(sparkline(&[5.0, 5.0], 10), "__");
+        assert_eq!(sparkline(&[], 10), "");
+        
+        let mut monitor = SystemMonitor::new().unwrap();
+        assert_eq!(monitor.render_sparkline(&Metric::Cpu, 10), "");
+        for depth in [10.0, 20.0, 30.0] {
+            monitor.record_sample(&SystemResources {
+                cpu_usage_percent: 10.0,
+                thermal: Some(ThermalInfo {
+                    cpu_temperature_celsius: Some(92.5),
+                    ..Default::default()
+                }),
+                custom: BTreeMap::from([("jobs.queue_depth".to_string(), depth)]),
+                ..Default::default()
+            });
         }
+        assert_eq!(monitor.render_sparkline(&Metric::Custom("jobs.queue_depth".to_string()), 10), "_=#");
+        assert_eq!(monitor.render_sparkline(&Metric::CpuTemperature, 10), "");
         
-        let mut peak = MonitoringDataPoint {
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
-            cpu_usage: 0.0,
-            memory_usage: 0.0,
-            disk_usage: 0.0,
-            network_usage: 0.0,
-            gpu_usage: 0.0,
+        let dashboard = monitor.render_dashboard();
+        assert!(dashboard.starts_with("System monitor: Critical (3 samples)\n"));
+        assert!(dashboard.contains("\ncpu                  10.0  ___\n"));
+        assert!(dashboard.contains("\njobs.queue_depth     30.0  _=#\n"));
+        assert!(dashboard.contains("  ! CPU temperature (92.5°C) exceeds threshold (85.0°C)\n"));
+    }
+    
+    #[test]
+    fn test_health() {
+        let mut config = MonitoringConfig::default();
+        let mut monitor = SystemMonitor::with_config(config.clone()).unwrap();
+        let report = monitor.health();
+        assert_eq!(report.status, HealthStatus::Critical);
+        assert_eq!(report.reasons, ["No sample taken yet"]);
+        
+        let sample = SystemResources {
+            cpu_usage_percent: 10.0,
+            ..Default::default()
         };
+        monitor.record_sample(&sample);
+        assert!(monitor.health().is_healthy());
         
-        for point in &self.history {
-            peak.cpu_usage = peak.cpu_usage.max(point.cpu_usage);
-            peak.memory_usage = peak.memory_usage.max(point.memory_usage);
-            peak.disk_usage = peak.disk_usage.max(point.disk_usage);
-            peak.network_usage = peak.network_usage.max(point.network_usage);
-            peak.gpu_usage = peak.gpu_usage.max(point.gpu_usage);
-        }
+        // Above threshold, but not yet for the sustain time
+        config.cpu_threshold = 5.0;
+        config.alert_policy.sustain = Duration::from_secs(3600);
+        monitor.set_config(config.clone());
+        monitor.record_sample(&sample);
+        let report = monitor.health();
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.status.http_status(), 200);
+        assert!(report.reasons[0].starts_with("CPU usage"));
+        
+        config.alert_policy.sustain = Duration::ZERO;
+        config.health_stale_after = Some(Duration::from_millis(5));
+        monitor.set_config(config);
+        monitor.record_sample(&sample);
+        assert_eq!(monitor.health().status, HealthStatus::Critical);
+        assert_eq!(monitor.health().status.http_status(), 503);
         
-        Some(peak)
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(monitor.health().reasons.iter().any(|reason| reason.starts_with("Last sample is")));
     }
     
-    /// Check if it's time to update based on the configured interval
-    pub fn should_update(&self) -> bool {
-        match self.last_update {
-            Some(last) => last.elapsed() >= self.config.update_interval,
-            None => true,
-        }
+    #[test]
+    fn test_watchdog() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&fired);
+        let metric = Metric::Custom("jobs.queue_depth".to_string());
+        monitor.watchdog(metric.clone(), 100.0, Duration::from_millis(20), move |resources| {
+            seen.lock().unwrap().push(resources.custom["jobs.queue_depth"]);
+        });
+        
+        let mut sample = |depth: f64| {
+            monitor.evaluate_watchdogs(&SystemResources {
+                custom: BTreeMap::from([("jobs.queue_depth".to_string(), depth)]),
+                ..Default::default()
+            });
+        };
+        
+        // A spike that drops back does not fire
+        sample(150.0);
+        std::thread::sleep(Duration::from_millis(25));
+        sample(50.0);
+        sample(150.0);
+        assert!(fired.lock().unwrap().is_empty());
+        
+        std::thread::sleep(Duration::from_millis(25));
+        sample(160.0);
+        sample(170.0);
+        assert_eq!(*fired.lock().unwrap(), vec![160.0]);
+        
+        assert_eq!(metric.name(), "jobs.queue_depth");
+        assert_eq!(Metric::CpuTemperature.value(&SystemResources::default()), None);
+        assert!(monitor.remove_watchdogs(&metric));
+        assert!(!monitor.remove_watchdogs(&metric));
     }
     
-    /// Add a data point to history
-    fn add_to_history(&mut self, resources: &SystemResources) {
-        let data_point = MonitoringDataPoint::from(resources);
+    #[test]
+    fn test_anomaly_detection() {
+        let mut monitor = SystemMonitor::with_config(MonitoringConfig {
+            anomaly_detection: Some(AnomalyDetection::default()),
+            ..Default::default()
+        }).unwrap();
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&alerts);
+        monitor.on_alert(move |alert| seen.lock().unwrap().push(alert.clone()));
         
-        self.history.push_back(data_point);
+        let mut sample = |latency: f64| {
+            let resources = SystemResources {
+                custom: BTreeMap::from([("http.latency_ms".to_string(), latency)]),
+                ..Default::default()
+            };
+            monitor.evaluate_alerts(&resources);
+            alerts.lock().unwrap().drain(..).collect::<Vec<_>>()
+        };
         
-        // Maintain history size limit
-        while self.history.len() > self.config.history_size {
-            self.history.pop_front();
+        // Jumps during warmup are not reported
+        assert!(sample(10.0).is_empty());
+        assert!(sample(40.0).is_empty());
+        for i in 0..30 {
+            assert!(sample(if i % 2 == 0 { 9.0 } else { 11.0 }).is_empty());
         }
+        
+        // Far below any threshold, but far from the baseline
+        let anomalies = sample(30.0);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].metric, "http.latency_ms");
+        assert_eq!(anomalies[0].state, AlertState::Anomaly);
+        assert!(anomalies[0].threshold < 30.0);
+        assert!(anomalies[0].message.starts_with("Metric http.latency_ms (30.0) outside its usual range"));
+        assert!(sample(10.0).is_empty());
     }
-}
-
-impl Default for SystemMonitor {
-    fn default() -> Self {
-        Self::new().unwrap_or(SystemMonitor {
-            initialized: false,
-            config: MonitoringConfig::default(),
-            history: VecDeque::new(),
-            last_update: None,
-        })
-    }
-}
-
-/// Async monitoring operations (requires "async" feature)
-#[cfg(feature = "async")]
-pub mod async_ops {
-    use super::*;
-    use tokio::time::{interval, Duration};
-    use tokio::sync::mpsc;
     
-    /// Async system monitor that continuously monitors system resources
-    pub struct AsyncSystemMonitor {
-        monitor: SystemMonitor,
-        sender: Option<mpsc::UnboundedSender<SystemResources>>,
+    #[test]
+    fn test_alert_logging() {
+        let logging = AlertLogging::default()
+            .with_metric_level("cpu_temperature", LogLevel::Critical)
+            .with_metric_level("disk", LogLevel::Error);
+        assert_eq!(logging.level_for("cpu_temperature"), LogLevel::Critical);
+        assert_eq!(logging.level_for("memory"), LogLevel::Warning);
+        
+        let config = MonitoringConfig {
+            cpu_threshold: 5.0,
+            alert_logging: Some(logging.clone()),
+            ..Default::default()
+        };
+        let round_trip: MonitoringConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_trip.alert_logging, Some(logging));
+        
+        // Alerts are tracked for logging even without callbacks
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        monitor.evaluate_alerts(&SystemResources {
+            cpu_usage_percent: 50.0,
+            ..Default::default()
+        });
+        assert!(monitor.alert_states["cpu"].active);
     }
     
-    impl AsyncSystemMonitor {
-        /// Create a new async system monitor
-        pub fn new(config: MonitoringConfig) -> CoreBaseResult<Self> {
-            Ok(AsyncSystemMonitor {
-                monitor: SystemMonitor::with_config(config)?,
-                sender: None,
-            })
+    #[test]
+    fn test_history_tiers() {
+        let mut monitor = SystemMonitor::with_config(MonitoringConfig {
+            history_size: 2,
+            history_tiers: vec![
+                HistoryTier::new(Duration::from_secs(10), 2),
+                HistoryTier::new(Duration::from_secs(100), 10),
+            ],
+            ..Default::default()
+        }).unwrap();
+        for timestamp in [0, 5, 12, 25, 31, 45] {
+            monitor.add_to_history(&SystemResources {
+                timestamp,
+                cpu_usage_percent: timestamp as f64,
+                ..Default::default()
+            });
         }
         
-        /// Start continuous monitoring
-        pub async fn start_monitoring(&mut self) -> CoreBaseResult<mpsc::UnboundedReceiver<SystemResources>> {
-            let (sender, receiver) = mpsc::unbounded_channel();
-            self.sender = Some(sender.clone());
-            
-            let update_interval = self.monitor.config.update_interval;
-            let mut interval_timer = interval(update_interval);
-            
-            tokio::spawn(async move {
-                loop {
-                    interval_timer.tick().await;
-                    
-                    // In a real implementation, we would need to safely access the monitor
-                    // For now, this is a placeholder for the async monitoring loop
-                    if sender.is_closed() {
-                        break;
-                    }
-                }
+        let raw: Vec<u64> = monitor.get_history().iter().map(|point| point.timestamp).collect();
+        assert_eq!(raw, vec![31, 45]);
+        let seconds = monitor.get_history_tier(0).unwrap();
+        let spans: Vec<(u64, u64, usize)> = seconds.iter().map(|a| (a.start, a.end, a.samples)).collect();
+        assert_eq!(spans, vec![(10, 20, 1), (20, 30, 1)]);
+        
+        let minutes = monitor.get_history_tier(1).unwrap();
+        assert_eq!(minutes.len(), 1);
+        assert_eq!((minutes[0].start, minutes[0].end, minutes[0].samples), (0, 100, 2));
+        let cpu = minutes[0].get("cpu_usage").unwrap();
+        assert_eq!((cpu.min, cpu.max, cpu.mean()), (0.0, 5.0, 2.5));
+        assert!(monitor.get_history_tier(2).is_none());
+        
+        monitor.clear_history();
+        assert!(monitor.get_history_tier(0).is_none());
+    }
+    
+    #[test]
+    fn test_history_retention() {
+        let mut monitor = SystemMonitor::with_config(MonitoringConfig {
+            history_retention: Some(Duration::from_secs(60)),
+            history_tiers: vec![HistoryTier::minutes(10)],
+            ..Default::default()
+        }).unwrap();
+        for timestamp in [1000, 1030, 1050, 1100] {
+            monitor.add_to_history(&SystemResources {
+                timestamp,
+                ..Default::default()
             });
-            
-            Ok(receiver)
         }
         
-        /// Stop monitoring
-        pub fn stop_monitoring(&mut self) {
-            self.sender = None;
+        let kept: Vec<u64> = monitor.get_history().iter().map(|point| point.timestamp).collect();
+        assert_eq!(kept, vec![1050, 1100]);
+        let minutes: Vec<u64> = monitor.get_history_tier(0).unwrap().iter().map(|a| a.start).collect();
+        assert_eq!(minutes, vec![960, 1020]);
+        
+        // The point count still applies
+        let mut config = monitor.get_config().clone();
+        config.history_size = 1;
+        monitor.set_config(config);
+        assert_eq!(monitor.get_history().len(), 1);
+    }
+    
+    #[test]
+    fn test_history_queries() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        assert_eq!(monitor.history_last(Duration::from_secs(60)).count(), 0);
+        for timestamp in [1000, 1030, 1050, 1100] {
+            monitor.add_to_history(&SystemResources {
+                timestamp,
+                ..Default::default()
+            });
         }
         
-        /// Get the underlying monitor
-        pub fn monitor(&mut self) -> &mut SystemMonitor {
-            &mut self.monitor
+        let timestamps = |points: Vec<&MonitoringDataPoint>| -> Vec<u64> {
+            points.into_iter().map(|point| point.timestamp).collect()
+        };
+        assert_eq!(timestamps(monitor.history_range(1030, 1050).collect()), [1030, 1050]);
+        assert_eq!(timestamps(monitor.history_range(1001, 1099).collect()), [1030, 1050]);
+        assert_eq!(monitor.history_range(1200, 1300).count(), 0);
+        assert_eq!(timestamps(monitor.history_last(Duration::from_secs(50)).collect()), [1050, 1100]);
+        assert_eq!(monitor.history_last(Duration::from_secs(3600)).count(), 4);
+    }
+    
+    #[test]
+    fn test_history_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        
+        let mut monitor = SystemMonitor::new().unwrap();
+        for i in 0..3u32 {
+            let resources = SystemResources {
+                timestamp: u64::from(i),
+                cpu_usage_percent: 10.0 / 2.7 + f64::from(i),
+                ..Default::default()
+            };
+            monitor.history.push_back(MonitoringDataPoint::from(&resources));
         }
+        monitor.save_history(&path).unwrap();
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(files, ["history.json"]);
+        
+        let mut restored = SystemMonitor::with_config(MonitoringConfig {
+            history_size: 2,
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(restored.load_history(&path).unwrap(), 2);
+        let saved = monitor.get_history_vec();
+        let timestamps: Vec<u64> = restored.get_history().iter().map(|point| point.timestamp).collect();
+        assert_eq!(timestamps, vec![saved[1].timestamp, saved[2].timestamp]);
+        assert_eq!(restored.get_history()[0].cpu_usage, saved[1].cpu_usage);
+        
+        assert!(matches!(
+            restored.load_history(dir.path().join("missing.json")),
+            Err(CoreBaseError::MonitorError(..))
+        ));
+        assert_eq!(restored.get_history().len(), 2);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
     
     #[test]
-    fn test_system_monitor_creation() {
-        let monitor = SystemMonitor::new();
-        assert!(monitor.is_ok());
-        assert!(monitor.unwrap().initialized);
+    fn test_export_history() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        monitor.get_system_resources().unwrap();
+        monitor.history.push_back(MonitoringDataPoint {
+            timestamp: 42,
+            cpu_usage: 12.5,
+            max_core_usage: 0.0,
+            load_average: 0.0,
+            memory_usage: 0.0,
+            swap_usage: 0.0,
+            disk_usage: 0.0,
+            network_usage: 0.0,
+            network_received_bytes_per_sec: 0.0,
+            network_sent_bytes_per_sec: 0.0,
+            gpu_usage: 0.0,
+            custom: BTreeMap::from([("queue,depth".to_string(), 3.0)]),
+        });
+        
+        let mut csv = Vec::new();
+        assert_eq!(monitor.export_history(ExportFormat::Csv, &mut csv).unwrap(), 2);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,cpu_usage,"));
+        assert!(lines[0].ends_with(",gpu_usage,\"queue,depth\""));
+        assert!(lines[1].ends_with(','));
+        assert_eq!(lines[2], "42,12.5,0,0,0,0,0,0,0,0,0,3");
+        
+        let mut json_lines = Vec::new();
+        monitor.export_history(ExportFormat::JsonLines, &mut json_lines).unwrap();
+        let points: Vec<MonitoringDataPoint> = String::from_utf8(json_lines).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].custom["queue,depth"], 3.0);
     }
     
     #[test]
-    fn test_system_resources_calculations() {
-        let resources = SystemResources {
+    fn test_resource_delta() {
+        let earlier = SystemResources {
+            cpu_usage_percent: 20.0,
+            available_memory_bytes: 6e9,
+            total_memory_bytes: 8e9,
+            timestamp: 1000,
+            custom: BTreeMap::from([("jobs.queue_depth".to_string(), 10.0), ("gone".to_string(), 1.0)]),
+            ..Default::default()
+        };
+        let later = SystemResources {
             cpu_usage_percent: 50.0,
-            available_memory_bytes: 2_000_000_000.0, // 2GB
-            total_memory_bytes: 8_000_000_000.0,     // 8GB
-            available_disk_bytes: 100_000_000_000.0, // 100GB
-            total_disk_bytes: 500_000_000_000.0,     // 500GB
-            network_usage_percent: 25.0,
-            gpu_usage_percent: 75.0,
-            timestamp: 1234567890,
+            available_memory_bytes: 5.4e9,
+            total_memory_bytes: 8e9,
+            timestamp: 1060,
+            custom: BTreeMap::from([("jobs.queue_depth".to_string(), 40.0)]),
+            ..Default::default()
         };
         
-        assert_eq!(resources.memory_usage_percent(), 75.0); // (8-2)/8 * 100
-        assert_eq!(resources.disk_usage_percent(), 80.0);   // (500-100)/500 * 100
-        assert_eq!(resources.used_memory_bytes(), 6_000_000_000.0);
-        assert_eq!(resources.used_disk_bytes(), 400_000_000_000.0);
+        let delta = later.delta(&earlier);
+        assert_eq!(delta.elapsed, Duration::from_secs(60));
+        let cpu = delta.get("cpu_usage").unwrap();
+        assert_eq!((cpu.before, cpu.after, cpu.change), (20.0, 50.0, 30.0));
+        assert_eq!(cpu.rate_per_sec, Some(0.5));
+        assert_eq!(delta.get("memory_used_bytes").unwrap().rate_per_sec, Some(1e7));
+        assert_eq!(delta.get("jobs.queue_depth").unwrap().change, 30.0);
+        assert!(delta.get("gone").is_none());
+        assert_eq!(delta.changed().count(), 4);
+        
+        let text = delta.to_string();
+        assert!(text.starts_with("changes over 60s\n"));
+        assert!(text.contains("\n  cpu_usage: 20.00 -> 50.00 (+30.00, +0.50/s)"));
+        assert!(!text.contains("disk_usage"));
+        
+        let same = earlier.delta(&earlier);
+        assert_eq!(same.get("cpu_usage").unwrap().rate_per_sec, None);
+        assert_eq!(same.to_string(), "changes over 0s: none");
     }
     
     #[test]
-    fn test_format_bytes() {
-        assert_eq!(SystemResources::format_bytes(1024.0), "1.00 KB");
-        assert_eq!(SystemResources::format_bytes(1_048_576.0), "1.00 MB");
-        assert_eq!(SystemResources::format_bytes(1_073_741_824.0), "1.00 GB");
-        assert_eq!(SystemResources::format_bytes(500.0), "500.00 B");
+    fn test_history_statistics() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        assert!(monitor.get_statistics().is_none());
+        for (timestamp, cpu_usage) in [(10, 40.0), (20, 10.0), (30, 20.0), (40, 30.0)] {
+            monitor.history.push_back(MonitoringDataPoint {
+                timestamp,
+                cpu_usage,
+                max_core_usage: 0.0,
+                load_average: 0.0,
+                memory_usage: 50.0,
+                swap_usage: 0.0,
+                disk_usage: 0.0,
+                network_usage: 0.0,
+                network_received_bytes_per_sec: 0.0,
+                network_sent_bytes_per_sec: 0.0,
+                gpu_usage: 0.0,
+                custom: BTreeMap::new(),
+            });
+        }
+        
+        let stats = monitor.get_statistics().unwrap();
+        assert_eq!((stats.start, stats.end, stats.samples), (10, 40, 4));
+        let cpu = stats.get("cpu_usage").unwrap();
+        assert_eq!((cpu.count, cpu.min, cpu.max, cpu.mean), (4, 10.0, 40.0, 25.0));
+        assert!((cpu.stddev - 125.0_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(cpu.p50, 25.0);
+        assert!((cpu.p95 - 38.5).abs() < 1e-9);
+        assert!((cpu.p99 - 39.7).abs() < 1e-9);
+        assert_eq!(stats.get("memory_usage").unwrap().stddev, 0.0);
+        assert!(stats.get("jobs.queue_depth").is_none());
+        
+        let recent = monitor.get_statistics_between(25, 40).unwrap();
+        assert_eq!(recent.samples, 2);
+        assert_eq!(recent.get("cpu_usage").unwrap().mean, 25.0);
+        assert!(monitor.get_statistics_between(50, 60).is_none());
+        
+        assert_eq!(monitor.get_average_usage().unwrap().cpu_usage, 25.0);
+        assert_eq!(monitor.get_peak_usage().unwrap().cpu_usage, 40.0);
     }
     
     #[test]
-    fn test_monitoring_config() {
+    fn test_default_system_monitor() {
+        let monitor = SystemMonitor::default();
+        assert_eq!(monitor.history.len(), 0);
+        assert!(monitor.last_update.is_none());
+    }
+    
+    /// Source reporting a fixed queue depth
+    #[derive(Debug)]
+    struct QueueDepth(f64);
+    
+    impl MetricSource for QueueDepth {
+        fn sample(&mut self) -> Vec<(String, f64)> {
+            self.0 += 10.0;
+            vec![("jobs.queue_depth".to_string(), self.0)]
+        }
+    }
+    
+    #[test]
+    fn test_custom_metrics() {
+        let mut config = MonitoringConfig::default();
+        config.metric_thresholds.insert("jobs.queue_depth".to_string(), 25.0);
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        monitor.add_metric_source(QueueDepth(0.0));
+        
+        let first = monitor.get_system_resources().unwrap();
+        assert_eq!(first.custom["jobs.queue_depth"], 10.0);
+        assert!(monitor.check_thresholds(&first).iter().all(|alert| alert.metric != "jobs.queue_depth"));
+        monitor.get_system_resources().unwrap();
+        let third = monitor.get_system_resources().unwrap();
+        let queue = monitor.check_thresholds(&third).into_iter().find(|alert| alert.metric == "jobs.queue_depth").unwrap();
+        assert!(queue.message.contains("jobs.queue_depth (30.0)"));
+        assert_eq!(queue.count, 1);
+        
+        let history: Vec<f64> = monitor.get_metric_history("jobs.queue_depth").into_iter().map(|(_, value)| value).collect();
+        assert_eq!(history, [10.0, 20.0, 30.0]);
+        assert_eq!(monitor.get_average_usage().unwrap().custom["jobs.queue_depth"], 20.0);
+        assert_eq!(monitor.get_peak_usage().unwrap().custom["jobs.queue_depth"], 30.0);
+    }
+    
+    #[test]
+    fn test_current() {
         let config = MonitoringConfig {
-            update_interval: Duration::from_millis(500),
-            history_size: 50,
-            cpu_threshold: 90.0,
+            update_interval: Duration::from_secs(3600),
             ..Default::default()
         };
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        monitor.add_metric_source(QueueDepth(0.0));
+        assert!(monitor.should_update());
         
-        let mut monitor = SystemMonitor::with_config(config.clone()).unwrap();
-        assert_eq!(monitor.get_config().update_interval, Duration::from_millis(500));
-        assert_eq!(monitor.get_config().history_size, 50);
-        assert_eq!(monitor.get_config().cpu_threshold, 90.0);
+        assert_eq!(monitor.current().unwrap().custom["jobs.queue_depth"], 10.0);
+        assert!(!monitor.should_update());
+        assert_eq!(monitor.current().unwrap().custom["jobs.queue_depth"], 10.0);
+        assert_eq!(monitor.get_history().len(), 1);
+        
+        let mut config = monitor.get_config().clone();
+        config.update_interval = Duration::ZERO;
+        monitor.set_config(config);
+        assert_eq!(monitor.current().unwrap().custom["jobs.queue_depth"], 20.0);
+        assert_eq!(monitor.get_history().len(), 2);
     }
     
     #[test]
-    fn test_threshold_checking() {
-        let monitor = SystemMonitor::new().unwrap();
-        let resources = SystemResources {
-            cpu_usage_percent: 85.0, // Above default threshold of 80%
-            available_memory_bytes: 1_000_000_000.0,
-            total_memory_bytes: 8_000_000_000.0, // 87.5% usage, above 85% threshold
+    fn test_sample_schedule() {
+        let mut config = MonitoringConfig {
+            update_interval: Duration::from_secs(60),
             ..Default::default()
         };
+        let now = UNIX_EPOCH + Duration::from_secs(125);
+        assert_eq!(config.next_sample_delay(now), Duration::from_secs(60));
+        assert_eq!(config.first_sample_delay(now), Duration::ZERO);
         
-        let alerts = monitor.check_thresholds(&resources);
-        assert_eq!(alerts.len(), 2); // CPU and memory alerts
-        assert!(alerts[0].contains("CPU usage"));
-        assert!(alerts[1].contains("Memory usage"));
+        config.align_samples = true;
+        assert_eq!(config.next_sample_delay(now), Duration::from_secs(55));
+        assert_eq!(config.next_sample_delay(UNIX_EPOCH + Duration::from_secs(120)), Duration::from_secs(60));
+        assert_eq!(config.first_sample_delay(now), Duration::from_secs(55));
+        
+        config.sample_jitter = Duration::from_secs(5);
+        let delays: Vec<Duration> = (0..20).map(|_| config.next_sample_delay(now)).collect();
+        assert!(delays.iter().all(|delay| (Duration::from_secs(55)..=Duration::from_secs(60)).contains(delay)));
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
     }
     
     #[test]
-    fn test_default_system_monitor() {
-        let monitor = SystemMonitor::default();
-        assert_eq!(monitor.history.len(), 0);
-        assert!(monitor.last_update.is_none());
+    fn test_shared_monitor() {
+        let mut monitor = SystemMonitor::new().unwrap();
+        monitor.add_metric_source(QueueDepth(0.0));
+        let shared = monitor.into_shared();
+        assert!(shared.latest().is_none());
+        
+        let sampler = shared.clone();
+        let sampling = std::thread::spawn(move || {
+            for _ in 0..5 {
+                sampler.sample().unwrap();
+            }
+        });
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let reader = shared.clone();
+                std::thread::spawn(move || {
+                    let mut seen = 0.0;
+                    while seen < 50.0 {
+                        if let Some(latest) = reader.latest() {
+                            assert!(latest.custom["jobs.queue_depth"] >= seen);
+                            seen = latest.custom["jobs.queue_depth"];
+                        }
+                        assert!(reader.monitor().unwrap().get_history().len() <= 5);
+                    }
+                })
+            })
+            .collect();
+        sampling.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        
+        let latest = shared.latest().unwrap();
+        assert_eq!(latest.custom["jobs.queue_depth"], 50.0);
+        assert!(Arc::ptr_eq(&latest, &shared.latest().unwrap()));
+        assert_eq!(shared.monitor().unwrap().get_history().len(), 5);
+        
+        shared.monitor().unwrap().clear_history();
+        assert!(shared.monitor().unwrap().get_history().is_empty());
+        
+        // Health follows samples recorded through the guard
+        let mut config = MonitoringConfig::default();
+        config.alert_policy.sustain = Duration::ZERO;
+        shared.monitor().unwrap().set_config(config);
+        let calm = SystemResources {
+            cpu_usage_percent: 10.0,
+            ..Default::default()
+        };
+        shared.monitor().unwrap().record_sample(&calm);
+        assert!(shared.health().is_healthy());
+        let busy = SystemResources {
+            cpu_usage_percent: 99.0,
+            ..Default::default()
+        };
+        shared.monitor().unwrap().record_sample(&busy);
+        assert_eq!(shared.health().status, HealthStatus::Critical);
+    }
+    
+    #[test]
+    fn test_background_monitor() {
+        let config = MonitoringConfig {
+            update_interval: Duration::from_millis(10),
+            history_size: 3,
+            ..Default::default()
+        };
+        let mut monitor = SystemMonitor::with_config(config).unwrap();
+        monitor.add_metric_source(QueueDepth(0.0));
+        let mut handle = monitor.into_background().unwrap();
+        
+        let samples = handle.subscribe();
+        let first = samples.recv_timeout(Duration::from_secs(5)).unwrap();
+        let second = samples.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(second.custom["jobs.queue_depth"] > first.custom["jobs.queue_depth"]);
+        assert!(handle.latest().is_some());
+        
+        while handle.history().len() < 3 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(handle.monitor().unwrap().get_history().len(), 3);
+        
+        handle.pause();
+        assert!(handle.is_paused());
+        std::thread::sleep(Duration::from_millis(30));
+        while samples.try_recv().is_ok() {}
+        let paused = handle.latest().unwrap().custom["jobs.queue_depth"];
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(samples.try_recv().is_err());
+        let once = handle.sample_once().unwrap().custom["jobs.queue_depth"];
+        assert!(once > paused);
+        assert_eq!(samples.try_recv().unwrap().custom["jobs.queue_depth"], once);
+        handle.resume();
+        assert!(samples.recv_timeout(Duration::from_secs(5)).is_ok());
+        
+        handle.stop();
+        let last = handle.latest().unwrap().custom["jobs.queue_depth"];
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(handle.latest().unwrap().custom["jobs.queue_depth"], last);
+        while samples.try_recv().is_ok() {}
+        assert!(samples.recv_timeout(Duration::from_millis(30)).is_err());
+    }
+    
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_system_monitor() {
+        use async_ops::AsyncSystemMonitor;
+        
+        let config = MonitoringConfig {
+            update_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let mut monitor = AsyncSystemMonitor::new(config).unwrap();
+        monitor.monitor().unwrap().add_metric_source(QueueDepth(0.0));
+        
+        let mut samples = monitor.start_monitoring().await.unwrap();
+        let first = samples.recv().await.unwrap();
+        let second = samples.recv().await.unwrap();
+        assert!(second.custom["jobs.queue_depth"] > first.custom["jobs.queue_depth"]);
+        assert!(monitor.is_running());
+        
+        monitor.pause_monitoring();
+        let recorded = monitor.monitor().unwrap().get_history().len();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        while samples.try_recv().is_ok() {}
+        let paused = monitor.monitor().unwrap().get_history().len();
+        assert!(paused <= recorded + 1);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(monitor.monitor().unwrap().get_history().len(), paused);
+        
+        monitor.resume_monitoring();
+        assert!(samples.recv().await.is_some());
+        
+        monitor.stop_monitoring();
+        while samples.recv().await.is_some() {}
+        assert!(!monitor.is_running());
+    }
+    
+    #[cfg(feature = "fallback-sysinfo")]
+    #[test]
+    fn test_sysinfo_backend() {
+        let (available, total) = sysinfo_backend::memory_usage();
+        assert!(total > 0.0 && available <= total);
+        assert!(!sysinfo_backend::cpu_per_core().is_empty());
+        assert!(sysinfo_backend::uptime_seconds() > 0);
+        
+        let usage = sysinfo_backend::process_usage().unwrap();
+        assert!(usage.rss_bytes > 0);
+        assert!(usage.thread_count >= 1);
+        let pid = std::process::id();
+        assert!(sysinfo_backend::list_processes().iter().any(|process| process.pid == pid));
     }
-}
\ No newline at end of file
+}
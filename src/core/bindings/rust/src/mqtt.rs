@@ -0,0 +1,550 @@
+//! MQTT client module for CoreBase Rust bindings
+//!
+//! This module provides a topic-based publish/subscribe API on top of the
+//! C++ NetworkManager's MQTT protocol support.
+
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::{to_c_string, CbaString};
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{NetworkConfig, NetworkMessage, NetworkProtocol};
+use crate::router::is_valid_filter;
+use crate::util::native_len;
+pub use crate::router::topic_matches;
+
+/// Header set on received messages with the delivery QoS
+pub const MQTT_QOS_HEADER: &str = "mqtt-qos";
+/// Header set to "true" on received messages that were retained by the broker
+pub const MQTT_RETAINED_HEADER: &str = "mqtt-retained";
+
+/// Initial payload buffer size for received messages
+const INITIAL_PAYLOAD_BUFFER: usize = 64 * 1024;
+/// Maximum topic length accepted from the broker
+const TOPIC_BUFFER_SIZE: usize = 1024;
+/// How long the dispatcher waits for a message before checking for shutdown
+const POLL_TIMEOUT_MS: c_int = 100;
+
+/// MQTT quality of service levels
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MqttQos {
+    #[default]
+    AtMostOnce = 0,
+    AtLeastOnce = 1,
+    ExactlyOnce = 2,
+}
+
+impl From<c_int> for MqttQos {
+    fn from(value: c_int) -> Self {
+        match value {
+            1 => MqttQos::AtLeastOnce,
+            2 => MqttQos::ExactlyOnce,
+            _ => MqttQos::AtMostOnce, // Default fallback
+        }
+    }
+}
+
+impl From<MqttQos> for c_int {
+    fn from(qos: MqttQos) -> Self {
+        qos as c_int
+    }
+}
+
+/// Message published by the broker if the client disconnects unexpectedly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastWill {
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub qos: MqttQos,
+    pub retain: bool,
+}
+
+impl LastWill {
+    /// Create a new last-will message
+    pub fn new(topic: &str, payload: &[u8]) -> Self {
+        LastWill {
+            topic: topic.to_string(),
+            payload: payload.to_vec(),
+            qos: MqttQos::default(),
+            retain: false,
+        }
+    }
+    
+    /// Set QoS
+    pub fn with_qos(mut self, qos: MqttQos) -> Self {
+        self.qos = qos;
+        self
+    }
+    
+    /// Ask the broker to retain the will message
+    pub fn retained(mut self) -> Self {
+        self.retain = true;
+        self
+    }
+}
+
+/// MQTT session options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttOptions {
+    pub client_id: String,
+    pub keep_alive: Duration,
+    pub clean_session: bool,
+    pub last_will: Option<LastWill>,
+}
+
+impl MqttOptions {
+    /// Create options for the given client ID
+    pub fn new(client_id: &str) -> Self {
+        MqttOptions {
+            client_id: client_id.to_string(),
+            keep_alive: Duration::from_secs(60),
+            clean_session: true,
+            last_will: None,
+        }
+    }
+    
+    /// Set keep-alive interval
+    pub fn with_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+    
+    /// Set whether the broker should discard previous session state
+    pub fn with_clean_session(mut self, clean_session: bool) -> Self {
+        self.clean_session = clean_session;
+        self
+    }
+    
+    /// Set last-will message
+    pub fn with_last_will(mut self, last_will: LastWill) -> Self {
+        self.last_will = Some(last_will);
+        self
+    }
+}
+
+/// Receiver for messages matching a subscription filter
+#[derive(Debug)]
+pub struct MqttSubscription {
+    filter: String,
+    receiver: Receiver<NetworkMessage>,
+}
+
+impl MqttSubscription {
+    /// Get the topic filter of this subscription
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+    
+    /// Block until the next message arrives
+    pub fn recv(&self) -> CoreBaseResult<NetworkMessage> {
+        self.receiver.recv().map_err(|_| CoreBaseError::NetworkError(
+            "MQTT client disconnected".to_string(),
+            None,
+        ))
+    }
+    
+    /// Wait up to `timeout` for the next message
+    pub fn recv_timeout(&self, timeout: Duration) -> CoreBaseResult<NetworkMessage> {
+        self.receiver.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => CoreBaseError::Timeout(
+                format!("No message on '{}' within {:?}", self.filter, timeout),
+                None,
+            ),
+            RecvTimeoutError::Disconnected => CoreBaseError::NetworkError(
+                "MQTT client disconnected".to_string(),
+                None,
+            ),
+        })
+    }
+    
+    /// Get the next message if one is already waiting
+    pub fn try_recv(&self) -> Option<NetworkMessage> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    filter: String,
+    sender: Sender<NetworkMessage>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    connection_id: String,
+    subscribers: Mutex<Vec<Subscriber>>,
+    running: AtomicBool,
+}
+
+/// MQTT client wrapping an MQTT connection of the C++ NetworkManager
+///
+/// Incoming messages are dispatched on a background thread to every
+/// subscription whose filter matches the message topic.
+#[derive(Debug)]
+pub struct MqttClient {
+    shared: Arc<Shared>,
+    dispatcher: Option<JoinHandle<()>>,
+}
+
+impl MqttClient {
+    /// Connect to an MQTT broker
+    pub fn connect(config: &NetworkConfig, options: MqttOptions) -> CoreBaseResult<Self> {
+        if config.protocol != NetworkProtocol::MQTT {
+            return Err(CoreBaseError::InvalidParameter(
+                format!("MqttClient requires the MQTT protocol, got {:?}", config.protocol),
+                None,
+            ));
+        }
+        
+        let c_host = to_c_string(&config.host)?;
+        let c_client_id = to_c_string(&options.client_id)?;
+        let c_username = config.username.as_deref().map(to_c_string).transpose()?;
+        let c_password = config.password.as_deref().map(to_c_string).transpose()?;
+        let c_will_topic = options.last_will.as_ref().map(|w| to_c_string(&w.topic)).transpose()?;
+        let will = options.last_will.as_ref();
+        
+        let connection_id = unsafe {
+            let connection_id_ptr = crate::cba_mqtt_connect(
+                c_host.as_ptr(),
+                config.port as c_int,
+                c_client_id.as_ptr(),
+                c_username.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                c_password.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                options.keep_alive.as_secs().min(u16::MAX as u64) as c_int,
+                options.clean_session as c_int,
+                c_will_topic.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                will.map_or(ptr::null(), |w| w.payload.as_ptr()),
+                will.map_or(0, |w| w.payload.len() as c_int),
+                will.map_or(0, |w| w.qos.into()),
+                will.is_some_and(|w| w.retain) as c_int,
+            );
+            
+            CbaString::from_raw(connection_id_ptr)
+                .ok_or_else(|| CoreBaseError::NetworkError(
+                    format!("Failed to connect to MQTT broker {}:{}", config.host, config.port),
+                    None,
+                ))?
+                .into_string()?
+        };
+        
+        let shared = Arc::new(Shared {
+            connection_id,
+            subscribers: Mutex::new(Vec::new()),
+            running: AtomicBool::new(true),
+        });
+        
+        let dispatcher_shared = Arc::clone(&shared);
+        let dispatcher = std::thread::Builder::new()
+            .name("cba-mqtt-dispatch".to_string())
+            .spawn(move || dispatch_loop(&dispatcher_shared))
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to start MQTT dispatcher: {}", e), None))?;
+        
+        Ok(MqttClient {
+            shared,
+            dispatcher: Some(dispatcher),
+        })
+    }
+    
+    /// Get the underlying connection ID
+    pub fn connection_id(&self) -> &str {
+        &self.shared.connection_id
+    }
+    
+    /// Publish a message to a topic
+    pub fn publish(&self, topic: &str, payload: &[u8], qos: MqttQos) -> CoreBaseResult<()> {
+        self.publish_message(topic, payload, qos, false)
+    }
+    
+    /// Publish a message the broker retains for future subscribers
+    pub fn publish_retained(&self, topic: &str, payload: &[u8], qos: MqttQos) -> CoreBaseResult<()> {
+        self.publish_message(topic, payload, qos, true)
+    }
+    
+    /// Clear the retained message of a topic
+    pub fn clear_retained(&self, topic: &str) -> CoreBaseResult<()> {
+        self.publish_message(topic, &[], MqttQos::AtLeastOnce, true)
+    }
+    
+    /// Publish a `NetworkMessage`, using its topic
+    pub fn publish_network_message(&self, message: &NetworkMessage, qos: MqttQos) -> CoreBaseResult<()> {
+        let topic = message.topic.as_deref().ok_or_else(|| CoreBaseError::InvalidParameter(
+            "MQTT messages require a topic".to_string(),
+            None,
+        ))?;
+        self.publish(topic, &message.data, qos)
+    }
+    
+    fn publish_message(&self, topic: &str, payload: &[u8], qos: MqttQos, retain: bool) -> CoreBaseResult<()> {
+        validate_topic(topic)?;
+        let c_connection_id = to_c_string(&self.shared.connection_id)?;
+        let c_topic = to_c_string(topic)?;
+        
+        unsafe {
+            let result = crate::cba_mqtt_publish(
+                c_connection_id.as_ptr(),
+                c_topic.as_ptr(),
+                payload.as_ptr(),
+                payload.len() as c_int,
+                qos.into(),
+                retain as c_int,
+            );
+            
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(CoreBaseError::NetworkError(
+                    format!("Failed to publish to topic '{}'", topic),
+                    None,
+                ).with_last_error())
+            }
+        }
+    }
+    
+    /// Subscribe to a topic filter (`+` and `#` wildcards are supported)
+    ///
+    /// Messages are delivered to the returned subscription until it is
+    /// dropped or the filter is unsubscribed.
+    pub fn subscribe(&self, filter: &str, qos: MqttQos) -> CoreBaseResult<MqttSubscription> {
+        validate_filter(filter)?;
+        let c_connection_id = to_c_string(&self.shared.connection_id)?;
+        let c_filter = to_c_string(filter)?;
+        
+        let (sender, receiver) = mpsc::channel();
+        
+        // Register before subscribing so retained messages are not missed
+        self.shared.subscribers.lock()
+            .map_err(|_| CoreBaseError::OperationFailed("Failed to access MQTT subscribers".to_string(), None))?
+            .push(Subscriber {
+                filter: filter.to_string(),
+                sender,
+            });
+        
+        let result = unsafe {
+            crate::cba_mqtt_subscribe(c_connection_id.as_ptr(), c_filter.as_ptr(), qos.into())
+        };
+        
+        if result != 0 {
+            self.remove_subscribers(filter);
+            return Err(CoreBaseError::NetworkError(
+                format!("Failed to subscribe to '{}'", filter),
+                None,
+            ).with_last_error());
+        }
+        
+        Ok(MqttSubscription {
+            filter: filter.to_string(),
+            receiver,
+        })
+    }
+    
+    /// Unsubscribe from a topic filter, closing all its subscriptions
+    pub fn unsubscribe(&self, filter: &str) -> CoreBaseResult<()> {
+        self.remove_subscribers(filter);
+        let c_connection_id = to_c_string(&self.shared.connection_id)?;
+        let c_filter = to_c_string(filter)?;
+        
+        unsafe {
+            let result = crate::cba_mqtt_unsubscribe(c_connection_id.as_ptr(), c_filter.as_ptr());
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(CoreBaseError::NetworkError(
+                    format!("Failed to unsubscribe from '{}'", filter),
+                    None,
+                ).with_last_error())
+            }
+        }
+    }
+    
+    fn remove_subscribers(&self, filter: &str) {
+        if let Ok(mut subscribers) = self.shared.subscribers.lock() {
+            subscribers.retain(|s| s.filter != filter);
+        }
+    }
+    
+    /// Disconnect cleanly; the broker will not publish the last will
+    pub fn disconnect(mut self) -> CoreBaseResult<()> {
+        self.shutdown()
+    }
+    
+    fn shutdown(&mut self) -> CoreBaseResult<()> {
+        let Some(dispatcher) = self.dispatcher.take() else {
+            return Ok(());
+        };
+        
+        self.shared.running.store(false, Ordering::SeqCst);
+        let _ = dispatcher.join();
+        
+        let c_connection_id = to_c_string(&self.shared.connection_id)?;
+        unsafe {
+            let result = crate::cba_mqtt_disconnect(c_connection_id.as_ptr());
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(CoreBaseError::NetworkError(
+                    "Failed to disconnect from MQTT broker".to_string(),
+                    None,
+                ).with_last_error())
+            }
+        }
+    }
+}
+
+impl Drop for MqttClient {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Receive messages from the broker and route them to subscribers
+fn dispatch_loop(shared: &Shared) {
+    let Ok(c_connection_id) = to_c_string(&shared.connection_id) else {
+        return;
+    };
+    let mut payload_buffer = vec![0u8; INITIAL_PAYLOAD_BUFFER];
+    let mut topic_buffer = vec![0u8; TOPIC_BUFFER_SIZE];
+    
+    while shared.running.load(Ordering::SeqCst) {
+        let mut payload_len: c_int = 0;
+        let mut qos: c_int = 0;
+        let mut retained: c_int = 0;
+        
+        let result = unsafe {
+            crate::cba_mqtt_poll_message(
+                c_connection_id.as_ptr(),
+                topic_buffer.as_mut_ptr() as *mut c_char,
+                topic_buffer.len() as c_int,
+                payload_buffer.as_mut_ptr(),
+                payload_buffer.len() as c_int,
+                &mut payload_len,
+                &mut qos,
+                &mut retained,
+                POLL_TIMEOUT_MS,
+            )
+        };
+        
+        // A negative length means the C++ side is in a bad state; it is
+        // handled like a failed poll
+        match (result, native_len(payload_len)) {
+            (0, Ok(payload_len)) => {
+                let topic_len = topic_buffer.iter().position(|&x| x == 0).unwrap_or(topic_buffer.len());
+                let topic = String::from_utf8_lossy(&topic_buffer[..topic_len]).into_owned();
+                let payload_len = payload_len.min(payload_buffer.len());
+                
+                let message = NetworkMessage::new_binary(payload_buffer[..payload_len].to_vec())
+                    .with_topic(&topic)
+                    .with_header(MQTT_QOS_HEADER, &qos.to_string())
+                    .with_header(MQTT_RETAINED_HEADER, if retained != 0 { "true" } else { "false" });
+                dispatch(shared, &message);
+            }
+            // Payload larger than the buffer; the message stays queued
+            (2, Ok(payload_len)) if payload_len > payload_buffer.len() => {
+                payload_buffer.resize(payload_len, 0);
+            }
+            (1, _) => {}
+            _ => {
+                // Avoid spinning if the connection has failed
+                std::thread::sleep(Duration::from_millis(POLL_TIMEOUT_MS as u64));
+            }
+        }
+    }
+}
+
+fn dispatch(shared: &Shared, message: &NetworkMessage) {
+    let Some(topic) = message.topic.as_deref() else {
+        return;
+    };
+    
+    if let Ok(mut subscribers) = shared.subscribers.lock() {
+        // Drop subscribers whose receiver has gone away
+        subscribers.retain(|subscriber| {
+            !topic_matches(&subscriber.filter, topic) || subscriber.sender.send(message.clone()).is_ok()
+        });
+    }
+}
+
+fn validate_topic(topic: &str) -> CoreBaseResult<()> {
+    if topic.is_empty() || topic.contains(['+', '#']) {
+        return Err(CoreBaseError::InvalidParameter(
+            format!("Invalid MQTT topic: '{}'", topic),
+            None,
+        ));
+    }
+    Ok(())
+}
+
+fn validate_filter(filter: &str) -> CoreBaseResult<()> {
+    if is_valid_filter(filter) {
+        Ok(())
+    } else {
+        Err(CoreBaseError::InvalidParameter(
+            format!("Invalid MQTT topic filter: '{}'", filter),
+            None,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_topic_matching() {
+        assert!(topic_matches("sensors/+/temperature", "sensors/kitchen/temperature"));
+        assert!(!topic_matches("sensors/+/temperature", "sensors/kitchen/humidity"));
+        assert!(topic_matches("sensors/#", "sensors"));
+        assert!(topic_matches("sensors/#", "sensors/kitchen/temperature"));
+        assert!(topic_matches("#", "anything/at/all"));
+        assert!(!topic_matches("#", "$SYS/broker/uptime"));
+        assert!(!topic_matches("sensors/+", "sensors/kitchen/temperature"));
+        
+        assert!(validate_filter("sensors/+/temperature").is_ok());
+        assert!(validate_filter("sensors/#/temperature").is_err());
+        assert!(validate_filter("sensors/kit+chen").is_err());
+        assert!(validate_topic("sensors/+").is_err());
+    }
+    
+    #[test]
+    fn test_options_builder() {
+        let options = MqttOptions::new("device-1")
+            .with_keep_alive(Duration::from_secs(30))
+            .with_last_will(LastWill::new("devices/device-1/status", b"offline").with_qos(MqttQos::AtLeastOnce).retained());
+        
+        assert_eq!(options.keep_alive, Duration::from_secs(30));
+        let will = options.last_will.unwrap();
+        assert_eq!(will.qos, MqttQos::AtLeastOnce);
+        assert!(will.retain);
+        assert_eq!(MqttQos::from(2), MqttQos::ExactlyOnce);
+        assert_eq!(c_int::from(MqttQos::AtLeastOnce), 1);
+        assert!(MqttClient::connect(&NetworkConfig::tcp("localhost", 1883), MqttOptions::new("c")).is_err());
+    }
+    
+    #[test]
+    #[ignore = "needs an MQTT broker at localhost:1883"]
+    fn test_publish_subscribe() {
+        let config = NetworkConfig {
+            protocol: NetworkProtocol::MQTT,
+            ..NetworkConfig::tcp("localhost", 1883)
+        };
+        let client = MqttClient::connect(&config, MqttOptions::new("test-client")).unwrap();
+        let subscription = client.subscribe("sensors/+/temperature", MqttQos::AtLeastOnce).unwrap();
+        assert!(client.publish("sensors/+/temperature", b"21.5", MqttQos::AtMostOnce).is_err());
+        
+        client.publish_retained("sensors/kitchen/temperature", b"21.5", MqttQos::AtLeastOnce).unwrap();
+        let message = subscription.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(message.topic.as_deref(), Some("sensors/kitchen/temperature"));
+        assert_eq!(message.as_bytes(), b"21.5");
+        assert_eq!(message.headers.get(MQTT_QOS_HEADER).map(String::as_str), Some("1"));
+        assert_eq!(message.headers.get(MQTT_RETAINED_HEADER).map(String::as_str), Some("true"));
+        
+        client.unsubscribe("sensors/+/temperature").unwrap();
+        assert!(subscription.recv_timeout(Duration::from_millis(10)).is_err());
+        client.disconnect().unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+//! Mockable network operations for CoreBase Rust bindings
+//!
+//! This module provides the `NetworkOps` trait, implemented by
+//! `NetworkManager` and by `MockNetworkManager`, so code that takes
+//! `&dyn NetworkOps` or a generic can be tested with scripted responses
+//! instead of the C++ NetworkManager.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{ConnectionState, NetworkConfig, NetworkConnection, NetworkManager, NetworkMessage};
+
+/// Connection-level operations of a network manager
+pub trait NetworkOps {
+    /// Open a connection and register it
+    fn create_connection(&self, config: NetworkConfig) -> CoreBaseResult<NetworkConnection>;
+    
+    /// Close a registered connection
+    fn close_connection(&self, connection_id: &str) -> CoreBaseResult<()>;
+    
+    /// Send a message on a registered connection
+    fn send_message(&self, connection_id: &str, message: &NetworkMessage) -> CoreBaseResult<()>;
+    
+    /// Receive a message from a registered connection
+    fn receive_message(&self, connection_id: &str) -> CoreBaseResult<NetworkMessage>;
+    
+    /// Send a message on every connection, returning the IDs it failed on
+    fn broadcast_message(&self, message: &NetworkMessage) -> CoreBaseResult<Vec<String>>;
+    
+    /// Get the number of registered connections
+    fn connection_count(&self) -> usize;
+}
+
+impl NetworkOps for NetworkManager {
+    fn create_connection(&self, config: NetworkConfig) -> CoreBaseResult<NetworkConnection> {
+        NetworkManager::create_connection(self, config)
+    }
+    
+    fn close_connection(&self, connection_id: &str) -> CoreBaseResult<()> {
+        NetworkManager::close_connection(self, connection_id)
+    }
+    
+    fn send_message(&self, connection_id: &str, message: &NetworkMessage) -> CoreBaseResult<()> {
+        NetworkManager::send_message(self, connection_id, message)
+    }
+    
+    fn receive_message(&self, connection_id: &str) -> CoreBaseResult<NetworkMessage> {
+        NetworkManager::receive_message(self, connection_id)
+    }
+    
+    fn broadcast_message(&self, message: &NetworkMessage) -> CoreBaseResult<Vec<String>> {
+        NetworkManager::broadcast_message(self, message)
+    }
+    
+    fn connection_count(&self) -> usize {
+        NetworkManager::connection_count(self)
+    }
+}
+
+/// Operation of a `MockNetworkManager` that can be scripted to fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockCall {
+    Connect,
+    Send,
+    Receive,
+}
+
+type Matcher = dyn Fn(&NetworkMessage) -> bool + Send + Sync;
+
+struct ScriptedReply {
+    matcher: Arc<Matcher>,
+    reply: NetworkMessage,
+}
+
+/// Both ends of a mock connection
+#[derive(Debug)]
+struct MockConnection {
+    /// End handed to the code under test
+    local: NetworkConnection,
+    /// End the mock answers from
+    remote: NetworkConnection,
+}
+
+/// `NetworkOps` implementation with scripted responses
+///
+/// Connections are in-memory pairs, so nothing reaches the C++ side and
+/// their protocol is `NetworkProtocol::InMemory`. Messages sent with
+/// `send_message()` are recorded and answered by the first matching
+/// `respond()` rule; the reply is received from the same connection,
+/// either with `receive_message()` or directly on the connection.
+/// `receive_message()` fails with `CoreBaseError::Timeout` instead of
+/// waiting when nothing was scripted.
+///
+/// ```ignore
+/// let mock = MockNetworkManager::new();
+/// mock.respond(|message| message.as_text().is_ok_and(|text| text == "ping"), NetworkMessage::new_text("pong"));
+/// assert_eq!(health_check(&mock)?, "pong");
+/// ```
+pub struct MockNetworkManager {
+    manager: NetworkManager,
+    connections: Mutex<HashMap<String, MockConnection>>,
+    replies: Mutex<Vec<ScriptedReply>>,
+    failures: Mutex<VecDeque<(MockCall, CoreBaseError)>>,
+    sent: Mutex<Vec<(String, NetworkMessage)>>,
+}
+
+impl MockNetworkManager {
+    /// Create a mock without scripted responses
+    pub fn new() -> Self {
+        MockNetworkManager {
+            manager: NetworkManager::default(),
+            connections: Mutex::new(HashMap::new()),
+            replies: Mutex::new(Vec::new()),
+            failures: Mutex::new(VecDeque::new()),
+            sent: Mutex::new(Vec::new()),
+        }
+    }
+    
+    /// Answer sent messages matching `matcher` with `reply`
+    ///
+    /// Rules are tried in the order they were added.
+    pub fn respond<F>(&self, matcher: F, reply: NetworkMessage)
+    where
+        F: Fn(&NetworkMessage) -> bool + Send + Sync + 'static,
+    {
+        if let Ok(mut replies) = self.replies.lock() {
+            replies.push(ScriptedReply {
+                matcher: Arc::new(matcher),
+                reply,
+            });
+        }
+    }
+    
+    /// Make the next call of an operation fail with `error`
+    ///
+    /// Failures are used up in the order they were added.
+    pub fn fail_next(&self, call: MockCall, error: CoreBaseError) {
+        if let Ok(mut failures) = self.failures.lock() {
+            failures.push_back((call, error));
+        }
+    }
+    
+    /// Deliver an unsolicited message to a connection
+    pub fn push_incoming(&self, connection_id: &str, message: &NetworkMessage) -> CoreBaseResult<()> {
+        let remote = self.connection(connection_id, |connection| connection.remote.clone())?;
+        remote.send(message)
+    }
+    
+    /// Get the messages sent to a connection with `send_message()` or
+    /// `broadcast_message()`
+    pub fn sent(&self, connection_id: &str) -> Vec<NetworkMessage> {
+        self.sent.lock()
+            .map(|sent| sent.iter()
+                .filter(|(id, _)| id == connection_id)
+                .map(|(_, message)| message.clone())
+                .collect())
+            .unwrap_or_default()
+    }
+    
+    fn scripted_failure(&self, call: MockCall) -> CoreBaseResult<()> {
+        let Ok(mut failures) = self.failures.lock() else {
+            return Ok(());
+        };
+        let index = failures.iter().position(|(failing, _)| *failing == call);
+        match index.and_then(|index| failures.remove(index)) {
+            Some((_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+    
+    fn connection<T>(&self, connection_id: &str, f: impl FnOnce(&MockConnection) -> T) -> CoreBaseResult<T> {
+        let connections = self.connections.lock().map_err(|_| mock_lock_error())?;
+        connections.get(connection_id).map(f).ok_or_else(|| CoreBaseError::ResourceNotFound(
+            format!("Connection not found: {}", connection_id),
+            None,
+        ))
+    }
+}
+
+impl NetworkOps for MockNetworkManager {
+    fn create_connection(&self, config: NetworkConfig) -> CoreBaseResult<NetworkConnection> {
+        self.scripted_failure(MockCall::Connect)?;
+        let (local, remote) = self.manager.create_pair(config)?;
+        self.connections.lock().map_err(|_| mock_lock_error())?
+            .insert(local.id.clone(), MockConnection { local: local.clone(), remote });
+        Ok(local)
+    }
+    
+    fn close_connection(&self, connection_id: &str) -> CoreBaseResult<()> {
+        let connection = self.connections.lock().map_err(|_| mock_lock_error())?
+            .remove(connection_id)
+            .ok_or_else(|| CoreBaseError::ResourceNotFound(format!("Connection not found: {}", connection_id), None))?;
+        let _ = self.manager.close_connection(&connection.remote.id);
+        self.manager.close_connection(&connection.local.id)
+    }
+    
+    fn send_message(&self, connection_id: &str, message: &NetworkMessage) -> CoreBaseResult<()> {
+        let (local, remote) = self.connection(connection_id, |connection| {
+            (connection.local.clone(), connection.remote.clone())
+        })?;
+        self.scripted_failure(MockCall::Send)?;
+        if local.current_state() != ConnectionState::Connected {
+            return Err(CoreBaseError::NetworkError(format!("Connection {} is closed", connection_id), None));
+        }
+        
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.push((connection_id.to_string(), message.clone()));
+        }
+        let reply = self.replies.lock().ok().and_then(|replies| {
+            replies.iter().find(|rule| (rule.matcher)(message)).map(|rule| rule.reply.clone())
+        });
+        match reply {
+            Some(reply) => remote.send(&reply),
+            None => Ok(()),
+        }
+    }
+    
+    fn receive_message(&self, connection_id: &str) -> CoreBaseResult<NetworkMessage> {
+        let local = self.connection(connection_id, |connection| connection.local.clone())?;
+        self.scripted_failure(MockCall::Receive)?;
+        local.try_receive()?.ok_or_else(|| CoreBaseError::Timeout(
+            format!("No scripted message for connection {}", connection_id),
+            None,
+        ))
+    }
+    
+    fn broadcast_message(&self, message: &NetworkMessage) -> CoreBaseResult<Vec<String>> {
+        let connection_ids: Vec<String> = self.connections.lock().map_err(|_| mock_lock_error())?
+            .keys()
+            .cloned()
+            .collect();
+        Ok(connection_ids
+            .into_iter()
+            .filter(|id| self.send_message(id, message).is_err())
+            .collect())
+    }
+    
+    fn connection_count(&self) -> usize {
+        self.connections.lock().map(|connections| connections.len()).unwrap_or(0)
+    }
+}
+
+impl Default for MockNetworkManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MockNetworkManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockNetworkManager")
+            .field("connections", &self.connection_count())
+            .field("replies", &self.replies.lock().map(|replies| replies.len()).unwrap_or(0))
+            .field("failures", &self.failures)
+            .finish()
+    }
+}
+
+fn mock_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access mock network manager".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Application code under test
+    fn health_check(network: &dyn NetworkOps) -> CoreBaseResult<String> {
+        let connection = network.create_connection(NetworkConfig::tcp("service", 9000))?;
+        network.send_message(&connection.id, &NetworkMessage::new_text("ping"))?;
+        let reply = network.receive_message(&connection.id)?;
+        network.close_connection(&connection.id)?;
+        reply.as_text()
+    }
+    
+    #[test]
+    fn test_mock_network_manager() {
+        let mock = MockNetworkManager::new();
+        mock.respond(|message| message.as_text().is_ok_and(|text| text == "ping"), NetworkMessage::new_text("pong"));
+        assert_eq!(health_check(&mock).unwrap(), "pong");
+        assert_eq!(mock.connection_count(), 0);
+        
+        mock.fail_next(MockCall::Connect, CoreBaseError::NetworkError("refused".to_string(), None));
+        assert!(matches!(health_check(&mock), Err(CoreBaseError::NetworkError(..))));
+        
+        let connection = mock.create_connection(NetworkConfig::default()).unwrap();
+        assert!(matches!(mock.receive_message(&connection.id), Err(CoreBaseError::Timeout(..))));
+        mock.push_incoming(&connection.id, &NetworkMessage::new_text("event")).unwrap();
+        assert_eq!(connection.receive().unwrap().as_text().unwrap(), "event");
+        
+        mock.fail_next(MockCall::Send, CoreBaseError::Timeout("slow".to_string(), None));
+        assert_eq!(mock.broadcast_message(&NetworkMessage::new_text("hello")).unwrap(), vec![connection.id.clone()]);
+        mock.send_message(&connection.id, &NetworkMessage::new_text("hello")).unwrap();
+        assert_eq!(mock.sent(&connection.id).len(), 1);
+        assert!(mock.send_message("missing", &NetworkMessage::new_text("hello")).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+//! Connection pooling for CoreBase Rust bindings
+//!
+//! This module provides `ConnectionPool`, which keeps connections to a single
+//! endpoint open between requests instead of creating a fresh connection
+//! for every request.
+
+use std::collections::VecDeque;
+use std::ops::Deref;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{ConnectionState, NetworkConfig, NetworkConnection, NetworkManager};
+
+/// Connection pool options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolOptions {
+    /// Connections kept open even when idle
+    pub min: usize,
+    /// Maximum number of open connections
+    pub max: usize,
+    /// Idle connections above `min` are closed after this long
+    pub idle_timeout: Duration,
+    /// How long `checkout()` waits for a free connection
+    pub checkout_timeout: Duration,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        PoolOptions {
+            min: 0,
+            max: 10,
+            idle_timeout: Duration::from_secs(300),
+            checkout_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Snapshot of pool statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolMetrics {
+    /// Open connections, idle or checked out
+    pub total: usize,
+    pub idle: usize,
+    pub in_use: usize,
+    pub checkouts: u64,
+    pub created: u64,
+    pub closed: u64,
+    /// Idle connections discarded because they failed a health check
+    pub health_check_failures: u64,
+    /// Checkouts that timed out waiting for a connection
+    pub timeouts: u64,
+    /// Total time spent waiting in `checkout()`
+    pub wait_time: Duration,
+}
+
+#[derive(Debug)]
+struct IdleConnection {
+    connection: NetworkConnection,
+    since: Instant,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    idle: VecDeque<IdleConnection>,
+    total: usize,
+    closed: bool,
+    metrics: PoolMetrics,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    manager: NetworkManager,
+    config: NetworkConfig,
+    options: PoolOptions,
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+/// Pool of connections to one endpoint
+///
+/// Connections are checked out with `checkout()` and return to the pool
+/// when the `PooledConnection` guard is dropped. Idle connections are health
+/// checked before being handed out.
+#[derive(Debug)]
+pub struct ConnectionPool {
+    inner: Arc<PoolInner>,
+}
+
+impl ConnectionPool {
+    /// Create a new pool, opening `options.min` connections up front
+    pub fn new(config: NetworkConfig, options: PoolOptions) -> CoreBaseResult<Self> {
+        Self::with_manager(NetworkManager::new()?, config, options)
+    }
+    
+    /// Create a new pool whose connections are opened through `manager`
+    ///
+    /// Use this to pool connections over transports registered with the
+    /// manager.
+    pub fn with_manager(manager: NetworkManager, config: NetworkConfig, options: PoolOptions) -> CoreBaseResult<Self> {
+        if options.max == 0 || options.min > options.max {
+            return Err(CoreBaseError::InvalidParameter(
+                format!("Invalid pool size: min {} max {}", options.min, options.max),
+                None,
+            ));
+        }
+        
+        let pool = ConnectionPool {
+            inner: Arc::new(PoolInner {
+                manager,
+                config,
+                options,
+                state: Mutex::new(PoolState::default()),
+                available: Condvar::new(),
+            }),
+        };
+        
+        for _ in 0..pool.inner.options.min {
+            let connection = pool.inner.open()?;
+            let mut state = pool.inner.lock()?;
+            state.total += 1;
+            state.idle.push_back(IdleConnection {
+                connection,
+                since: Instant::now(),
+            });
+        }
+        
+        Ok(pool)
+    }
+    
+    /// Get the configuration used for new connections
+    pub fn config(&self) -> &NetworkConfig {
+        &self.inner.config
+    }
+    
+    /// Get the pool options
+    pub fn options(&self) -> &PoolOptions {
+        &self.inner.options
+    }
+    
+    /// Check out a connection, waiting up to `checkout_timeout` for one
+    pub fn checkout(&self) -> CoreBaseResult<PooledConnection> {
+        let started = Instant::now();
+        let deadline = started + self.inner.options.checkout_timeout;
+        let mut state = self.inner.lock()?;
+        
+        loop {
+            if state.closed {
+                return Err(CoreBaseError::OperationFailed("Connection pool is closed".to_string(), None));
+            }
+            
+            self.inner.evict_expired(&mut state);
+            
+            // Reuse the most recently used idle connection that is still healthy
+            while let Some(idle) = state.idle.pop_back() {
+                if is_healthy(&idle.connection) {
+                    state.metrics.checkouts += 1;
+                    state.metrics.wait_time += started.elapsed();
+                    return Ok(self.guard(idle.connection));
+                }
+                state.metrics.health_check_failures += 1;
+                self.inner.close(&mut state, &idle.connection);
+            }
+            
+            if state.total < self.inner.options.max {
+                // Reserve the slot, then connect without holding the lock
+                state.total += 1;
+                drop(state);
+                
+                let opened = self.inner.open();
+                let mut relocked = self.inner.lock()?;
+                return match opened {
+                    Ok(connection) => {
+                        relocked.metrics.checkouts += 1;
+                        relocked.metrics.wait_time += started.elapsed();
+                        Ok(self.guard(connection))
+                    }
+                    Err(e) => {
+                        relocked.total -= 1;
+                        self.inner.available.notify_one();
+                        Err(e)
+                    }
+                };
+            }
+            
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                state.metrics.timeouts += 1;
+                state.metrics.wait_time += started.elapsed();
+                return Err(CoreBaseError::Timeout(
+                    format!("No pooled connection available within {:?}", self.inner.options.checkout_timeout),
+                    None,
+                ));
+            }
+            
+            state = self.inner.available
+                .wait_timeout(state, remaining)
+                .map_err(|_| lock_error())?
+                .0;
+        }
+    }
+    
+    /// Close idle connections that exceeded `idle_timeout`, keeping `min` open
+    pub fn evict_idle(&self) -> CoreBaseResult<()> {
+        let mut state = self.inner.lock()?;
+        self.inner.evict_expired(&mut state);
+        Ok(())
+    }
+    
+    /// Get current pool statistics
+    pub fn metrics(&self) -> PoolMetrics {
+        self.inner.lock()
+            .map(|state| PoolMetrics {
+                total: state.total,
+                idle: state.idle.len(),
+                in_use: state.total - state.idle.len(),
+                ..state.metrics.clone()
+            })
+            .unwrap_or_default()
+    }
+    
+    /// Close all idle connections and refuse further checkouts
+    ///
+    /// Checked-out connections are closed when they are returned.
+    pub fn close(&self) -> CoreBaseResult<()> {
+        let mut state = self.inner.lock()?;
+        state.closed = true;
+        while let Some(idle) = state.idle.pop_front() {
+            self.inner.close(&mut state, &idle.connection);
+        }
+        self.inner.available.notify_all();
+        Ok(())
+    }
+    
+    fn guard(&self, connection: NetworkConnection) -> PooledConnection {
+        PooledConnection {
+            connection: Some(connection),
+            pool: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl Drop for ConnectionPool {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+impl PoolInner {
+    fn lock(&self) -> CoreBaseResult<MutexGuard<'_, PoolState>> {
+        self.state.lock().map_err(|_| lock_error())
+    }
+    
+    fn open(&self) -> CoreBaseResult<NetworkConnection> {
+        let connection = self.manager.create_connection(self.config.clone())?;
+        if let Ok(mut state) = self.state.lock() {
+            state.metrics.created += 1;
+        }
+        Ok(connection)
+    }
+    
+    fn close(&self, state: &mut PoolState, connection: &NetworkConnection) {
+        let _ = self.manager.close_connection(&connection.id);
+        state.total = state.total.saturating_sub(1);
+        state.metrics.closed += 1;
+    }
+    
+    fn evict_expired(&self, state: &mut PoolState) {
+        while state.total > self.options.min {
+            match state.idle.front() {
+                Some(idle) if idle.since.elapsed() >= self.options.idle_timeout => {
+                    if let Some(idle) = state.idle.pop_front() {
+                        self.close(state, &idle.connection);
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+    
+    fn checkin(&self, connection: NetworkConnection, reusable: bool) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        
+        if reusable && !state.closed && connection.current_state() == ConnectionState::Connected {
+            state.idle.push_back(IdleConnection {
+                connection,
+                since: Instant::now(),
+            });
+        } else {
+            self.close(&mut state, &connection);
+        }
+        self.available.notify_one();
+    }
+}
+
+/// A connection checked out of a `ConnectionPool`
+///
+/// Dereferences to `NetworkConnection` and is returned to the pool on drop.
+#[derive(Debug)]
+pub struct PooledConnection {
+    connection: Option<NetworkConnection>,
+    pool: Arc<PoolInner>,
+}
+
+impl PooledConnection {
+    /// Close the connection instead of returning it to the pool
+    ///
+    /// Use this after an I/O error leaves the connection in an unknown state.
+    pub fn discard(mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.checkin(connection, false);
+        }
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = NetworkConnection;
+    
+    fn deref(&self) -> &NetworkConnection {
+        self.connection.as_ref().expect("pooled connection already returned")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.checkin(connection, true);
+        }
+    }
+}
+
+/// An idle connection is healthy if it has no unexpected pending data
+///
+/// Readable idle sockets usually mean the peer closed the connection.
+fn is_healthy(connection: &NetworkConnection) -> bool {
+    connection.current_state() == ConnectionState::Connected && matches!(connection.poll_readable(0), Ok(false))
+}
+
+fn lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access connection pool".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::testing::{echo_config, echo_manager};
+    
+    fn options(min: usize, max: usize) -> PoolOptions {
+        PoolOptions {
+            min,
+            max,
+            checkout_timeout: Duration::from_millis(20),
+            ..PoolOptions::default()
+        }
+    }
+    
+    #[test]
+    fn test_checkout_and_checkin() {
+        let pool = ConnectionPool::with_manager(echo_manager(), echo_config(), options(1, 2)).unwrap();
+        assert_eq!(pool.metrics().idle, 1);
+        
+        let first = pool.checkout().unwrap();
+        let second = pool.checkout().unwrap();
+        assert_eq!(pool.metrics().in_use, 2);
+        
+        assert!(matches!(pool.checkout(), Err(CoreBaseError::Timeout(..))));
+        assert_eq!(pool.metrics().timeouts, 1);
+        
+        drop(first);
+        second.discard();
+        let metrics = pool.metrics();
+        assert_eq!(metrics.total, 1);
+        assert_eq!(metrics.idle, 1);
+        assert_eq!(metrics.checkouts, 2);
+        assert!(metrics.closed >= 1);
+        
+        let _third = pool.checkout().unwrap();
+        pool.close().unwrap();
+        assert!(pool.checkout().is_err());
+    }
+    
+    #[test]
+    fn test_idle_eviction() {
+        let pool_options = PoolOptions {
+            idle_timeout: Duration::ZERO,
+            ..options(1, 3)
+        };
+        let pool = ConnectionPool::with_manager(echo_manager(), echo_config(), pool_options).unwrap();
+        
+        let connections: Vec<_> = (0..3).map(|_| pool.checkout().unwrap()).collect();
+        drop(connections);
+        assert_eq!(pool.metrics().idle, 3);
+        
+        pool.evict_idle().unwrap();
+        let metrics = pool.metrics();
+        assert_eq!(metrics.total, 1);
+        assert_eq!(metrics.idle, 1);
+        
+        assert!(ConnectionPool::new(NetworkConfig::default(), options(3, 2)).is_err());
+    }
+}
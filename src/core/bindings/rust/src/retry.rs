@@ -0,0 +1,221 @@
+//! Retrying for CoreBase Rust bindings
+//!
+//! This module provides `RetryPolicy` and `retry()`, which repeat an
+//! operation with exponential backoff while it fails with a transient
+//! error (see `CoreBaseError::is_transient`).
+
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "async")]
+use std::future::Future;
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::util::random_delay;
+
+/// How often and how long to wait when retrying an operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound of every delay
+    pub max_delay: Duration,
+    /// Factor each delay grows by
+    pub multiplier: f64,
+    /// Wait a random duration between half and all of each delay, so
+    /// clients failing together don't retry together
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy making up to `max_attempts` attempts with the
+    /// default backoff
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+    
+    /// Set the first delay and the bound of all delays
+    pub fn with_delays(mut self, initial_delay: Duration, max_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self.max_delay = max_delay;
+        self
+    }
+    
+    /// Set the factor each delay grows by
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+    
+    /// Enable or disable jitter
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+    
+    /// Get the delay before retry number `retry`, counting from 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let seconds = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let delay = Duration::try_from_secs_f64(seconds).unwrap_or(self.max_delay).min(self.max_delay);
+        if self.jitter {
+            delay / 2 + random_delay(delay / 2)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Run `op` until it succeeds, fails with an error that isn't transient,
+/// or `policy.max_attempts` attempts were made
+///
+/// The thread sleeps between attempts. The last error is returned if every
+/// attempt failed.
+///
+/// ```ignore
+/// let message = retry(&RetryPolicy::new(5), || connection.receive())?;
+/// ```
+pub fn retry<T, F>(policy: &RetryPolicy, op: F) -> CoreBaseResult<T>
+where
+    F: FnMut() -> CoreBaseResult<T>,
+{
+    retry_if(policy, CoreBaseError::is_transient, op)
+}
+
+/// Run `op` like `retry()`, retrying the errors `should_retry` accepts
+pub fn retry_if<T, F, P>(policy: &RetryPolicy, mut should_retry: P, mut op: F) -> CoreBaseResult<T>
+where
+    F: FnMut() -> CoreBaseResult<T>,
+    P: FnMut(&CoreBaseError) -> bool,
+{
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(e) if attempt < policy.max_attempts && should_retry(&e) => {
+                thread::sleep(policy.delay(attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Run the future returned by `op` like `retry()`, waiting between
+/// attempts without blocking the runtime
+#[cfg(feature = "async")]
+pub async fn retry_async<T, F, Fut>(policy: &RetryPolicy, mut op: F) -> CoreBaseResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = CoreBaseResult<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if attempt < policy.max_attempts && e.is_transient() => {
+                tokio::time::sleep(policy.delay(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    use crate::error::ErrorCode;
+    
+    #[test]
+    fn test_retry_policy_delays() {
+        let policy = RetryPolicy::new(5)
+            .with_delays(Duration::from_millis(100), Duration::from_millis(350))
+            .with_jitter(false);
+        let delays: Vec<Duration> = (1..=4).map(|retry| policy.delay(retry)).collect();
+        assert_eq!(delays, [100, 200, 350, 350].map(Duration::from_millis));
+        assert_eq!(policy.clone().with_multiplier(1.0).delay(4), Duration::from_millis(100));
+        assert_eq!(policy.with_multiplier(f64::INFINITY).delay(2), Duration::from_millis(350));
+        
+        let jittered = RetryPolicy::new(5).with_delays(Duration::from_secs(1), Duration::from_secs(1));
+        for retry in 1..=10 {
+            let delay = jittered.delay(retry);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_secs(1));
+        }
+    }
+    
+    #[test]
+    fn test_retry() {
+        let policy = RetryPolicy::new(4).with_delays(Duration::ZERO, Duration::ZERO);
+        
+        let mut calls = 0;
+        let result = retry(&policy, || {
+            calls += 1;
+            if calls < 3 {
+                Err(CoreBaseError::Timeout("slow".to_string(), None))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        
+        // Errors that won't go away are returned at once
+        let mut calls = 0;
+        let result: CoreBaseResult<()> = retry(&policy, || {
+            calls += 1;
+            Err(CoreBaseError::InvalidParameter("port".to_string(), None))
+        });
+        assert!(matches!(result, Err(CoreBaseError::InvalidParameter(..))));
+        assert_eq!(calls, 1);
+        
+        let mut calls = 0;
+        let result: CoreBaseResult<()> = retry(&policy, || {
+            calls += 1;
+            Err(CoreBaseError::OperationFailed(format!("attempt {}", calls), Some(ErrorCode::Network)))
+        });
+        assert_eq!(result.unwrap_err().to_string(), "Operation failed: attempt 4");
+        assert_eq!(calls, 4);
+        
+        let mut calls = 0;
+        let result: CoreBaseResult<()> = retry_if(&policy, |e| matches!(e, CoreBaseError::ResourceNotFound(..)), || {
+            calls += 1;
+            Err(CoreBaseError::ResourceNotFound("key".to_string(), None))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 4);
+    }
+    
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_retry_async() {
+        let policy = RetryPolicy::new(3).with_delays(Duration::from_millis(1), Duration::from_millis(1));
+        let mut calls = 0;
+        let result = retry_async(&policy, || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt < 2 {
+                    Err(CoreBaseError::NetworkError("reset".to_string(), Some(ErrorCode::Network)))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        }).await;
+        assert_eq!(result.unwrap(), 2);
+    }
+}
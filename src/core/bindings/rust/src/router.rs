@@ -0,0 +1,253 @@
+//! Topic routing for CoreBase Rust bindings
+//!
+//! This module provides `MessageRouter`, which dispatches messages to
+//! handlers registered by topic filter, and the MQTT-style wildcard
+//! matching shared by the router and `MqttClient` subscriptions.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::network::{NetworkConnection, NetworkMessage};
+
+type Handler = dyn Fn(&NetworkMessage) + Send + Sync;
+
+struct Route {
+    filter: String,
+    handler: Arc<Handler>,
+}
+
+/// Dispatches messages to handlers by topic
+///
+/// Filters use MQTT syntax: `+` matches one topic level and a trailing `#`
+/// matches any number of levels. A message is passed to every matching
+/// handler in registration order; messages that match no route, or have no
+/// topic, go to the `otherwise()` handler.
+///
+/// ```ignore
+/// let router = MessageRouter::new();
+/// router.on("sensor/+/temp", |message| println!("{:?}", message.as_text()))?;
+/// router.dispatch(&subscription.recv()?);
+/// ```
+pub struct MessageRouter {
+    routes: Mutex<Vec<Route>>,
+    fallback: Mutex<Option<Arc<Handler>>>,
+}
+
+impl MessageRouter {
+    /// Create a router without routes
+    pub fn new() -> Self {
+        MessageRouter {
+            routes: Mutex::new(Vec::new()),
+            fallback: Mutex::new(None),
+        }
+    }
+    
+    /// Register a handler for a topic filter
+    pub fn on<F>(&self, filter: &str, handler: F) -> CoreBaseResult<()>
+    where
+        F: Fn(&NetworkMessage) + Send + Sync + 'static,
+    {
+        if !is_valid_filter(filter) {
+            return Err(CoreBaseError::InvalidParameter(
+                format!("Invalid topic filter: '{}'", filter),
+                None,
+            ));
+        }
+        
+        self.routes.lock().map_err(|_| router_lock_error())?.push(Route {
+            filter: filter.to_string(),
+            handler: Arc::new(handler),
+        });
+        Ok(())
+    }
+    
+    /// Remove every handler registered for a filter
+    ///
+    /// Returns whether any handler was removed.
+    pub fn off(&self, filter: &str) -> bool {
+        let Ok(mut routes) = self.routes.lock() else {
+            return false;
+        };
+        let before = routes.len();
+        routes.retain(|route| route.filter != filter);
+        routes.len() != before
+    }
+    
+    /// Set the handler for messages no route matches
+    pub fn otherwise<F>(&self, handler: F)
+    where
+        F: Fn(&NetworkMessage) + Send + Sync + 'static,
+    {
+        if let Ok(mut fallback) = self.fallback.lock() {
+            *fallback = Some(Arc::new(handler));
+        }
+    }
+    
+    /// Dispatch a message by its topic
+    ///
+    /// Returns the number of route handlers called, not counting the
+    /// `otherwise()` handler.
+    pub fn dispatch(&self, message: &NetworkMessage) -> usize {
+        match message.topic.as_deref() {
+            Some(topic) => self.dispatch_topic(topic, message),
+            None => {
+                self.fall_back(message);
+                0
+            }
+        }
+    }
+    
+    /// Dispatch a message under an explicit topic
+    ///
+    /// Use this for protocols whose topic is carried in the payload rather
+    /// than in `NetworkMessage::topic`, such as WebSocket or custom
+    /// transports.
+    pub fn dispatch_topic(&self, topic: &str, message: &NetworkMessage) -> usize {
+        // Handlers run without the lock so they may register further routes
+        let handlers: Vec<Arc<Handler>> = match self.routes.lock() {
+            Ok(routes) => routes
+                .iter()
+                .filter(|route| topic_matches(&route.filter, topic))
+                .map(|route| Arc::clone(&route.handler))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        
+        if handlers.is_empty() {
+            self.fall_back(message);
+        }
+        for handler in &handlers {
+            handler(message);
+        }
+        handlers.len()
+    }
+    
+    /// Receive one message from a connection and dispatch it
+    ///
+    /// Waits at most `timeout` and returns the number of route handlers
+    /// called.
+    pub fn dispatch_next(&self, connection: &NetworkConnection, timeout: Duration) -> CoreBaseResult<usize> {
+        let message = connection.receive_timeout(timeout)?;
+        Ok(self.dispatch(&message))
+    }
+    
+    fn fall_back(&self, message: &NetworkMessage) {
+        let fallback = self.fallback.lock().ok().and_then(|fallback| fallback.clone());
+        if let Some(handler) = fallback {
+            handler(message);
+        }
+    }
+}
+
+impl Default for MessageRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for MessageRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let filters: Vec<String> = self.routes.lock()
+            .map(|routes| routes.iter().map(|route| route.filter.clone()).collect())
+            .unwrap_or_default();
+        f.debug_struct("MessageRouter")
+            .field("filters", &filters)
+            .field("fallback", &self.fallback.lock().is_ok_and(|fallback| fallback.is_some()))
+            .finish()
+    }
+}
+
+/// Check whether a topic matches an MQTT-style topic filter
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    // Topics starting with '$' are not matched by leading wildcards
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+    
+    let mut filter_levels = filter.split('/');
+    let mut topic_levels = topic.split('/');
+    
+    loop {
+        match (filter_levels.next(), topic_levels.next()) {
+            (Some("#"), _) => return true,
+            (Some("+"), Some(_)) => {}
+            (Some(f), Some(t)) if f == t => {}
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Check that wildcards only appear as whole levels, with `#` last
+pub(crate) fn is_valid_filter(filter: &str) -> bool {
+    let levels: Vec<&str> = filter.split('/').collect();
+    !filter.is_empty()
+        && levels.iter().enumerate().all(|(i, level)| match *level {
+            "#" => i == levels.len() - 1,
+            "+" => true,
+            other => !other.contains(['+', '#']),
+        })
+}
+
+fn router_lock_error() -> CoreBaseError {
+    CoreBaseError::OperationFailed("Failed to access message routes".to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    use crate::transport::testing::{echo_config, echo_manager};
+    
+    #[test]
+    fn test_message_router() {
+        let router = MessageRouter::new();
+        let temperatures = Arc::new(Mutex::new(Vec::new()));
+        let unmatched = Arc::new(AtomicUsize::new(0));
+        
+        let seen = Arc::clone(&temperatures);
+        router.on("sensor/+/temp", move |message| {
+            seen.lock().unwrap().push(message.as_text().unwrap());
+        }).unwrap();
+        router.on("sensor/#", |_| {}).unwrap();
+        let count = Arc::clone(&unmatched);
+        router.otherwise(move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+        assert!(router.on("sensor/#/temp", |_| {}).is_err());
+        
+        assert_eq!(router.dispatch(&NetworkMessage::new_text("21.5").with_topic("sensor/kitchen/temp")), 2);
+        assert_eq!(router.dispatch(&NetworkMessage::new_text("40%").with_topic("sensor/kitchen/humidity")), 1);
+        assert_eq!(router.dispatch(&NetworkMessage::new_text("up").with_topic("status")), 0);
+        assert_eq!(router.dispatch(&NetworkMessage::new_text("no topic")), 0);
+        assert_eq!(router.dispatch_topic("sensor/garage/temp", &NetworkMessage::new_text("8.0")), 2);
+        assert_eq!(*temperatures.lock().unwrap(), ["21.5", "8.0"]);
+        assert_eq!(unmatched.load(Ordering::SeqCst), 2);
+        
+        assert!(router.off("sensor/#"));
+        assert!(!router.off("sensor/#"));
+        
+        let manager = echo_manager();
+        let connection = manager.create_connection(echo_config()).unwrap();
+        connection.send(&NetworkMessage::new_text("hello")).unwrap();
+        assert_eq!(router.dispatch_next(&connection, Duration::from_secs(1)).unwrap(), 0);
+        assert_eq!(unmatched.load(Ordering::SeqCst), 3);
+    }
+    
+    #[test]
+    fn test_topic_filters() {
+        assert!(topic_matches("sensor/+/temp", "sensor/kitchen/temp"));
+        assert!(!topic_matches("sensor/+/temp", "sensor/kitchen/humidity"));
+        assert!(topic_matches("sensor/#", "sensor"));
+        assert!(!topic_matches("+/status", "$SYS/status"));
+        
+        assert!(is_valid_filter("sensor/+/temp"));
+        assert!(is_valid_filter("#"));
+        assert!(!is_valid_filter(""));
+        assert!(!is_valid_filter("sensor/#/temp"));
+        assert!(!is_valid_filter("sensor/kit+chen"));
+    }
+}
@@ -0,0 +1,398 @@
+//! Benchmark sessions for CoreBase Rust bindings
+//!
+//! This module provides `MonitorSession`, which records the CPU time and
+//! peak resident memory of the current process over labelled sections of a
+//! run, and `SessionReport`, which compares two sessions section by section
+//! so performance CI jobs can flag resource regressions. Sessions serialize
+//! to JSON, so a baseline can be stored and compared against later runs.
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+use crate::monitor::{ProcessUsage, SystemMonitor};
+
+/// Resource usage of one recorded section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub label: String,
+    pub duration: Duration,
+    /// CPU time of the process, summed over all cores
+    pub cpu_seconds: f64,
+    /// Highest resident set size sampled
+    pub peak_rss_bytes: u64,
+    /// Number of process samples taken
+    pub samples: usize,
+}
+
+/// Labelled sections of a run and their resource usage
+///
+/// Each `record()` call samples the process on a background thread every
+/// `interval` until the returned `Recording` ends, so CPU time is
+/// integrated from the sampled usage and sections much shorter than the
+/// interval are measured coarsely.
+///
+/// ```ignore
+/// let mut session = MonitorSession::new();
+/// {
+///     let _recording = session.record("parse")?;
+///     parse_corpus();
+/// }
+/// let baseline: MonitorSession = serde_json::from_str(&fs::read_to_string("baseline.json")?)?;
+/// let report = MonitorSession::compare(&baseline, &session);
+/// if report.has_regression(10.0) {
+///     panic!("{}", report);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorSession {
+    /// Time between process samples
+    pub interval: Duration,
+    records: Vec<SessionRecord>,
+}
+
+impl MonitorSession {
+    /// Create a session sampling every 50 ms
+    pub fn new() -> Self {
+        Self::with_interval(Duration::from_millis(50))
+    }
+    
+    /// Create a session sampling every `interval`
+    pub fn with_interval(interval: Duration) -> Self {
+        MonitorSession {
+            interval,
+            records: Vec::new(),
+        }
+    }
+    
+    /// Start recording a section, which ends when the returned
+    /// `Recording` is ended or dropped
+    ///
+    /// Recording a label again replaces its earlier record.
+    pub fn record(&mut self, label: &str) -> CoreBaseResult<Recording<'_>> {
+        let (stop, stopped) = mpsc::channel();
+        let interval = self.interval;
+        let sampler = std::thread::Builder::new()
+            .name("cba-session".to_string())
+            .spawn(move || sample_process(interval, &stopped))
+            .map_err(|e| CoreBaseError::OperationFailed(format!("Failed to start session sampler: {}", e), None))?;
+        
+        Ok(Recording {
+            session: self,
+            label: label.to_string(),
+            started: Instant::now(),
+            stop: Some(stop),
+            sampler: Some(sampler),
+        })
+    }
+    
+    /// Get the recorded sections, in the order they ended
+    pub fn records(&self) -> &[SessionRecord] {
+        &self.records
+    }
+    
+    /// Get the record of a section
+    pub fn get(&self, label: &str) -> Option<&SessionRecord> {
+        self.records.iter().find(|record| record.label == label)
+    }
+    
+    /// Compare the sections recorded in both sessions, in the order of
+    /// `baseline`
+    pub fn compare(baseline: &MonitorSession, candidate: &MonitorSession) -> SessionReport {
+        let sections = baseline
+            .records
+            .iter()
+            .filter_map(|before| {
+                let after = candidate.get(&before.label)?;
+                Some(SectionComparison {
+                    label: before.label.clone(),
+                    baseline: before.clone(),
+                    candidate: after.clone(),
+                })
+            })
+            .collect();
+        SessionReport { sections }
+    }
+    
+    fn push(&mut self, record: SessionRecord) {
+        self.records.retain(|existing| existing.label != record.label);
+        self.records.push(record);
+    }
+}
+
+impl Default for MonitorSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Section of a `MonitorSession` being recorded
+///
+/// Dropping the recording ends it; use `end()` to get the record or the
+/// sampling error.
+#[derive(Debug)]
+pub struct Recording<'a> {
+    session: &'a mut MonitorSession,
+    label: String,
+    started: Instant,
+    stop: Option<Sender<()>>,
+    sampler: Option<JoinHandle<CoreBaseResult<ProcessTotals>>>,
+}
+
+impl Recording<'_> {
+    /// End the section and add its record to the session
+    pub fn end(mut self) -> CoreBaseResult<SessionRecord> {
+        self.finish()
+    }
+    
+    fn finish(&mut self) -> CoreBaseResult<SessionRecord> {
+        let duration = self.started.elapsed();
+        self.stop.take();
+        let totals = self.sampler
+            .take()
+            .ok_or_else(|| CoreBaseError::OperationFailed("Recording already ended".to_string(), None))?
+            .join()
+            .map_err(|_| CoreBaseError::OperationFailed("Session sampler panicked".to_string(), None))??;
+        
+        let record = SessionRecord {
+            label: self.label.clone(),
+            duration,
+            cpu_seconds: totals.cpu_seconds,
+            peak_rss_bytes: totals.peak_rss_bytes,
+            samples: totals.samples,
+        };
+        self.session.push(record.clone());
+        Ok(record)
+    }
+}
+
+impl Drop for Recording<'_> {
+    fn drop(&mut self) {
+        if self.sampler.is_some() {
+            let _ = self.finish();
+        }
+    }
+}
+
+/// Usage accumulated by the sampler thread of a recording
+#[derive(Debug, Default)]
+struct ProcessTotals {
+    cpu_seconds: f64,
+    peak_rss_bytes: u64,
+    samples: usize,
+}
+
+impl ProcessTotals {
+    fn add(&mut self, usage: &ProcessUsage, elapsed: Duration) {
+        self.cpu_seconds += usage.cpu_usage_percent / 100.0 * elapsed.as_secs_f64();
+        self.peak_rss_bytes = self.peak_rss_bytes.max(usage.rss_bytes);
+        self.samples += 1;
+    }
+}
+
+/// Sample the process until the recording is ended, once more at the end
+fn sample_process(interval: Duration, stopped: &Receiver<()>) -> CoreBaseResult<ProcessTotals> {
+    let monitor = SystemMonitor::new()?;
+    let mut totals = ProcessTotals::default();
+    let mut last = Instant::now();
+    totals.add(&monitor.get_process_usage()?, Duration::ZERO);
+    
+    loop {
+        let ended = !matches!(stopped.recv_timeout(interval), Err(RecvTimeoutError::Timeout));
+        let now = Instant::now();
+        totals.add(&monitor.get_process_usage()?, now.duration_since(last));
+        last = now;
+        if ended {
+            return Ok(totals);
+        }
+    }
+}
+
+/// Change of one section between two sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionComparison {
+    pub label: String,
+    pub baseline: SessionRecord,
+    pub candidate: SessionRecord,
+}
+
+impl SectionComparison {
+    /// Get the change in CPU time, in percent of the baseline
+    ///
+    /// Returns `None` if the baseline used no CPU time.
+    pub fn cpu_change_percent(&self) -> Option<f64> {
+        change_percent(self.baseline.cpu_seconds, self.candidate.cpu_seconds)
+    }
+    
+    /// Get the change in peak resident memory, in percent of the baseline
+    ///
+    /// Returns `None` if the baseline has no resident memory sample.
+    pub fn peak_rss_change_percent(&self) -> Option<f64> {
+        change_percent(self.baseline.peak_rss_bytes as f64, self.candidate.peak_rss_bytes as f64)
+    }
+    
+    /// Check whether CPU time or peak memory grew by more than
+    /// `tolerance_percent`
+    pub fn is_regression(&self, tolerance_percent: f64) -> bool {
+        [self.cpu_change_percent(), self.peak_rss_change_percent()]
+            .into_iter()
+            .flatten()
+            .any(|change| change > tolerance_percent)
+    }
+}
+
+/// Section-by-section comparison of two sessions, from
+/// `MonitorSession::compare`
+///
+/// Displays as one line per section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub sections: Vec<SectionComparison>,
+}
+
+impl SessionReport {
+    /// Get the sections whose CPU time or peak memory grew by more than
+    /// `tolerance_percent`
+    pub fn regressions(&self, tolerance_percent: f64) -> impl Iterator<Item = &SectionComparison> {
+        self.sections.iter().filter(move |section| section.is_regression(tolerance_percent))
+    }
+    
+    /// Check whether any section regressed by more than `tolerance_percent`
+    pub fn has_regression(&self, tolerance_percent: f64) -> bool {
+        self.regressions(tolerance_percent).next().is_some()
+    }
+}
+
+impl fmt::Display for SessionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.sections.is_empty() {
+            return write!(f, "no common sections");
+        }
+        for (i, section) in self.sections.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}: cpu {:.3}s -> {:.3}s{}, peak rss {} -> {}{}",
+                section.label,
+                section.baseline.cpu_seconds,
+                section.candidate.cpu_seconds,
+                format_change(section.cpu_change_percent()),
+                crate::monitor::SystemResources::format_bytes(section.baseline.peak_rss_bytes as f64),
+                crate::monitor::SystemResources::format_bytes(section.candidate.peak_rss_bytes as f64),
+                format_change(section.peak_rss_change_percent()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn change_percent(before: f64, after: f64) -> Option<f64> {
+    (before > 0.0).then(|| (after - before) / before * 100.0)
+}
+
+fn format_change(change: Option<f64>) -> String {
+    change.map(|change| format!(" ({:+.1}%)", change)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn record(label: &str, cpu_seconds: f64, peak_rss_bytes: u64) -> SessionRecord {
+        SessionRecord {
+            label: label.to_string(),
+            duration: Duration::from_secs(1),
+            cpu_seconds,
+            peak_rss_bytes,
+            samples: 10,
+        }
+    }
+    
+    #[test]
+    fn test_process_totals() {
+        let usage = |cpu_usage_percent: f64, rss_bytes: u64| ProcessUsage {
+            cpu_usage_percent,
+            rss_bytes,
+            ..Default::default()
+        };
+        let mut totals = ProcessTotals::default();
+        totals.add(&usage(100.0, 2_000), Duration::ZERO);
+        totals.add(&usage(50.0, 8_000), Duration::from_secs(2));
+        totals.add(&usage(200.0, 4_000), Duration::from_millis(500));
+        assert_eq!(totals.cpu_seconds, 2.0);
+        assert_eq!(totals.peak_rss_bytes, 8_000);
+        assert_eq!(totals.samples, 3);
+        
+        // Recording a label again replaces its record
+        let mut session = MonitorSession::new();
+        session.push(record("idle", 1.0, 1));
+        session.push(record("busy", 2.0, 2));
+        session.push(record("idle", 3.0, 3));
+        let labels: Vec<&str> = session.records().iter().map(|record| record.label.as_str()).collect();
+        assert_eq!(labels, ["busy", "idle"]);
+        assert_eq!(session.get("idle").unwrap().cpu_seconds, 3.0);
+    }
+    
+    #[test]
+    #[ignore = "needs the native CoreBase library"]
+    fn test_record_sections() {
+        let mut session = MonitorSession::with_interval(Duration::from_millis(5));
+        {
+            let _recording = session.record("idle").unwrap();
+            std::thread::sleep(Duration::from_millis(30));
+        }
+        let recorded = session.record("again").unwrap().end().unwrap();
+        assert_eq!(recorded.label, "again");
+        
+        let idle = session.get("idle").unwrap();
+        assert!(idle.duration >= Duration::from_millis(30));
+        assert!(idle.samples >= 3);
+        assert!(idle.peak_rss_bytes > 0);
+        assert!(idle.cpu_seconds >= 0.0);
+        assert_eq!(session.records().len(), 2);
+    }
+    
+    #[test]
+    fn test_session_json() {
+        let mut session = MonitorSession::new();
+        session.push(record("parse", 2.5, 100_000_000));
+        session.push(record("render", 0.125, 0));
+        
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: MonitorSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, session);
+    }
+    
+    #[test]
+    fn test_compare_sessions() {
+        let mut baseline = MonitorSession::new();
+        baseline.push(record("parse", 2.0, 100_000_000));
+        baseline.push(record("render", 1.0, 0));
+        baseline.push(record("removed", 1.0, 1));
+        let mut candidate = MonitorSession::new();
+        candidate.push(record("render", 1.05, 0));
+        candidate.push(record("parse", 2.0, 125_000_000));
+        candidate.push(record("added", 1.0, 1));
+        
+        let report = MonitorSession::compare(&baseline, &candidate);
+        let labels: Vec<&str> = report.sections.iter().map(|section| section.label.as_str()).collect();
+        assert_eq!(labels, ["parse", "render"]);
+        assert_eq!(report.sections[0].peak_rss_change_percent(), Some(25.0));
+        assert_eq!(report.sections[1].peak_rss_change_percent(), None);
+        
+        let regressed: Vec<&str> = report.regressions(10.0).map(|section| section.label.as_str()).collect();
+        assert_eq!(regressed, ["parse"]);
+        assert!(report.has_regression(4.0));
+        assert!(!report.has_regression(30.0));
+        
+        let text = report.to_string();
+        assert!(text.starts_with("parse: cpu 2.000s -> 2.000s (+0.0%), peak rss 95.37 MB -> 119.21 MB (+25.0%)\n"));
+        assert!(text.ends_with("render: cpu 1.000s -> 1.050s (+5.0%), peak rss 0.00 B -> 0.00 B"));
+    }
+}
@@ -0,0 +1,259 @@
+//! `metrics` facade publishing for CoreBase Rust bindings
+//!
+//! With the "metrics" feature, every sample taken by `SystemMonitor` is
+//! published as gauges through the `metrics` crate, so an application's
+//! existing exporter (Prometheus, StatsD, ...) picks up CoreBase data.
+//! Gauges are named `corebase.<group>.<reading>` and carry the monitor's
+//! labels and metric tags; custom metrics keep their own names. Without the
+//! feature `publish()` does nothing.
+
+#[cfg(feature = "metrics")]
+use metrics::Label;
+
+use crate::monitor::{MonitoringConfig, SystemResources};
+
+/// Publish a sample as gauges
+#[cfg(feature = "metrics")]
+pub(crate) fn publish(config: &MonitoringConfig, resources: &SystemResources) {
+    let gauges = Gauges { config };
+    
+    if config.enable_cpu_monitoring {
+        gauges.set("corebase.cpu.usage_percent", "cpu_usage", &[], resources.cpu_usage_percent);
+        for (core, usage) in resources.cpu_per_core.iter().enumerate() {
+            gauges.set("corebase.cpu.core_usage_percent", "max_core_usage", &[("core", core.to_string())], *usage);
+        }
+        for (window, load) in ["1m", "5m", "15m"].into_iter().zip(resources.load_average) {
+            gauges.set("corebase.load_average", "load_average", &[("window", window.to_string())], load);
+        }
+    }
+    
+    if config.enable_memory_monitoring {
+        gauges.set("corebase.memory.usage_percent", "memory_usage", &[], resources.memory_usage_percent());
+        gauges.set("corebase.memory.available_bytes", "memory_usage", &[], resources.available_memory_bytes);
+        gauges.set("corebase.memory.total_bytes", "memory_usage", &[], resources.total_memory_bytes);
+        if let Some(memory) = &resources.memory {
+            gauges.set("corebase.memory.free_bytes", "memory_usage", &[], memory.free_bytes);
+            gauges.set("corebase.memory.cached_bytes", "memory_usage", &[], memory.cached_bytes);
+            gauges.set("corebase.memory.buffers_bytes", "memory_usage", &[], memory.buffers_bytes);
+            gauges.set("corebase.memory.shared_bytes", "memory_usage", &[], memory.shared_bytes);
+            if let Some(charge) = memory.commit_charge_bytes {
+                gauges.set("corebase.memory.commit_charge_bytes", "memory_usage", &[], charge);
+            }
+            if let Some(limit) = memory.commit_limit_bytes {
+                gauges.set("corebase.memory.commit_limit_bytes", "memory_usage", &[], limit);
+            }
+        }
+        gauges.set("corebase.swap.usage_percent", "swap_usage", &[], resources.swap_usage_percent());
+        gauges.set("corebase.swap.used_bytes", "swap_usage", &[], resources.swap_used_bytes);
+        gauges.set("corebase.swap.total_bytes", "swap_usage", &[], resources.swap_total_bytes);
+    }
+    
+    if config.enable_disk_monitoring {
+        gauges.set("corebase.disk.usage_percent", "disk_usage", &[], resources.disk_usage_percent());
+        gauges.set("corebase.disk.available_bytes", "disk_usage", &[], resources.available_disk_bytes);
+        gauges.set("corebase.disk.total_bytes", "disk_usage", &[], resources.total_disk_bytes);
+        for io in &resources.disk_io {
+            let device = [("device", io.device.clone())];
+            gauges.set("corebase.disk.read_bytes_per_sec", "disk_usage", &device, io.read_bytes_per_sec);
+            gauges.set("corebase.disk.write_bytes_per_sec", "disk_usage", &device, io.write_bytes_per_sec);
+            gauges.set("corebase.disk.read_iops", "disk_usage", &device, io.read_iops);
+            gauges.set("corebase.disk.write_iops", "disk_usage", &device, io.write_iops);
+        }
+    }
+    
+    if config.enable_network_monitoring {
+        gauges.set("corebase.network.usage_percent", "network_usage", &[], resources.network_usage_percent);
+        for io in &resources.network_io {
+            let interface = [("interface", io.interface.clone())];
+            gauges.set(
+                "corebase.network.received_bytes_per_sec",
+                "network_received_bytes_per_sec",
+                &interface,
+                io.received_bytes_per_sec,
+            );
+            gauges.set("corebase.network.sent_bytes_per_sec", "network_sent_bytes_per_sec", &interface, io.sent_bytes_per_sec);
+        }
+    }
+    
+    if config.enable_gpu_monitoring {
+        gauges.set("corebase.gpu.usage_percent", "gpu_usage", &[], resources.gpu_usage_percent);
+        gauges.set("corebase.gpu.memory_used_bytes", "gpu_usage", &[], resources.gpu_memory_used_bytes);
+        gauges.set("corebase.gpu.memory_total_bytes", "gpu_usage", &[], resources.gpu_memory_total_bytes);
+    }
+    
+    if let Some(thermal) = &resources.thermal {
+        if let Some(celsius) = thermal.cpu_temperature_celsius {
+            gauges.set("corebase.cpu.temperature_celsius", "cpu_temperature", &[], celsius);
+        }
+        if let Some(celsius) = thermal.gpu_temperature_celsius {
+            gauges.set("corebase.gpu.temperature_celsius", "gpu_temperature", &[], celsius);
+        }
+        for (fan, rpm) in thermal.fan_rpm.iter().enumerate() {
+            gauges.set("corebase.fan.rpm", "fan_rpm", &[("fan", fan.to_string())], *rpm);
+        }
+    }
+    
+    if let Some(container) = &resources.container {
+        gauges.set("corebase.host.cpu.usage_percent", "cpu_usage", &[], container.host_cpu_usage_percent);
+        gauges.set("corebase.host.memory.available_bytes", "memory_usage", &[], container.host_available_memory_bytes);
+        gauges.set("corebase.host.memory.total_bytes", "memory_usage", &[], container.host_total_memory_bytes);
+        gauges.set("corebase.container.memory.used_bytes", "memory_usage", &[], container.memory_used_bytes);
+        if let Some(cores) = container.cpu_limit_cores {
+            gauges.set("corebase.container.cpu.limit_cores", "cpu_usage", &[], cores);
+        }
+        if let Some(limit) = container.memory_limit_bytes {
+            gauges.set("corebase.container.memory.limit_bytes", "memory_usage", &[], limit);
+        }
+    }
+    
+    gauges.set("corebase.uptime_seconds", "uptime", &[], resources.uptime_seconds as f64);
+    
+    for (name, value) in &resources.custom {
+        metrics::gauge!(name.clone(), gauges.labels(name, &[])).set(*value);
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn publish(_config: &MonitoringConfig, _resources: &SystemResources) {}
+
+/// Sets gauges labelled from a monitor configuration
+#[cfg(feature = "metrics")]
+struct Gauges<'a> {
+    config: &'a MonitoringConfig,
+}
+
+#[cfg(feature = "metrics")]
+impl Gauges<'_> {
+    /// Set a gauge with the labels of `metric`, as named in
+    /// `MonitoringConfig::metric_tags`, and the given extra labels
+    fn set(&self, name: &'static str, metric: &str, extra: &[(&'static str, String)], value: f64) {
+        metrics::gauge!(name, self.labels(metric, extra)).set(value);
+    }
+    
+    fn labels(&self, metric: &str, extra: &[(&'static str, String)]) -> Vec<Label> {
+        self.config
+            .metric_labels(metric)
+            .into_iter()
+            .map(|(key, value)| Label::new(key, value))
+            .chain(extra.iter().map(|(key, value)| Label::new(*key, value.clone())))
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    
+    use metrics::{Counter, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+    
+    use crate::monitor::{DiskIo, MemoryBreakdown, NetworkIo, ThermalInfo};
+    
+    type Values = Arc<Mutex<BTreeMap<String, f64>>>;
+    
+    /// Recorder keeping the last value of every gauge, by name and labels
+    #[derive(Default)]
+    struct GaugeRecorder {
+        values: Values,
+    }
+    
+    struct RecordedGauge {
+        key: String,
+        values: Values,
+    }
+    
+    impl GaugeFn for RecordedGauge {
+        fn increment(&self, value: f64) {
+            *self.values.lock().unwrap().entry(self.key.clone()).or_default() += value;
+        }
+        
+        fn decrement(&self, value: f64) {
+            *self.values.lock().unwrap().entry(self.key.clone()).or_default() -= value;
+        }
+        
+        fn set(&self, value: f64) {
+            self.values.lock().unwrap().insert(self.key.clone(), value);
+        }
+    }
+    
+    impl Recorder for GaugeRecorder {
+        fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        
+        fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        
+        fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        
+        fn register_counter(&self, _key: &Key, _metadata: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+        
+        fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+            let labels: Vec<String> = key.labels().map(|label| format!("{}={}", label.key(), label.value())).collect();
+            Gauge::from_arc(Arc::new(RecordedGauge {
+                key: format!("{}{{{}}}", key.name(), labels.join(",")),
+                values: Arc::clone(&self.values),
+            }))
+        }
+        
+        fn register_histogram(&self, _key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+    
+    #[test]
+    fn test_publish_gauges() {
+        let recorder = GaugeRecorder::default();
+        let values = Arc::clone(&recorder.values);
+        let config = MonitoringConfig::default()
+            .with_label("host", "web-1")
+            .with_metric_tag("cpu_usage", "team", "infra");
+        let resources = SystemResources {
+            cpu_usage_percent: 10.0,
+            cpu_per_core: vec![10.0, 20.0, 30.0, 90.0],
+            load_average: [9.0, 6.0, 3.0],
+            available_memory_bytes: 4e9,
+            total_memory_bytes: 8e9,
+            memory: Some(MemoryBreakdown {
+                total_bytes: 8e9,
+                available_bytes: 4e9,
+                cached_bytes: 2.5e9,
+                ..Default::default()
+            }),
+            swap_used_bytes: 3e9,
+            swap_total_bytes: 4e9,
+            disk_io: vec![DiskIo {
+                device: "sda".to_string(),
+                read_bytes_per_sec: 3_000_000.0,
+                write_bytes_per_sec: 1_000_000.0,
+                read_iops: 200.0,
+                write_iops: 100.0,
+            }],
+            network_io: vec![NetworkIo {
+                interface: "eth0".to_string(),
+                received_bytes_per_sec: 1_000_000.0,
+                sent_bytes_per_sec: 250_000.0,
+            }],
+            thermal: Some(ThermalInfo {
+                cpu_temperature_celsius: Some(92.5),
+                ..Default::default()
+            }),
+            uptime_seconds: 3600,
+            ..Default::default()
+        };
+        metrics::with_local_recorder(&recorder, || publish(&config, &resources));
+        
+        let values = values.lock().unwrap();
+        assert_eq!(values["corebase.cpu.usage_percent{host=web-1,team=infra}"], 10.0);
+        assert_eq!(values["corebase.cpu.core_usage_percent{host=web-1,core=3}"], 90.0);
+        assert_eq!(values["corebase.load_average{host=web-1,window=5m}"], 6.0);
+        assert_eq!(values["corebase.memory.usage_percent{host=web-1}"], 50.0);
+        assert_eq!(values["corebase.memory.cached_bytes{host=web-1}"], 2.5e9);
+        assert!(!values.contains_key("corebase.memory.commit_charge_bytes{host=web-1}"));
+        assert_eq!(values["corebase.swap.usage_percent{host=web-1}"], 75.0);
+        assert!(values.contains_key("corebase.disk.read_iops{host=web-1,device=sda}"));
+        assert!(values.contains_key("corebase.network.sent_bytes_per_sec{host=web-1,interface=eth0}"));
+        assert_eq!(values["corebase.cpu.temperature_celsius{host=web-1}"], 92.5);
+        assert_eq!(values["corebase.uptime_seconds{host=web-1}"], 3600.0);
+    }
+}
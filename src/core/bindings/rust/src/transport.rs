@@ -0,0 +1,135 @@
+//! Custom transports for CoreBase Rust bindings
+//!
+//! This module provides the `Transport` trait, which lets Rust code carry
+//! the traffic of `NetworkProtocol::Custom` connections instead of the C++
+//! NetworkManager. Transports are registered on a `NetworkManager` under a
+//! name and selected with `NetworkConfig::custom`.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::error::CoreBaseResult;
+use crate::network::NetworkConfig;
+
+/// Message transport implemented in Rust
+///
+/// A transport opens any number of connections and refers to them by the
+/// ID returned from `connect()`. Messages are whole payloads; the
+/// connection layer splits them into chunks for streamed reads.
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Open a connection and return an ID unique within this transport
+    fn connect(&self, config: &NetworkConfig) -> CoreBaseResult<String>;
+    
+    /// Send one message
+    fn send(&self, connection_id: &str, data: &[u8]) -> CoreBaseResult<()>;
+    
+    /// Receive one message, waiting at most `timeout` (`None` waits forever)
+    ///
+    /// Returns `Ok(None)` if nothing arrived in time.
+    fn receive(&self, connection_id: &str, timeout: Option<Duration>) -> CoreBaseResult<Option<Vec<u8>>>;
+    
+    /// Close a connection
+    fn close(&self, connection_id: &str) -> CoreBaseResult<()>;
+}
+
+/// Transport double for tests that need a peer
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Condvar, Mutex};
+    use std::time::Duration;
+    
+    use super::Transport;
+    use crate::error::{CoreBaseError, CoreBaseResult};
+    use crate::network::{NetworkConfig, NetworkManager};
+    
+    /// Transport that echoes every message back to its sender
+    ///
+    /// Each connection gets its own ID and queue.
+    #[derive(Debug, Default)]
+    pub(crate) struct EchoTransport {
+        connects: AtomicU64,
+        queues: Mutex<HashMap<String, VecDeque<Vec<u8>>>>,
+        changed: Condvar,
+    }
+    
+    impl Transport for EchoTransport {
+        fn connect(&self, config: &NetworkConfig) -> CoreBaseResult<String> {
+            let number = self.connects.fetch_add(1, Ordering::SeqCst) + 1;
+            let id = format!("{}:{}-{}", config.host, config.port, number);
+            self.queues.lock().unwrap().insert(id.clone(), VecDeque::new());
+            Ok(id)
+        }
+        
+        fn send(&self, connection_id: &str, data: &[u8]) -> CoreBaseResult<()> {
+            let mut queues = self.queues.lock().unwrap();
+            let queue = queues.get_mut(connection_id)
+                .ok_or_else(|| CoreBaseError::NetworkError("closed".to_string(), None))?;
+            queue.push_back(data.to_vec());
+            self.changed.notify_all();
+            Ok(())
+        }
+        
+        fn receive(&self, connection_id: &str, timeout: Option<Duration>) -> CoreBaseResult<Option<Vec<u8>>> {
+            let wait = timeout.unwrap_or(Duration::MAX);
+            let queues = self.queues.lock().unwrap();
+            let (mut queues, _) = self.changed
+                .wait_timeout_while(queues, wait, |queues| {
+                    queues.get(connection_id).is_some_and(VecDeque::is_empty)
+                })
+                .unwrap();
+            let queue = queues.get_mut(connection_id)
+                .ok_or_else(|| CoreBaseError::NetworkError("closed".to_string(), None))?;
+            Ok(queue.pop_front())
+        }
+        
+        fn close(&self, connection_id: &str) -> CoreBaseResult<()> {
+            self.queues.lock().unwrap().remove(connection_id);
+            self.changed.notify_all();
+            Ok(())
+        }
+    }
+    
+    /// Create a manager with an `EchoTransport` registered as "echo"
+    pub(crate) fn echo_manager() -> NetworkManager {
+        let manager = NetworkManager::new().unwrap();
+        manager.register_transport("echo", EchoTransport::default()).unwrap();
+        manager
+    }
+    
+    /// Configuration of a connection to the "echo" transport
+    pub(crate) fn echo_config() -> NetworkConfig {
+        NetworkConfig::custom("echo", "localhost", 8080)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::EchoTransport;
+    
+    use crate::network::{NetworkConfig, NetworkManager, NetworkMessage, NetworkProtocol};
+    
+    #[test]
+    fn test_custom_transport() {
+        let manager = NetworkManager::new().unwrap();
+        let config = NetworkConfig::custom("echo", "device", 7);
+        assert_eq!(config.protocol, NetworkProtocol::Custom);
+        assert!(manager.create_connection(config.clone()).is_err());
+        
+        manager.register_transport("echo", EchoTransport::default()).unwrap();
+        assert!(manager.register_transport("echo", EchoTransport::default()).is_err());
+        
+        let connection = manager.create_connection(config).unwrap();
+        assert_eq!(connection.id, "echo/device:7-1");
+        assert!(connection.try_receive().unwrap().is_none());
+        
+        connection.send(&NetworkMessage::new_text("hello")).unwrap();
+        assert_eq!(connection.receive().unwrap().as_text().unwrap(), "hello");
+        assert!(connection.set_nodelay(true).is_err());
+        
+        manager.close_connection(&connection.id).unwrap();
+        assert!(connection.send(&NetworkMessage::new_text("late")).is_err());
+        assert!(manager.unregister_transport("echo"));
+    }
+}
@@ -0,0 +1,56 @@
+//! Small helpers shared by CoreBase Rust bindings modules
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::os::raw::c_int;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{CoreBaseError, CoreBaseResult};
+
+/// Get a random duration up to `max`, differing between processes
+pub(crate) fn random_delay(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    // Hasher keys are seeded randomly for every process
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    max.mul_f64(hasher.finish() as f64 / u64::MAX as f64)
+}
+
+/// Get a path in `dir` that no other call, in this process or another, returns
+pub(crate) fn unique_path(dir: &Path, prefix: &str, suffix: &str) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    dir.join(format!("{}-{}-{}{}", prefix, process::id(), NEXT.fetch_add(1, Ordering::Relaxed), suffix))
+}
+
+/// Check a length reported by the C++ side
+pub(crate) fn native_len(len: c_int) -> CoreBaseResult<usize> {
+    usize::try_from(len).map_err(|_| CoreBaseError::InvalidData(format!("Invalid length from C++: {}", len), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_native_len() {
+        assert_eq!(native_len(0).unwrap(), 0);
+        assert_eq!(native_len(4096).unwrap(), 4096);
+        assert!(matches!(native_len(-1), Err(CoreBaseError::InvalidData(..))));
+    }
+    
+    #[test]
+    fn test_unique_path() {
+        let dir = Path::new("/var/lib/app");
+        let first = unique_path(dir, ".history", ".tmp");
+        let second = unique_path(dir, ".history", ".tmp");
+        assert_ne!(first, second);
+        assert_eq!(first.parent(), Some(dir));
+        let name = first.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with(".history-") && name.ends_with(".tmp"));
+    }
+}
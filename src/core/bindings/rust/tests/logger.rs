@@ -0,0 +1,13 @@
+//! Global logger installation
+//!
+//! The `log` crate accepts one global logger per process, so this runs in
+//! its own test binary where nothing else installs one.
+
+use corebase_bindings::logger::init_logger;
+
+#[test]
+fn test_init_logger() {
+    assert!(init_logger().is_ok());
+    assert!(init_logger().is_err());
+    log::info!("forwarded with a NUL \0 inside");
+}